@@ -0,0 +1,204 @@
+//! Traits over the clients, to write code generic over "something that renders documents".
+//!
+//! `CarboneApi` is implemented by `carbone::Carbone` and, with the `testing` feature,
+//! `testing::mock::MockCarbone`; `CarboneApiBlocking` by `blocking::Carbone`. Both are
+//! object safe, so wrappers adding caching, retries or logging can hold a
+//! `Box<dyn CarboneApi>` or an `Arc<dyn CarboneApi>` and be composed.
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::StatusCode;
+use std::sync::Arc;
+
+use crate::carbone_response::TemplateList;
+use crate::errors::CarboneError;
+use crate::render::RenderId;
+use crate::status::Status;
+use crate::template::{TemplateId, TemplateListQuery};
+#[cfg(feature = "blocking")]
+use crate::template::TemplateSource;
+use crate::types::{JsonData, Result};
+
+/// Operations of the asynchronous client.
+///
+/// Implementors provide the API calls; `generate_report` and
+/// `generate_report_with_template_id` have default implementations built on them.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::api::CarboneApi;
+/// use carbone_sdk_rust::errors::CarboneError;
+/// use carbone_sdk_rust::types::JsonData;
+///
+/// async fn invoice<C: CarboneApi>(carbone: &C, template: Vec<u8>) -> Result<Vec<u8>, CarboneError> {
+///     let json_data = JsonData::new(r#"{ "data": { "id": 42 }, "convertTo": "pdf" }"#.to_string())?;
+///     let report = carbone
+///         .generate_report("invoice.docx".to_string(), template, json_data, None)
+///         .await?;
+///
+///     Ok(report.to_vec())
+/// }
+/// ```
+#[async_trait]
+pub trait CarboneApi: Send + Sync {
+    async fn upload_template(
+        &self,
+        file_name: &str,
+        file_content: Vec<u8>,
+        payload: Option<&str>,
+    ) -> Result<TemplateId>;
+
+    async fn render_data(&self, template_id: TemplateId, json_data: JsonData) -> Result<RenderId>;
+
+    async fn get_report(&self, render_id: &RenderId) -> Result<Bytes>;
+
+    async fn download_template(&self, template_id: &TemplateId) -> Result<Bytes>;
+
+    async fn delete_template(&self, template_id: TemplateId) -> Result<bool>;
+
+    async fn get_status(&self) -> Result<Status>;
+
+    /// List the stored templates, used by the workflows comparing local templates with them.
+    async fn list_templates(&self, query: &TemplateListQuery) -> Result<TemplateList>;
+
+    /// Render the template, uploading it first if the API answers `404` for its ID.
+    async fn generate_report(
+        &self,
+        template_name: String,
+        template_data: Vec<u8>,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        let template_id = TemplateId::from_bytes(template_data.clone(), payload)?;
+
+        let render_id = match self.render_data(template_id, json_data.clone()).await {
+            Ok(render_id) => render_id,
+            Err(CarboneError::HttpError { status_code, .. }) if status_code == StatusCode::NOT_FOUND => {
+                let template_id = self
+                    .upload_template(template_name.as_str(), template_data, payload)
+                    .await?;
+                self.render_data(template_id, json_data).await?
+            }
+            Err(e) => return Err(e),
+        };
+
+        self.get_report(&render_id).await
+    }
+
+    async fn generate_report_with_template_id(
+        &self,
+        template_id: TemplateId,
+        json_data: JsonData,
+    ) -> Result<Bytes> {
+        let render_id = self.render_data(template_id, json_data).await?;
+        self.get_report(&render_id).await
+    }
+}
+
+/// Operations of the blocking client.
+///
+/// Requires the `blocking` feature.
+#[cfg(feature = "blocking")]
+pub trait CarboneApiBlocking: Send + Sync {
+    fn upload_template(&self, template: TemplateSource, payload: Option<&str>) -> Result<TemplateId>;
+
+    fn render_data(&self, template_id: TemplateId, json_data: JsonData) -> Result<RenderId>;
+
+    fn get_report(&self, render_id: &RenderId) -> Result<Bytes>;
+
+    fn download_template(&self, template_id: &TemplateId) -> Result<Bytes>;
+
+    fn delete_template(&self, template_id: TemplateId) -> Result<bool>;
+
+    fn get_status(&self) -> Result<Status>;
+
+    fn list_templates(&self, query: &TemplateListQuery) -> Result<TemplateList>;
+
+    /// Render the template, uploading it first if the API does not know it.
+    fn generate_report_with_file(
+        &self,
+        template: TemplateSource,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        let template_file = template.into_template_file()?;
+        let template_id = template_file.generate_id(payload)?;
+
+        let render_id = match self.render_data(template_id, json_data.clone()) {
+            Ok(render_id) => render_id,
+            Err(CarboneError::HttpError { status_code, .. }) if status_code == StatusCode::NOT_FOUND => {
+                let template = TemplateSource::from_bytes(template_file.file_name(), template_file.content_bytes()?);
+                let template_id = self.upload_template(template, payload)?;
+                self.render_data(template_id, json_data)?
+            }
+            Err(e) => return Err(e),
+        };
+
+        self.get_report(&render_id)
+    }
+
+    fn generate_report_with_template_id(&self, template_id: TemplateId, json_data: JsonData) -> Result<Bytes> {
+        let render_id = self.render_data(template_id, json_data)?;
+        self.get_report(&render_id)
+    }
+}
+
+/// Clients shared between services.
+#[async_trait]
+impl<T: CarboneApi + ?Sized> CarboneApi for Arc<T> {
+    async fn upload_template(
+        &self,
+        file_name: &str,
+        file_content: Vec<u8>,
+        payload: Option<&str>,
+    ) -> Result<TemplateId> {
+        (**self).upload_template(file_name, file_content, payload).await
+    }
+
+    async fn render_data(&self, template_id: TemplateId, json_data: JsonData) -> Result<RenderId> {
+        (**self).render_data(template_id, json_data).await
+    }
+
+    async fn get_report(&self, render_id: &RenderId) -> Result<Bytes> {
+        (**self).get_report(render_id).await
+    }
+
+    async fn download_template(&self, template_id: &TemplateId) -> Result<Bytes> {
+        (**self).download_template(template_id).await
+    }
+
+    async fn delete_template(&self, template_id: TemplateId) -> Result<bool> {
+        (**self).delete_template(template_id).await
+    }
+
+    async fn get_status(&self) -> Result<Status> {
+        (**self).get_status().await
+    }
+
+    async fn list_templates(&self, query: &TemplateListQuery) -> Result<TemplateList> {
+        (**self).list_templates(query).await
+    }
+
+    async fn generate_report(
+        &self,
+        template_name: String,
+        template_data: Vec<u8>,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        (**self)
+            .generate_report(template_name, template_data, json_data, payload)
+            .await
+    }
+
+    async fn generate_report_with_template_id(
+        &self,
+        template_id: TemplateId,
+        json_data: JsonData,
+    ) -> Result<Bytes> {
+        (**self)
+            .generate_report_with_template_id(template_id, json_data)
+            .await
+    }
+}
@@ -0,0 +1,547 @@
+//! Batch rendering: one report per row of a CSV, NDJSON or JSON-array dataset.
+//!
+//! `render_batch` works with any `CarboneApi`, and `render_batch_blocking` with any
+//! `CarboneApiBlocking`.
+//!
+//! Requires the `batch` feature.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "blocking")]
+use std::sync::Mutex;
+#[cfg(feature = "blocking")]
+use std::thread;
+
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::api::CarboneApi;
+#[cfg(feature = "blocking")]
+use crate::api::CarboneApiBlocking;
+use crate::errors::CarboneError;
+use crate::render::write_report;
+use crate::template::{TemplateId, TemplateSource};
+use crate::types::{JsonData, Result};
+
+/// Number of renders running at the same time by default.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetFormat {
+    /// A header line, then one row per line. Every value is a string.
+    Csv,
+    /// One JSON object per line.
+    Ndjson,
+    /// A JSON array of objects.
+    JsonArray,
+}
+
+impl DatasetFormat {
+    /// Format matching the extension of a file: `csv`, `ndjson` or `jsonl`, and `json`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "json" => Some(Self::JsonArray),
+            _ => None,
+        }
+    }
+}
+
+/// The rows of a dataset, each one rendered as the `data` of a report.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::batch::Dataset;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let dataset = Dataset::from_path("customers.csv")?;
+///
+///     println!("{} rows", dataset.len());
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dataset {
+    pub rows: Vec<Value>,
+}
+
+impl Dataset {
+    /// Load a dataset, with the format given by the extension of the file.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let name = path.to_string_lossy().into_owned();
+
+        let format = DatasetFormat::from_path(path).ok_or_else(|| {
+            CarboneError::ParseError(name.clone(), "unknown dataset format".to_string())
+        })?;
+        let content =
+            fs::read_to_string(path).map_err(|_| CarboneError::FileNotFound(name.clone()))?;
+
+        Self::parse(&content, format).map_err(|e| match e {
+            CarboneError::ParseError(_, message) => CarboneError::ParseError(name, message),
+            e => e,
+        })
+    }
+
+    pub fn parse(content: &str, format: DatasetFormat) -> Result<Self> {
+        match format {
+            DatasetFormat::Csv => Self::from_csv(content),
+            DatasetFormat::Ndjson => Self::from_ndjson(content),
+            DatasetFormat::JsonArray => Self::from_json_array(content),
+        }
+    }
+
+    pub fn from_csv(content: &str) -> Result<Self> {
+        let mut reader = csv::Reader::from_reader(content.as_bytes());
+
+        let headers = reader.headers().map_err(csv_error)?.clone();
+
+        let rows = reader
+            .records()
+            .map(|record| {
+                let record = record.map_err(csv_error)?;
+                let row: Map<String, Value> = headers
+                    .iter()
+                    .zip(record.iter())
+                    .map(|(header, value)| (header.to_string(), Value::String(value.to_string())))
+                    .collect();
+                Ok(Value::Object(row))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { rows })
+    }
+
+    pub fn from_ndjson(content: &str) -> Result<Self> {
+        let rows = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    CarboneError::ParseError("dataset".to_string(), format!("line {}: {}", index + 1, e))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { rows })
+    }
+
+    pub fn from_json_array(content: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(content)
+            .map_err(|e| CarboneError::ParseError("dataset".to_string(), e.to_string()))?;
+
+        match value {
+            Value::Array(rows) => Ok(Self { rows }),
+            _ => Err(CarboneError::ParseError(
+                "dataset".to_string(),
+                "expected a JSON array".to_string(),
+            )),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+fn csv_error(e: csv::Error) -> CarboneError {
+    CarboneError::ParseError("dataset".to_string(), e.to_string())
+}
+
+/// File name of the reports, with `{key}` placeholders replaced by the values of the row,
+/// e.g. `{id}-{lastname}.pdf`. Nested values are read with dotted keys like `{customer.id}`,
+/// and `{#}` is the row number, starting at 1. The reports are written in the output
+/// directory itself: the pattern cannot contain path separators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputName(String);
+
+impl OutputName {
+    pub fn new<T: Into<String>>(pattern: T) -> Result<Self> {
+        let pattern = pattern.into();
+        if pattern.is_empty() {
+            return Err(CarboneError::EmptyString("output_name".to_string()));
+        }
+        if pattern.contains(['/', '\\']) {
+            return Err(CarboneError::Error(format!(
+                "output name {} contains a path separator",
+                pattern
+            )));
+        }
+        Ok(Self(pattern))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// File name of the report of a row. Characters not allowed in file names are
+    /// replaced by `_` in the values, and a name which is empty, `.` or `..` is an error.
+    pub fn format(&self, row: &Value, row_number: usize) -> Result<String> {
+        let mut name = String::new();
+        let mut rest = self.0.as_str();
+
+        while let Some(start) = rest.find('{') {
+            name.push_str(&rest[..start]);
+            let end = rest[start..]
+                .find('}')
+                .map(|end| start + end)
+                .ok_or_else(|| CarboneError::Error(format!("unclosed placeholder in {}", self.0)))?;
+
+            let key = &rest[start + 1..end];
+            let value = match key {
+                "#" => row_number.to_string(),
+                _ => placeholder_value(row, key)
+                    .ok_or_else(|| CarboneError::Error(format!("no value for {{{}}}", key)))?,
+            };
+            name.extend(value.chars().map(|c| {
+                if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+                    '_'
+                } else {
+                    c
+                }
+            }));
+
+            rest = &rest[end + 1..];
+        }
+        name.push_str(rest);
+
+        if matches!(name.trim(), "" | "." | "..") {
+            return Err(CarboneError::Error(format!("invalid output name \"{}\"", name)));
+        }
+
+        Ok(name)
+    }
+}
+
+fn placeholder_value(row: &Value, key: &str) -> Option<String> {
+    let value = key
+        .split('.')
+        .try_fold(row, |value, field| value.get(field))?;
+
+    match value {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Options of `render_batch`.
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    pub output_dir: PathBuf,
+    pub output_name: OutputName,
+    /// Number of renders running at the same time.
+    pub concurrency: usize,
+    /// Render options sent with every row, e.g. `convertTo` or `lang`. The `data` is the row.
+    pub render_options: Map<String, Value>,
+    /// Payload used to compute the template ID and sent with the upload.
+    pub payload: Option<String>,
+    /// Render the rows whose report already exists. By default they are skipped, so an
+    /// interrupted batch resumes where it stopped.
+    pub overwrite: bool,
+}
+
+impl BatchOptions {
+    pub fn new<P: Into<PathBuf>>(output_dir: P, output_name: OutputName) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            output_name,
+            concurrency: DEFAULT_CONCURRENCY,
+            render_options: Map::new(),
+            payload: None,
+            overwrite: false,
+        }
+    }
+}
+
+/// A row and its report.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEntry {
+    /// Row number in the dataset, starting at 1.
+    pub row: usize,
+    pub output: PathBuf,
+}
+
+/// A row which could not be rendered.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchFailure {
+    pub row: usize,
+    /// `None` when the output name could not be built from the row.
+    pub output: Option<PathBuf>,
+    pub error: String,
+}
+
+/// Outcome of `render_batch`, sorted by row.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReport {
+    pub template_id: TemplateId,
+    pub rendered: Vec<BatchEntry>,
+    /// Rows whose report already existed.
+    pub skipped: Vec<BatchEntry>,
+    pub failures: Vec<BatchFailure>,
+}
+
+impl BatchReport {
+    pub fn has_failures(&self) -> bool {
+        !self.failures.is_empty()
+    }
+}
+
+/// Render a template once per row of a dataset, with up to `options.concurrency`
+/// renders at the same time, and write the reports to `options.output_dir`.
+///
+/// The template is uploaded once. A failed row does not stop the batch: it is listed
+/// in the failures of the report. The rows whose report already exists are skipped
+/// unless `options.overwrite` is set.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::carbone::Carbone;
+/// use carbone_sdk_rust::batch::{render_batch, BatchOptions, Dataset, OutputName};
+/// use carbone_sdk_rust::template::TemplateFile;
+/// use carbone_sdk_rust::types::ApiJsonToken;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), CarboneError> {
+///
+///     let config: Config = Default::default();
+///     let api_token = ApiJsonToken::new(std::env::var("CARBONE_TOKEN").unwrap())?;
+///
+///     let carbone = Carbone::new(&config, Some(&api_token))?;
+///
+///     let dataset = Dataset::from_path("customers.csv")?;
+///     let mut options = BatchOptions::new("letters", OutputName::new("{id}-{lastname}.pdf")?);
+///     options.render_options.insert("convertTo".to_string(), "pdf".into());
+///
+///     let template_file = TemplateFile::new("letter.docx".to_string(), None)?;
+///     let report = render_batch(&carbone, &template_file, &dataset, &options).await?;
+///
+///     for failure in report.failures.iter() {
+///         println!("row {}: {}", failure.row, failure.error);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub async fn render_batch<C, T>(
+    carbone: &C,
+    template: T,
+    dataset: &Dataset,
+    options: &BatchOptions,
+) -> Result<BatchReport>
+where
+    C: CarboneApi + ?Sized,
+    T: Into<TemplateSource>,
+{
+    let template_file = template.into().into_template_file()?;
+    let payload = options.payload.as_deref();
+    let mut plan = BatchPlan::new(dataset, options)?;
+
+    if plan.jobs.is_empty() {
+        let template_id = template_file.generate_id(payload)?;
+        return Ok(plan.finish(template_id, Vec::new()));
+    }
+
+    fs::create_dir_all(&options.output_dir)?;
+    let template_id = carbone
+        .upload_template(template_file.file_name(), template_file.content_bytes()?, payload)
+        .await?;
+
+    let template_id_ref = &template_id;
+    let results = stream::iter(std::mem::take(&mut plan.jobs))
+        .map(|job| async move {
+            let result = render_job(carbone, template_id_ref, &job).await;
+            (job, result)
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    Ok(plan.finish(template_id, results))
+}
+
+async fn render_job<C: CarboneApi + ?Sized>(carbone: &C, template_id: &TemplateId, job: &BatchJob) -> Result<()> {
+    let render_id = carbone
+        .render_data(template_id.clone(), job.json_data.clone())
+        .await?;
+    let content = carbone.get_report(&render_id).await?;
+    write_report(&job.output, &content)
+}
+
+/// Blocking version of [`render_batch`], rendering the rows on `options.concurrency` threads.
+///
+/// Requires the `blocking` feature.
+#[cfg(feature = "blocking")]
+pub fn render_batch_blocking<C, T>(
+    carbone: &C,
+    template: T,
+    dataset: &Dataset,
+    options: &BatchOptions,
+) -> Result<BatchReport>
+where
+    C: CarboneApiBlocking + ?Sized,
+    T: Into<TemplateSource>,
+{
+    let payload = options.payload.as_deref();
+    let template_file = template.into().into_template_file()?;
+    let mut plan = BatchPlan::new(dataset, options)?;
+
+    if plan.jobs.is_empty() {
+        let template_id = template_file.generate_id(payload)?;
+        return Ok(plan.finish(template_id, Vec::new()));
+    }
+
+    fs::create_dir_all(&options.output_dir)?;
+    let template_id = carbone.upload_template(template_file.into(), payload)?;
+
+    let workers = options.concurrency.clamp(1, plan.jobs.len());
+    let jobs = Mutex::new(std::mem::take(&mut plan.jobs).into_iter());
+    let results = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let Some(job) = jobs.lock().unwrap().next() else {
+                    break;
+                };
+                let result = render_job_blocking(carbone, &template_id, &job);
+                results.lock().unwrap().push((job, result));
+            });
+        }
+    });
+
+    Ok(plan.finish(template_id, results.into_inner().unwrap()))
+}
+
+#[cfg(feature = "blocking")]
+fn render_job_blocking<C: CarboneApiBlocking + ?Sized>(carbone: &C, template_id: &TemplateId, job: &BatchJob) -> Result<()> {
+    let render_id = carbone.render_data(template_id.clone(), job.json_data.clone())?;
+    let content = carbone.get_report(&render_id)?;
+    write_report(&job.output, &content)
+}
+
+#[derive(Debug)]
+struct BatchJob {
+    pub row: usize,
+    pub output: PathBuf,
+    pub json_data: JsonData,
+}
+
+#[derive(Debug)]
+struct BatchPlan {
+    pub jobs: Vec<BatchJob>,
+    pub skipped: Vec<BatchEntry>,
+    pub failures: Vec<BatchFailure>,
+}
+
+impl BatchPlan {
+    /// Name the report of every row, and split the rows between the ones to render,
+    /// the ones already rendered and the ones without a valid or unique name.
+    pub fn new(dataset: &Dataset, options: &BatchOptions) -> Result<Self> {
+        if options.render_options.contains_key("data") {
+            return Err(CarboneError::Error(
+                "render_options can not contain data".to_string(),
+            ));
+        }
+
+        let mut plan = Self {
+            jobs: Vec::new(),
+            skipped: Vec::new(),
+            failures: Vec::new(),
+        };
+
+        let mut named_rows: HashMap<PathBuf, usize> = HashMap::new();
+
+        for (index, data) in dataset.rows.iter().enumerate() {
+            let row = index + 1;
+
+            let output = match options.output_name.format(data, row) {
+                Ok(name) => options.output_dir.join(name),
+                Err(e) => {
+                    plan.failures.push(BatchFailure {
+                        row,
+                        output: None,
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            if let Some(first_row) = named_rows.get(&output) {
+                plan.failures.push(BatchFailure {
+                    row,
+                    error: format!("duplicate output name, same as row {}", first_row),
+                    output: Some(output),
+                });
+                continue;
+            }
+            named_rows.insert(output.clone(), row);
+
+            if !options.overwrite && output.exists() {
+                plan.skipped.push(BatchEntry { row, output });
+                continue;
+            }
+
+            let mut render_options = options.render_options.clone();
+            render_options.insert("data".to_string(), data.clone());
+            let json_data = JsonData::new(Value::Object(render_options).to_string())?;
+
+            plan.jobs.push(BatchJob {
+                row,
+                output,
+                json_data,
+            });
+        }
+
+        Ok(plan)
+    }
+
+    /// Build the report from the outcome of the jobs.
+    pub fn finish(self, template_id: TemplateId, results: Vec<(BatchJob, Result<()>)>) -> BatchReport {
+        let mut report = BatchReport {
+            template_id,
+            rendered: Vec::new(),
+            skipped: self.skipped,
+            failures: self.failures,
+        };
+
+        for (job, result) in results {
+            match result {
+                Ok(()) => report.rendered.push(BatchEntry {
+                    row: job.row,
+                    output: job.output,
+                }),
+                Err(e) => report.failures.push(BatchFailure {
+                    row: job.row,
+                    output: Some(job.output),
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        report.rendered.sort_by_key(|entry| entry.row);
+        report.failures.sort_by_key(|failure| failure.row);
+
+        report
+    }
+}
@@ -0,0 +1,153 @@
+//! `carbone-batch`: render a template once per row of a CSV, NDJSON or JSON-array file.
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use serde_json::{Map, Value};
+
+use carbone_sdk_rust::batch::{render_batch_blocking, BatchOptions, BatchReport, Dataset, DatasetFormat, OutputName, DEFAULT_CONCURRENCY};
+use carbone_sdk_rust::blocking::Carbone;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::template::TemplateFile;
+use carbone_sdk_rust::types::ApiJsonToken;
+
+use common::ConnectionArgs;
+
+/// Exit code when some rows could not be rendered.
+const EXIT_ROW_FAILURES: u8 = 8;
+
+#[derive(Debug, Parser)]
+#[command(name = "carbone-batch", version, about = "Render a template once per row of a dataset")]
+struct Cli {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+
+    template: PathBuf,
+
+    /// CSV, NDJSON (`.ndjson`, `.jsonl`) or JSON-array file. Each row is the `data` of a report.
+    dataset: PathBuf,
+
+    /// Format of the dataset, found from its extension by default.
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+
+    /// Folder of the reports.
+    #[arg(short, long, default_value = ".")]
+    output_dir: PathBuf,
+
+    /// File name of the reports, e.g. `{id}-{lastname}.pdf`. `{#}` is the row number.
+    #[arg(short, long)]
+    name: String,
+
+    /// Number of renders running at the same time.
+    #[arg(short, long, default_value_t = DEFAULT_CONCURRENCY)]
+    concurrency: usize,
+
+    #[arg(long)]
+    convert_to: Option<String>,
+
+    /// JSON object of render options sent with every row, e.g. `{"lang": "fr-fr"}`.
+    #[arg(long)]
+    render_options: Option<String>,
+
+    #[arg(long)]
+    payload: Option<String>,
+
+    /// Render the rows whose report already exists instead of skipping them.
+    #[arg(long)]
+    overwrite: bool,
+
+    /// Write the summary report as JSON to this file.
+    #[arg(long)]
+    report: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    Ndjson,
+    Json,
+}
+
+impl From<Format> for DatasetFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Csv => DatasetFormat::Csv,
+            Format::Ndjson => DatasetFormat::Ndjson,
+            Format::Json => DatasetFormat::JsonArray,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    cli.connection.run(|api_token| {
+        let report = run(&cli, api_token)?;
+
+        if report.has_failures() {
+            Ok(ExitCode::from(EXIT_ROW_FAILURES))
+        } else {
+            Ok(ExitCode::SUCCESS)
+        }
+    })
+}
+
+fn run(cli: &Cli, api_token: &ApiJsonToken) -> Result<BatchReport, CarboneError> {
+    let config = cli.connection.config()?;
+    let carbone = Carbone::new(&config, api_token)?;
+
+    let dataset = match cli.format {
+        Some(format) => {
+            let content = fs::read_to_string(&cli.dataset)
+                .map_err(|_| CarboneError::FileNotFound(cli.dataset.to_string_lossy().into_owned()))?;
+            Dataset::parse(&content, format.into())?
+        }
+        None => Dataset::from_path(&cli.dataset)?,
+    };
+
+    let mut options = BatchOptions::new(&cli.output_dir, OutputName::new(cli.name.clone())?);
+    options.concurrency = cli.concurrency;
+    options.payload = cli.payload.clone();
+    options.overwrite = cli.overwrite;
+    if let Some(render_options) = &cli.render_options {
+        options.render_options = serde_json::from_str::<Map<String, Value>>(render_options)
+            .map_err(|e| CarboneError::ParseError("render_options".to_string(), e.to_string()))?;
+    }
+    if let Some(convert_to) = &cli.convert_to {
+        options
+            .render_options
+            .insert("convertTo".to_string(), Value::String(convert_to.clone()));
+    }
+
+    let template_file = TemplateFile::new(cli.template.to_string_lossy().into_owned(), None)?;
+    let report = render_batch_blocking(&carbone, &template_file, &dataset, &options)?;
+
+    if let Some(path) = &cli.report {
+        let content =
+            serde_json::to_string_pretty(&report).map_err(|e| CarboneError::Error(e.to_string()))?;
+        fs::write(path, content)?;
+    }
+
+    cli.connection.print(&report, || {
+        let mut lines = vec![format!(
+            "{} rendered, {} skipped, {} failed",
+            report.rendered.len(),
+            report.skipped.len(),
+            report.failures.len()
+        )];
+        lines.extend(
+            report
+                .failures
+                .iter()
+                .map(|failure| format!("row {}: {}", failure.row, failure.error)),
+        );
+        lines.join("\n")
+    })?;
+
+    Ok(report)
+}
@@ -0,0 +1,103 @@
+//! `carbone-gateway`: HTTP gateway rendering the templates of a folder for internal services.
+
+mod common;
+
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::Parser;
+use serde_json::json;
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::config::Config;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::server::cache::CacheOptions;
+use carbone_sdk_rust::server::{Gateway, GatewayOptions};
+use carbone_sdk_rust::store::FsTemplateStore;
+use carbone_sdk_rust::types::ApiJsonToken;
+
+use common::ConnectionArgs;
+
+#[derive(Debug, Parser)]
+#[command(name = "carbone-gateway", version, about = "Render the templates of a folder over HTTP")]
+struct Cli {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+
+    /// Folder of the templates, rendered by their path relative to it.
+    #[arg(long, env = "CARBONE_GATEWAY_TEMPLATES")]
+    templates: PathBuf,
+
+    #[arg(long, env = "CARBONE_GATEWAY_LISTEN", default_value = "127.0.0.1:3000")]
+    listen: String,
+
+    /// Keys accepted from the callers, in `Authorization: Bearer <key>`. Comma-separated
+    /// in the environment variable.
+    #[arg(long = "api-key", env = "CARBONE_GATEWAY_API_KEYS", value_delimiter = ',', hide_env_values = true)]
+    api_keys: Vec<String>,
+
+    /// Maximum number of cached reports, `0` to disable the cache.
+    #[arg(long)]
+    cache_entries: Option<usize>,
+
+    /// Maximum total size of the cached reports, in bytes.
+    #[arg(long)]
+    cache_bytes: Option<usize>,
+
+    /// Lifetime of the cached reports, in seconds.
+    #[arg(long)]
+    cache_ttl: Option<u64>,
+
+    #[arg(long)]
+    payload: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    cli.connection.run(|api_token| {
+        run(&cli, api_token.clone())?;
+        Ok(ExitCode::SUCCESS)
+    })
+}
+
+fn run(cli: &Cli, api_token: ApiJsonToken) -> Result<(), CarboneError> {
+    // The gateway serves until the process ends.
+    let config: &'static Config = Box::leak(Box::new(cli.connection.config()?));
+    let api_token: &'static ApiJsonToken = Box::leak(Box::new(api_token));
+
+    let carbone = Carbone::new(config, Some(api_token))?;
+
+    let defaults = CacheOptions::default();
+    let options = GatewayOptions {
+        api_keys: cli.api_keys.clone(),
+        cache: CacheOptions {
+            max_entries: cli.cache_entries.unwrap_or(defaults.max_entries),
+            max_bytes: cli.cache_bytes.unwrap_or(defaults.max_bytes),
+            ttl: cli.cache_ttl.map(Duration::from_secs).or(defaults.ttl),
+        },
+        payload: cli.payload.clone(),
+        ..Default::default()
+    };
+
+    if options.api_keys.is_empty() {
+        eprintln!("warning: no API key, the render API is open to every caller");
+    }
+
+    let store = Arc::new(FsTemplateStore::new(&cli.templates));
+    let gateway = Gateway::new(carbone, store, options);
+
+    let listener = TcpListener::bind(&cli.listen)?;
+    let addr = listener.local_addr()?;
+    cli.connection.print(&json!({ "listening": addr.to_string() }), || {
+        format!("listening on http://{}", addr)
+    })?;
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(gateway.serve(listener, async {
+        let _ = tokio::signal::ctrl_c().await;
+    }))
+}
@@ -0,0 +1,106 @@
+//! `carbone-watch`: render a template again each time the template or its data change.
+
+mod common;
+
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::Parser;
+use serde_json::json;
+
+use carbone_sdk_rust::blocking::Carbone;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::types::ApiJsonToken;
+use carbone_sdk_rust::watch::{watch_blocking, PreviewServer, WatchEvent, WatchOptions, DEFAULT_WATCH_INTERVAL};
+
+use common::ConnectionArgs;
+
+#[derive(Debug, Parser)]
+#[command(name = "carbone-watch", version, about = "Render a template again on every change")]
+struct Cli {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+
+    template: PathBuf,
+
+    /// JSON file of the render options (`data`, `convertTo`, ...).
+    data: PathBuf,
+
+    /// Report file, `<template>.preview.<convertTo>` next to the template by default.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    #[arg(long)]
+    payload: Option<String>,
+
+    /// Interval between two checks of the files, in milliseconds.
+    #[arg(long, default_value_t = DEFAULT_WATCH_INTERVAL.as_millis() as u64)]
+    interval: u64,
+
+    /// Serve the last report on a page reloading after every render.
+    #[arg(long, value_name = "ADDR", num_args = 0..=1, default_missing_value = "127.0.0.1:8080")]
+    serve: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    cli.connection.run(|api_token| {
+        run(&cli, api_token)?;
+        Ok(ExitCode::SUCCESS)
+    })
+}
+
+fn run(cli: &Cli, api_token: &ApiJsonToken) -> Result<(), CarboneError> {
+    let config = cli.connection.config()?;
+    let carbone = Carbone::new(&config, api_token)?;
+
+    let options = WatchOptions {
+        output: cli.output.clone(),
+        payload: cli.payload.clone(),
+        interval: Duration::from_millis(cli.interval),
+    };
+
+    let preview = match &cli.serve {
+        Some(addr) => {
+            let preview = PreviewServer::start(addr.as_str())?;
+            eprintln!("preview on {}", preview.url());
+            Some(preview)
+        }
+        None => None,
+    };
+
+    watch_blocking(&carbone, &cli.template, &cli.data, &options, |event| {
+        if let Some(preview) = &preview {
+            preview.update(event);
+        }
+
+        let printed = match event {
+            WatchEvent::Rendered {
+                template_id,
+                output,
+                elapsed,
+                ..
+            } => cli.connection.print(
+                &json!({
+                    "success": true,
+                    "templateId": template_id.as_str(),
+                    "output": output,
+                    "elapsedMs": elapsed.as_millis() as u64,
+                }),
+                || format!("rendered {} in {} ms ({})", output.display(), elapsed.as_millis(), template_id.as_str()),
+            ),
+            WatchEvent::Failed(e) => cli.connection.print(
+                &json!({ "success": false, "error": e.to_string() }),
+                || format!("error: {}", e),
+            ),
+        };
+
+        match printed {
+            Ok(()) => ControlFlow::Continue(()),
+            Err(_) => ControlFlow::Break(()),
+        }
+    })
+}
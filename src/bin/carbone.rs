@@ -0,0 +1,238 @@
+//! `carbone`: command-line tool on top of the blocking client.
+
+mod common;
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand};
+use serde_json::json;
+
+use carbone_sdk_rust::blocking::Carbone;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::sync::{sync_templates_blocking, SyncOptions};
+use carbone_sdk_rust::template::{TemplateFile, TemplateId, TemplateListQuery};
+use carbone_sdk_rust::types::{ApiJsonToken, JsonData};
+
+use common::ConnectionArgs;
+
+#[derive(Debug, Parser)]
+#[command(name = "carbone", version, about = "Generate documents with the Carbone API")]
+struct Cli {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print the status and the version of the API.
+    Status {
+        /// Wait until the API is ready, up to this number of seconds.
+        #[arg(long)]
+        wait: Option<u64>,
+    },
+    /// Upload a template and print its template ID.
+    Upload {
+        template: PathBuf,
+        #[arg(long)]
+        payload: Option<String>,
+    },
+    /// Download a template.
+    Download {
+        template_id: String,
+        /// Output file, the standard output by default.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Delete a template.
+    Delete { template_id: String },
+    /// Render a template with the render options of a JSON file.
+    Render {
+        /// Template file, or template ID with `--id`.
+        template: String,
+        /// JSON file of the render options (`data`, `convertTo`, ...).
+        data: PathBuf,
+        #[arg(short, long)]
+        output: PathBuf,
+        /// The template is a template ID of a stored template.
+        #[arg(long)]
+        id: bool,
+        #[arg(long, conflicts_with = "id")]
+        payload: Option<String>,
+    },
+    /// List the stored templates.
+    List {
+        #[arg(long)]
+        category: Option<String>,
+        #[arg(long)]
+        tag: Option<String>,
+        /// Search in the template names.
+        #[arg(long)]
+        search: Option<String>,
+        #[arg(long)]
+        limit: Option<u32>,
+        /// List every version of the templates.
+        #[arg(long)]
+        versions: bool,
+    },
+    /// Upload the new or changed templates of a folder.
+    Sync {
+        dir: PathBuf,
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+        /// Delete the templates of the manifest no longer matching a file.
+        #[arg(long, requires = "manifest")]
+        delete_orphans: bool,
+        #[arg(long)]
+        dry_run: bool,
+        #[arg(long)]
+        payload: Option<String>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    cli.connection.run(|api_token| {
+        run(&cli, api_token)?;
+        Ok(ExitCode::SUCCESS)
+    })
+}
+
+fn run(cli: &Cli, api_token: &ApiJsonToken) -> Result<(), CarboneError> {
+    let config = cli.connection.config()?;
+    let carbone = Carbone::new(&config, api_token)?;
+
+    match &cli.command {
+        Command::Status { wait } => {
+            let status = match wait {
+                Some(seconds) => carbone.wait_until_ready(Duration::from_secs(*seconds))?,
+                None => carbone.get_status()?,
+            };
+            cli.connection.print(&status, || {
+                format!(
+                    "success: {}\nversion: {}\nmessage: {}",
+                    status.success,
+                    status.version.as_deref().unwrap_or("-"),
+                    status.message.as_deref().unwrap_or("-")
+                )
+            })
+        }
+        Command::Upload { template, payload } => {
+            let template_file = TemplateFile::new(template.to_string_lossy().into_owned(), None)?;
+            let template_id = carbone.upload_template(&template_file, payload.as_deref())?;
+            cli.connection.print(&json!({ "templateId": template_id.as_str() }), || {
+                template_id.as_str().to_string()
+            })
+        }
+        Command::Download {
+            template_id,
+            output,
+        } => {
+            let content = carbone.download_template(&TemplateId::new(template_id)?)?;
+            write_output(output.as_ref(), &content)
+        }
+        Command::Delete { template_id } => {
+            carbone.delete_template(TemplateId::new(template_id)?)?;
+            cli.connection.print(&json!({ "success": true }), || {
+                format!("deleted {}", template_id)
+            })
+        }
+        Command::Render {
+            template,
+            data,
+            output,
+            id,
+            payload,
+        } => {
+            let render_options = fs::read_to_string(data)
+                .map_err(|_| CarboneError::FileNotFound(data.to_string_lossy().into_owned()))?;
+            let json_data = JsonData::new(render_options)?;
+
+            let content = if *id {
+                carbone.generate_report_with_template_id(TemplateId::new(template)?, json_data)?
+            } else {
+                let template_file = TemplateFile::new(template.clone(), None)?;
+                carbone.generate_report_with_file(&template_file, json_data, payload.as_deref())?
+            };
+
+            write_output(Some(output), &content)?;
+            cli.connection.print(&json!({ "output": output, "size": content.len() }), || {
+                format!("{} ({} bytes)", output.display(), content.len())
+            })
+        }
+        Command::List {
+            category,
+            tag,
+            search,
+            limit,
+            versions,
+        } => {
+            let query = TemplateListQuery {
+                category: category.clone(),
+                tag: tag.clone(),
+                search: search.clone(),
+                limit: *limit,
+                include_versions: *versions,
+                ..Default::default()
+            };
+            let list = carbone.list_templates(&query)?;
+            cli.connection.print(&list.templates, || {
+                list.templates
+                    .iter()
+                    .map(|template| {
+                        format!(
+                            "{}\t{}\t{}",
+                            template.id.as_str(),
+                            template.name.as_deref().unwrap_or("-"),
+                            template.extension.as_deref().unwrap_or("-")
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+        }
+        Command::Sync {
+            dir,
+            manifest,
+            delete_orphans,
+            dry_run,
+            payload,
+        } => {
+            let options = SyncOptions {
+                dry_run: *dry_run,
+                delete_orphans: *delete_orphans,
+                manifest_path: manifest.clone(),
+                payload: payload.clone(),
+            };
+            let report = sync_templates_blocking(&carbone, dir, &options)?;
+            cli.connection.print(&report, || {
+                let uploaded = report.uploaded.iter().map(|entry| ("uploaded", entry));
+                let unchanged = report.unchanged.iter().map(|entry| ("unchanged", entry));
+                let deleted = report.deleted.iter().map(|entry| ("deleted", entry));
+                uploaded
+                    .chain(unchanged)
+                    .chain(deleted)
+                    .map(|(action, entry)| {
+                        format!("{}\t{}\t{}", action, entry.path, entry.template_id.as_str())
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+        }
+    }
+}
+
+fn write_output(output: Option<&PathBuf>, content: &[u8]) -> Result<(), CarboneError> {
+    match output {
+        Some(path) => fs::write(path, content)?,
+        None => io::stdout().write_all(content)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Arguments and helpers shared by the command-line tools.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Args;
+use serde::Serialize;
+use serde_json::json;
+
+use carbone_sdk_rust::config::Config;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::types::{ApiJsonToken, ApiVersion};
+
+pub const EXIT_ERROR: u8 = 1;
+pub const EXIT_NOT_FOUND: u8 = 3;
+pub const EXIT_UNAUTHORIZED: u8 = 4;
+pub const EXIT_INVALID_INPUT: u8 = 5;
+pub const EXIT_UNAVAILABLE: u8 = 6;
+pub const EXIT_TEMPLATE_ID_MISMATCH: u8 = 7;
+
+/// Configuration of the API and output format.
+///
+/// The configuration is read from `--config` (or `CARBONE_CONFIG`), then overridden by
+/// `--api-url`, `--api-timeout` and `--api-version` (or `CARBONE_API_URL`, `CARBONE_API_TIMEOUT`
+/// and `CARBONE_API_VERSION`). The API token is read from `--token` or `CARBONE_TOKEN`.
+#[derive(Debug, Args)]
+pub struct ConnectionArgs {
+    /// JSON configuration file (`apiUrl`, `apiTimeout`, `apiVersion`).
+    #[arg(long, global = true, env = "CARBONE_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    #[arg(long, global = true, env = "CARBONE_API_URL")]
+    pub api_url: Option<String>,
+
+    /// Timeout of the requests, in seconds.
+    #[arg(long, global = true, env = "CARBONE_API_TIMEOUT")]
+    pub api_timeout: Option<u64>,
+
+    #[arg(long, global = true, env = "CARBONE_API_VERSION")]
+    pub api_version: Option<String>,
+
+    #[arg(long, global = true, env = "CARBONE_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+
+    /// Print the results as JSON.
+    #[arg(long, global = true)]
+    pub json: bool,
+}
+
+impl ConnectionArgs {
+    pub fn config(&self) -> Result<Config, CarboneError> {
+        let config = match &self.config {
+            Some(path) => Config::from_file(&path.to_string_lossy())?,
+            None => Config::default(),
+        };
+
+        let api_version = match &self.api_version {
+            Some(version) => ApiVersion::new(version.clone())?,
+            None => config.api_version,
+        };
+
+        Ok(Config::new(
+            self.api_url.clone().unwrap_or(config.api_url),
+            self.api_timeout.unwrap_or(config.api_timeout),
+            api_version,
+        )?)
+    }
+
+    pub fn api_token(&self) -> Result<ApiJsonToken, CarboneError> {
+        match &self.token {
+            Some(token) => ApiJsonToken::new(token.clone()),
+            None => Err(CarboneError::EmptyString("CARBONE_TOKEN".to_string())),
+        }
+    }
+
+    /// Print a result as JSON with `--json`, as text otherwise.
+    pub fn print<T: Serialize, F: FnOnce() -> String>(&self, value: &T, text: F) -> Result<(), CarboneError> {
+        let output = if self.json {
+            serde_json::to_string_pretty(value).map_err(|e| CarboneError::Error(e.to_string()))?
+        } else {
+            text()
+        };
+
+        if !output.is_empty() {
+            println!("{}", output);
+        }
+        Ok(())
+    }
+
+    /// Print an error on the standard error and return its exit code.
+    pub fn fail(&self, error: &CarboneError, code: u8) -> ExitCode {
+        if self.json {
+            eprintln!("{}", json!({ "success": false, "error": error.to_string() }));
+        } else {
+            eprintln!("error: {}", error);
+        }
+        ExitCode::from(code)
+    }
+
+    /// Run a command with the API token, and map its error to an exit code.
+    pub fn run<F>(&self, command: F) -> ExitCode
+    where
+        F: FnOnce(&ApiJsonToken) -> Result<ExitCode, CarboneError>,
+    {
+        let api_token = match self.api_token() {
+            Ok(api_token) => api_token,
+            Err(e) => return self.fail(&e, EXIT_UNAUTHORIZED),
+        };
+
+        match command(&api_token) {
+            Ok(code) => code,
+            Err(e) => self.fail(&e, exit_code(&e)),
+        }
+    }
+}
+
+/// Exit code of a command for an error, documented in the README.
+pub fn exit_code(error: &CarboneError) -> u8 {
+    match error {
+        CarboneError::TemplateIdNotFound(_)
+        | CarboneError::RenderIdNotFound(_)
+        | CarboneError::TemplateFileNotFound(_)
+        | CarboneError::FileNotFound(_)
+        | CarboneError::IsADirectory(_) => EXIT_NOT_FOUND,
+        CarboneError::EmptyString(_)
+        | CarboneError::RequestBodyNotWellFormedJsonError
+        | CarboneError::ParseError(..)
+        | CarboneError::DataValidation(_) => EXIT_INVALID_INPUT,
+        CarboneError::RequestError(_) | CarboneError::NotReady(_) | CarboneError::ServerError => {
+            EXIT_UNAVAILABLE
+        }
+        CarboneError::TemplateIdMismatch { .. } => EXIT_TEMPLATE_ID_MISMATCH,
+        CarboneError::HttpError { status_code, .. } => match status_code.as_u16() {
+            401 | 403 => EXIT_UNAUTHORIZED,
+            404 => EXIT_NOT_FOUND,
+            429 | 500..=599 => EXIT_UNAVAILABLE,
+            400..=499 => EXIT_INVALID_INPUT,
+            _ => EXIT_ERROR,
+        },
+        _ => EXIT_ERROR,
+    }
+}
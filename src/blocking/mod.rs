@@ -1,20 +1,36 @@
 use bytes::Bytes;
 
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use reqwest::blocking::multipart;
 use reqwest::blocking::Client;
 use reqwest::blocking::ClientBuilder;
+use reqwest::blocking::Request;
+use reqwest::blocking::RequestBuilder;
+use reqwest::blocking::Response;
 use reqwest::header;
 use reqwest::header::HeaderValue;
 use reqwest::StatusCode;
 
-use crate::carbone_response::APIResponse;
+use crate::api::CarboneApiBlocking;
+use crate::carbone_response::{
+    APIResponse, ErrorResponse, TemplateInfo, TemplateInfoResponse, TemplateList, TemplateListResponse,
+};
 use crate::config::Config;
 use crate::errors::*;
+use crate::interceptor::{self, InterceptedRequest, InterceptedResponse, Interceptor};
+use crate::metrics::MetricsRecorder;
+use crate::rate_limit::RateLimiter;
 use crate::render::*;
+use crate::status::{Status, READY_POLL_INTERVAL};
+use crate::store::TemplateStore;
 use crate::template::*;
-use crate::types::{ApiJsonToken, JsonData};
+use crate::template::validate::DataValidator;
+use crate::trace;
+#[cfg(feature = "testing")]
+use crate::testing::cassette::Cassette;
+use crate::types::{unix_timestamp, ApiJsonToken, Endpoint, JsonData};
 
 use crate::types::Result;
 
@@ -22,6 +38,14 @@ use crate::types::Result;
 pub struct Carbone<'a> {
     config: &'a Config,
     http_client: Client,
+    rate_limiter: Option<RateLimiter>,
+    validator: Option<DataValidator>,
+    verify_template_ids: bool,
+    template_store: Option<Arc<dyn TemplateStore>>,
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    #[cfg(feature = "testing")]
+    cassette: Option<Arc<Cassette>>,
 }
 
 impl<'a> Carbone<'a> {
@@ -47,9 +71,141 @@ impl<'a> Carbone<'a> {
         Ok(Self {
             config,
             http_client,
+            rate_limiter: None,
+            validator: None,
+            verify_template_ids: false,
+            template_store: None,
+            metrics: None,
+            interceptors: Vec::new(),
+            #[cfg(feature = "testing")]
+            cassette: None,
         })
     }
 
+    /// Throttle the requests of this client with the given rate limiter.
+    ///
+    /// The limiter can be cloned and shared between several clients.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Enable the strict mode: before `render_data`, the render options sent for a
+    /// template registered in the validator are checked against its markers, and a
+    /// `CarboneError::DataValidation` is returned if paths are missing or mismatched.
+    pub fn with_strict_validation(mut self, validator: DataValidator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Check that the template ID returned by `upload_template` matches the one computed
+    /// locally from the content and payload, and return `CarboneError::TemplateIdMismatch`
+    /// otherwise. Without it, a mismatch makes `generate_report` upload the template again
+    /// on every call.
+    ///
+    /// Supported with the Carbone API v3 and v4 (`Config::api_version`), see
+    /// `TemplateId::from_bytes_for_api`; with other versions `upload_template` fails.
+    pub fn with_template_id_verification(mut self) -> Self {
+        self.verify_template_ids = true;
+        self
+    }
+
+    /// Resolve the template names of `generate_report_with_template_name` with this store.
+    ///
+    /// The store can be shared between several clients.
+    pub fn with_template_store(mut self, template_store: Arc<dyn TemplateStore>) -> Self {
+        self.template_store = Some(template_store);
+        self
+    }
+
+    /// Report the requests, sizes and render durations of this client to a metrics recorder.
+    ///
+    /// The recorder can be shared between several clients.
+    pub fn with_metrics(mut self, metrics: Arc<dyn MetricsRecorder>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Add an interceptor called around every HTTP call of this client, after the
+    /// interceptors added before it.
+    pub fn with_interceptor(mut self, interceptor: Arc<dyn Interceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    /// Record the interactions with the Carbone API in a cassette, or replay them from it.
+    ///
+    /// Requires the `testing` feature.
+    #[cfg(feature = "testing")]
+    pub fn with_cassette(mut self, cassette: Arc<Cassette>) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "http_request",
+            skip_all,
+            fields(
+                endpoint = ?endpoint,
+                http.status_code = tracing::field::Empty,
+                bytes = tracing::field::Empty,
+                elapsed_ms = tracing::field::Empty
+            )
+        )
+    )]
+    fn send(&self, endpoint: Endpoint, request: RequestBuilder) -> Result<Response> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire_blocking(endpoint);
+        }
+
+        let start = Instant::now();
+        let response = self.execute(endpoint, request);
+
+        if let Some(metrics) = &self.metrics {
+            let status = response.as_ref().ok().map(|response| response.status());
+            metrics.record_request(endpoint, status, start.elapsed());
+        }
+        let response = response?;
+
+        trace::record("http.status_code", response.status().as_u16());
+        if let Some(bytes) = response.content_length() {
+            trace::record("bytes", bytes);
+        }
+        trace::record("elapsed_ms", start.elapsed().as_millis() as u64);
+
+        Ok(response)
+    }
+
+    fn execute(&self, endpoint: Endpoint, request: RequestBuilder) -> Result<Response> {
+        let mut request = request.build()?;
+
+        if self.interceptors.is_empty() {
+            return self.transport(request);
+        }
+
+        let mut intercepted = InterceptedRequest::from_blocking_request(&request);
+        interceptor::before_send(&self.interceptors, endpoint, &mut intercepted)?;
+        intercepted.apply_blocking(&mut request);
+
+        let response = match self.transport(request) {
+            Ok(response) => InterceptedResponse::from_blocking_response(response),
+            Err(e) => Err(e),
+        };
+
+        Ok(interceptor::after_receive(&self.interceptors, endpoint, response)?.into_blocking_response())
+    }
+
+    fn transport(&self, request: Request) -> Result<Response> {
+        #[cfg(feature = "testing")]
+        if let Some(cassette) = &self.cassette {
+            return cassette.send_blocking(&self.http_client, request);
+        }
+
+        Ok(self.http_client.execute(request)?)
+    }
+
     // Delete a template from the Carbone Service.
     ///
     ///
@@ -85,13 +241,18 @@ impl<'a> Carbone<'a> {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "delete_template", skip_all, fields(template_id = template_id.as_str())))]
     pub fn delete_template(&self, template_id: TemplateId) -> Result<bool> {
         let url = format!("{}/template/{}", self.config.api_url, template_id.as_str());
 
-        let response = self.http_client.delete(url).send();
+        let response = self.send(Endpoint::Delete, self.http_client.delete(url));
 
         match response {
             Ok(response) => {
+                if !response.status().is_success() {
+                    return Err(http_error(response));
+                }
+
                 let json = response.json::<APIResponse>()?;
 
                 if json.success {
@@ -100,7 +261,7 @@ impl<'a> Carbone<'a> {
                     Err(CarboneError::Error(json.error.unwrap()))
                 }
             }
-            Err(e) => Err(CarboneError::RequestError(e)),
+            Err(e) => Err(e),
         }
     }
 
@@ -141,21 +302,26 @@ impl<'a> Carbone<'a> {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "download_template", skip_all, fields(template_id = template_id.as_str(), bytes = tracing::field::Empty)))]
     pub fn download_template(&self, template_id: &TemplateId) -> Result<Bytes> {
         let url = format!("{}/template/{}", self.config.api_url, template_id.as_str());
 
-        let response = self.http_client.get(url).send();
+        let response = self.send(Endpoint::Download, self.http_client.get(url));
 
         match response {
             Ok(r) => {
                 if r.status() == StatusCode::OK {
-                    Ok(r.bytes()?)
+                    let content = r.bytes()?;
+                    trace::record("bytes", content.len() as u64);
+                    if let Some(metrics) = &self.metrics {
+                        metrics.record_download(Endpoint::Download, content.len() as u64);
+                    }
+                    Ok(content)
                 } else {
-                    let json = r.json::<APIResponse>()?;
-                    Err(CarboneError::Error(json.error.unwrap()))
+                    Err(http_error(r))
                 }
             }
-            Err(e) => Err(CarboneError::RequestError(e)),
+            Err(e) => Err(e),
         }
     }
 
@@ -198,7 +364,7 @@ impl<'a> Carbone<'a> {
     ///
     ///     let json_data = JsonData::new(json_data_value)?;
     ///
-    ///     let template_file = &TemplateFile::new("/path/to/template.odf".to_string(), None)?;
+    ///     let template_file = TemplateFile::new("/path/to/template.odf".to_string(), None)?;
     ///     let report_content = carbone.generate_report_with_file(&template_file, json_data, None)?;
     ///
     ///     assert_eq!(report_content.is_empty(), false);
@@ -206,18 +372,23 @@ impl<'a> Carbone<'a> {
     ///     Ok(())
     /// }
     /// ```
-    pub fn generate_report_with_file(
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "generate_report", skip_all, fields(template.name = tracing::field::Empty, template_id = tracing::field::Empty, uploaded = false)))]
+    pub fn generate_report_with_file<T: Into<TemplateSource>>(
         &self,
-        template_file: &TemplateFile,
+        template: T,
         json_data: JsonData,
         payload: Option<&str>,
     ) -> Result<Bytes> {
+        let template_file = template.into().into_template_file()?;
         let template_id_generated = template_file.generate_id(payload)?;
+        trace::record("template.name", template_file.file_name());
+        trace::record("template_id", template_id_generated.as_str());
 
         let result = self.download_template(&template_id_generated);
 
         let template_id = if result.is_err() {
-            self.upload_template(&template_file, None)?
+            trace::record("uploaded", true);
+            self.upload_template(&template_file, payload)?
         } else {
             template_id_generated
         };
@@ -228,6 +399,54 @@ impl<'a> Carbone<'a> {
         Ok(report_content)
     }
 
+    /// Generate a report from a template of the store set with `with_template_store`, by name.
+    ///
+    /// The template ID is computed from the stored content and the template is uploaded
+    /// only if the Carbone API does not know it yet.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    ///
+    /// use carbone_sdk_rust::config::Config;
+    /// use carbone_sdk_rust::blocking::Carbone;
+    /// use carbone_sdk_rust::store::FsTemplateStore;
+    /// use carbone_sdk_rust::types::{ApiJsonToken, JsonData};
+    /// use carbone_sdk_rust::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let config: Config = Default::default();
+    ///
+    ///     let api_token = ApiJsonToken::new(std::env::var("CARBONE_TOKEN").unwrap())?;
+    ///
+    ///     let carbone = Carbone::new(&config, &api_token)?
+    ///         .with_template_store(Arc::new(FsTemplateStore::new("templates")));
+    ///
+    ///     let json_data = JsonData::new(r#"{ "data": { "id": 42 }, "convertTo": "pdf" }"#.to_string())?;
+    ///
+    ///     let report_content = carbone.generate_report_with_template_name("invoices/invoice.docx", json_data, None)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn generate_report_with_template_name(
+        &self,
+        name: &str,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        let template_store = self
+            .template_store
+            .as_ref()
+            .ok_or_else(|| CarboneError::Error("no template store configured".to_string()))?;
+
+        let template_file = template_store.template_file(name)?;
+
+        self.generate_report_with_file(template_file, json_data, payload)
+    }
+
     /// Get a new report.
     ///
     ///
@@ -263,21 +482,26 @@ impl<'a> Carbone<'a> {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "get_report", skip_all, fields(render_id = render_id.as_str(), bytes = tracing::field::Empty)))]
     pub fn get_report(&self, render_id: &RenderId) -> Result<Bytes> {
         let url = format!("{}/render/{}", self.config.api_url, render_id.as_str());
 
-        let response = self.http_client.get(url).send();
+        let response = self.send(Endpoint::Report, self.http_client.get(url));
 
         match response {
             Ok(r) => {
                 if r.status() == StatusCode::OK {
-                    Ok(r.bytes()?)
+                    let content = r.bytes()?;
+                    trace::record("bytes", content.len() as u64);
+                    if let Some(metrics) = &self.metrics {
+                        metrics.record_download(Endpoint::Report, content.len() as u64);
+                    }
+                    Ok(content)
                 } else {
-                    let json = r.json::<APIResponse>()?;
-                    Err(CarboneError::Error(json.error.unwrap()))
+                    Err(http_error(r))
                 }
             }
-            Err(e) => Err(CarboneError::RequestError(e)),
+            Err(e) => Err(e),
         }
     }
 
@@ -327,6 +551,7 @@ impl<'a> Carbone<'a> {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "generate_report_with_template_id", skip_all, fields(template_id = template_id.as_str())))]
     pub fn generate_report_with_template_id(
         &self,
         template_id: TemplateId,
@@ -383,27 +608,43 @@ impl<'a> Carbone<'a> {
     ///     Ok(())
     /// }
     /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "render_data", skip_all, fields(template_id = template_id.as_str(), render_id = tracing::field::Empty)))]
     pub fn render_data(&self, template_id: TemplateId, json_data: JsonData) -> Result<RenderId> {
+        if let Some(validator) = &self.validator {
+            validator.validate(&template_id, &json_data)?;
+        }
+
         let url = format!("{}/render/{}", self.config.api_url, template_id.as_str());
 
-        let response = self
+        let request = self
             .http_client
             .post(url)
             .header("Content-Type", "application/json")
-            .body(json_data.as_str().to_owned())
-            .send();
+            .body(json_data.as_str().to_owned());
+
+        let start = Instant::now();
+        let response = self.send(Endpoint::Render, request);
 
         match response {
             Ok(response) => {
+                if !response.status().is_success() {
+                    return Err(http_error(response));
+                }
+
                 let json = response.json::<APIResponse>()?;
 
                 if json.success {
-                    Ok(json.data.unwrap().render_id.unwrap())
+                    let render_id = json.data.unwrap().render_id.unwrap();
+                    trace::record("render_id", render_id.as_str());
+                    if let Some(metrics) = &self.metrics {
+                        metrics.record_render(start.elapsed());
+                    }
+                    Ok(render_id)
                 } else {
                     Err(CarboneError::Error(json.error.unwrap()))
                 }
             }
-            Err(e) => Err(CarboneError::RequestError(e)),
+            Err(e) => Err(e),
         }
     }
 
@@ -442,35 +683,551 @@ impl<'a> Carbone<'a> {
     ///     Ok(())
     /// }
     /// ```
-    pub fn upload_template(
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "upload_template", skip_all, fields(template.name = tracing::field::Empty, bytes = tracing::field::Empty, template_id = tracing::field::Empty)))]
+    pub fn upload_template<T: Into<TemplateSource>>(
         &self,
-        template_file: &TemplateFile,
-        salt: Option<&str>,
+        template: T,
+        payload: Option<&str>,
     ) -> Result<TemplateId> {
-        let salt = match salt {
-            Some(s) => s.to_string(),
-            None => "".to_string(),
+        let template_file = template.into().into_template_file()?;
+        let bytes = template_size(&template_file);
+        trace::record("template.name", template_file.file_name());
+        trace::record("bytes", bytes);
+
+        let expected_template_id = if self.verify_template_ids {
+            Some(TemplateId::from_bytes_for_api(
+                &self.config.api_version,
+                template_file.content_bytes()?,
+                payload,
+            )?)
+        } else {
+            None
         };
 
-        let form = multipart::Form::new()
-            .text("", salt)
-            .file("template", template_file.path_as_str())?;
+        let mut form = multipart::Form::new();
+        if let Some(payload) = payload {
+            form = form.text("payload", payload.to_string());
+        }
+        let form = form.part("template", template_part(&template_file)?);
 
         let url = format!("{}/template", self.config.api_url);
 
-        let response = self.http_client.post(url).multipart(form).send();
+        let response = self.send(Endpoint::Upload, self.http_client.post(url).multipart(form));
 
         match response {
             Ok(response) => {
+                if !response.status().is_success() {
+                    return Err(http_error(response));
+                }
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_upload(bytes);
+                }
+                let json = response.json::<APIResponse>()?;
+
+                if json.success {
+                    let template_id = json.data.unwrap().template_id.unwrap();
+                    trace::record("template_id", template_id.as_str());
+                    match expected_template_id {
+                        Some(expected) => verify_template_id(expected, template_id),
+                        None => Ok(template_id),
+                    }
+                } else {
+                    Err(CarboneError::Error(json.error.unwrap()))
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Upload a template with its metadata (name, comment, tags, category, deployment
+    /// and expiration dates) and return the template metadata stored by the Carbone API v4.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::env;
+    ///
+    /// use carbone_sdk_rust::config::Config;
+    /// use carbone_sdk_rust::blocking::Carbone;
+    /// use carbone_sdk_rust::types::ApiJsonToken;
+    /// use carbone_sdk_rust::template::{TemplateFile, UploadOptions};
+    /// use carbone_sdk_rust::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let token =  match env::var("CARBONE_TOKEN") {
+    ///             Ok(v) => v,
+    ///             Err(e) => panic!("{}", e.to_string())
+    ///     };
+    ///
+    ///     let config: Config = Default::default();
+    ///
+    ///     let api_token = ApiJsonToken::new(token)?;
+    ///
+    ///     let template_file = TemplateFile::new("template.odt".to_string(), None)?;
+    ///
+    ///     let options = UploadOptions {
+    ///         name: Some("Invoice".to_string()),
+    ///         tags: vec!["accounting".to_string()],
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     let carbone = Carbone::new(&config, &api_token)?;
+    ///     let template_info = carbone.upload_template_with_options(&template_file, &options)?;
+    ///
+    ///     assert_eq!(template_info.name, Some("Invoice".to_string()));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn upload_template_with_options<T: Into<TemplateSource>>(
+        &self,
+        template: T,
+        options: &UploadOptions,
+    ) -> Result<TemplateInfo> {
+        self.upload_template_fields(template.into(), options.form_fields())
+    }
+
+    /// Upload a new version of an existing template, without deploying it unless
+    /// `options.deployed_at` is set.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::env;
+    ///
+    /// use carbone_sdk_rust::config::Config;
+    /// use carbone_sdk_rust::blocking::Carbone;
+    /// use carbone_sdk_rust::types::ApiJsonToken;
+    /// use carbone_sdk_rust::template::{TemplateFile, TemplateId, UploadOptions};
+    /// use carbone_sdk_rust::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let token =  match env::var("CARBONE_TOKEN") {
+    ///             Ok(v) => v,
+    ///             Err(e) => panic!("{}", e.to_string())
+    ///     };
+    ///
+    ///     let config: Config = Default::default();
+    ///
+    ///     let api_token = ApiJsonToken::new(token)?;
+    ///
+    ///     let template_id = TemplateId::new("0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114")?;
+    ///     let template_file = TemplateFile::new("contract-v2.docx".to_string(), None)?;
+    ///
+    ///     let carbone = Carbone::new(&config, &api_token)?;
+    ///     let version = carbone.upload_template_version(&template_id, &template_file, &UploadOptions::default())?;
+    ///
+    ///     carbone.deploy_template_version(&version.version_id.unwrap())?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn upload_template_version<T: Into<TemplateSource>>(
+        &self,
+        template_id: &TemplateId,
+        template: T,
+        options: &UploadOptions,
+    ) -> Result<TemplateInfo> {
+        let mut fields = vec![
+            ("versioning", "true".to_string()),
+            ("id", template_id.as_str().to_string()),
+        ];
+        fields.extend(options.form_fields());
+
+        self.upload_template_fields(template.into(), fields)
+    }
+
+    fn upload_template_fields(
+        &self,
+        template: TemplateSource,
+        fields: Vec<(&'static str, String)>,
+    ) -> Result<TemplateInfo> {
+        let template_file = template.into_template_file()?;
+        let bytes = template_size(&template_file);
+
+        let mut form = multipart::Form::new();
+        for (name, value) in fields {
+            form = form.text(name, value);
+        }
+        let form = form.part("template", template_part(&template_file)?);
+
+        let url = format!("{}/template", self.config.api_url);
+
+        let response = self.send(Endpoint::Upload, self.http_client.post(url).multipart(form));
+
+        match response {
+            Ok(response) => {
+                if !response.status().is_success() {
+                    return Err(http_error(response));
+                }
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_upload(bytes);
+                }
+                let json = response.json::<TemplateInfoResponse>()?;
+
+                json.into_template_info()
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// List every version of a template.
+    pub fn list_template_versions(&self, template_id: &TemplateId) -> Result<Vec<TemplateInfo>> {
+        let mut query = TemplateListQuery {
+            id: Some(template_id.clone()),
+            include_versions: true,
+            ..Default::default()
+        };
+        let mut versions = Vec::new();
+
+        loop {
+            let page = self.list_templates(&query)?;
+            versions.extend(page.templates);
+
+            match page.next_cursor {
+                Some(cursor) if page.has_more => query.cursor = Some(cursor),
+                _ => return Ok(versions),
+            }
+        }
+    }
+
+    /// Deploy a version of a template: the template ID renders this version from now on.
+    ///
+    /// Deploying a previous version rolls the template back.
+    pub fn deploy_template_version(&self, version_id: &VersionId) -> Result<bool> {
+        let url = format!("{}/template/{}", self.config.api_url, version_id.as_str());
+
+        let request = self
+            .http_client
+            .patch(url)
+            .json(&serde_json::json!({ "deployedAt": unix_timestamp() }));
+
+        match self.send(Endpoint::Update, request) {
+            Ok(response) => {
+                if !response.status().is_success() {
+                    return Err(http_error(response));
+                }
+
                 let json = response.json::<APIResponse>()?;
 
                 if json.success {
-                    Ok(json.data.unwrap().template_id.unwrap())
+                    Ok(true)
                 } else {
                     Err(CarboneError::Error(json.error.unwrap()))
                 }
             }
-            Err(e) => Err(CarboneError::RequestError(e)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Render data with a given version of a template, deployed or not.
+    pub fn render_template_version(&self, version_id: &VersionId, json_data: JsonData) -> Result<RenderId> {
+        self.render_data(TemplateId::new(version_id.as_str())?, json_data)
+    }
+
+    /// Generate a report with a given version of a template, deployed or not.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::env;
+    ///
+    /// use carbone_sdk_rust::config::Config;
+    /// use carbone_sdk_rust::blocking::Carbone;
+    /// use carbone_sdk_rust::types::{ApiJsonToken, JsonData};
+    /// use carbone_sdk_rust::template::VersionId;
+    /// use carbone_sdk_rust::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let token =  match env::var("CARBONE_TOKEN") {
+    ///             Ok(v) => v,
+    ///             Err(e) => panic!("{}", e.to_string())
+    ///     };
+    ///
+    ///     let config: Config = Default::default();
+    ///
+    ///     let api_token = ApiJsonToken::new(token)?;
+    ///
+    ///     let version_id = VersionId::new("0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114")?;
+    ///     let json_data = JsonData::new(r#"{ "data": { "name": "John" }, "convertTo": "pdf" }"#.to_string())?;
+    ///
+    ///     let carbone = Carbone::new(&config, &api_token)?;
+    ///     let report_content = carbone.generate_report_with_version_id(&version_id, json_data)?;
+    ///
+    ///     assert_eq!(report_content.is_empty(), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn generate_report_with_version_id(
+        &self,
+        version_id: &VersionId,
+        json_data: JsonData,
+    ) -> Result<Bytes> {
+        let render_id = self.render_template_version(version_id, json_data)?;
+        let report_content = self.get_report(&render_id)?;
+
+        Ok(report_content)
+    }
+
+    /// List the templates stored by the Carbone API v4, one page at a time.
+    ///
+    /// With a tag filter or a sort, every page is fetched and the templates are returned
+    /// in a single page. With a `Config::api_version` older than the v4, it fails without
+    /// sending the request.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::env;
+    ///
+    /// use carbone_sdk_rust::config::Config;
+    /// use carbone_sdk_rust::blocking::Carbone;
+    /// use carbone_sdk_rust::types::ApiJsonToken;
+    /// use carbone_sdk_rust::template::TemplateListQuery;
+    /// use carbone_sdk_rust::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let token =  match env::var("CARBONE_TOKEN") {
+    ///             Ok(v) => v,
+    ///             Err(e) => panic!("{}", e.to_string())
+    ///     };
+    ///
+    ///     let config: Config = Default::default();
+    ///
+    ///     let api_token = ApiJsonToken::new(token)?;
+    ///
+    ///     let carbone = Carbone::new(&config, &api_token)?;
+    ///
+    ///     let mut query = TemplateListQuery {
+    ///         category: Some("accounting".to_string()),
+    ///         limit: Some(50),
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     loop {
+    ///         let page = carbone.list_templates(&query)?;
+    ///         for template in page.templates.iter() {
+    ///             println!("{} {:?}", template.id.as_str(), template.name);
+    ///         }
+    ///         if !page.has_more {
+    ///             break;
+    ///         }
+    ///         query.cursor = page.next_cursor;
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn list_templates(&self, query: &TemplateListQuery) -> Result<TemplateList> {
+        TemplateListQuery::check_api_version(&self.config.api_version)?;
+
+        let mut page_query = match query.all_pages_query()? {
+            Some(page_query) => page_query,
+            None => return self.list_templates_page(query),
+        };
+        let mut templates = Vec::new();
+
+        loop {
+            let page = self.list_templates_page(&page_query)?;
+            templates.extend(page.templates);
+
+            match page.next_cursor {
+                Some(cursor) if page.has_more => page_query.cursor = Some(cursor),
+                _ => return Ok(query.apply(templates)),
+            }
         }
     }
+
+    fn list_templates_page(&self, query: &TemplateListQuery) -> Result<TemplateList> {
+        let url = format!("{}/templates", self.config.api_url);
+
+        let request = self.http_client.get(url).query(&query.query_params());
+
+        match self.send(Endpoint::List, request) {
+            Ok(response) => {
+                if !response.status().is_success() {
+                    return Err(http_error(response));
+                }
+
+                let json = response.json::<TemplateListResponse>()?;
+
+                json.into_template_list()
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get the metadata of the deployed version of a template.
+    pub fn get_template_info(&self, template_id: &TemplateId) -> Result<TemplateInfo> {
+        let query = TemplateListQuery {
+            id: Some(template_id.clone()),
+            limit: Some(1),
+            ..Default::default()
+        };
+
+        self.list_templates(&query)?
+            .templates
+            .into_iter()
+            .next()
+            .ok_or_else(|| CarboneError::TemplateIdNotFound(template_id.as_str().to_string()))
+    }
+
+    /// Get the status and the version of the Carbone API.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::env;
+    ///
+    /// use carbone_sdk_rust::config::Config;
+    /// use carbone_sdk_rust::blocking::Carbone;
+    /// use carbone_sdk_rust::types::ApiJsonToken;
+    /// use carbone_sdk_rust::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let token =  match env::var("CARBONE_TOKEN") {
+    ///             Ok(v) => v,
+    ///             Err(e) => panic!("{}", e.to_string())
+    ///     };
+    ///
+    ///     let config: Config = Default::default();
+    ///
+    ///     let api_token = ApiJsonToken::new(token)?;
+    ///
+    ///     let carbone = Carbone::new(&config, &api_token)?;
+    ///     let status = carbone.get_status()?;
+    ///
+    ///     assert!(status.success);
+    ///     assert!(status.is_compatible(&config));
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "get_status", skip_all))]
+    pub fn get_status(&self) -> Result<Status> {
+        let url = format!("{}/status", self.config.api_url);
+
+        let response = self.send(Endpoint::Status, self.http_client.get(url));
+
+        match response {
+            Ok(r) => {
+                if r.status() == StatusCode::OK {
+                    Ok(r.json::<Status>()?)
+                } else {
+                    Err(http_error(r))
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Poll the status of the Carbone API until it is ready or `timeout` is elapsed.
+    ///
+    /// Useful to wait for a Carbone container starting next to the application.
+    pub fn wait_until_ready(&self, timeout: Duration) -> Result<Status> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Ok(status) = self.get_status() {
+                if status.success {
+                    return Ok(status);
+                }
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(CarboneError::NotReady(timeout));
+            }
+            std::thread::sleep(READY_POLL_INTERVAL.min(deadline - now));
+        }
+    }
+}
+
+impl CarboneApiBlocking for Carbone<'_> {
+    fn upload_template(&self, template: TemplateSource, payload: Option<&str>) -> Result<TemplateId> {
+        Carbone::upload_template(self, template, payload)
+    }
+
+    fn render_data(&self, template_id: TemplateId, json_data: JsonData) -> Result<RenderId> {
+        Carbone::render_data(self, template_id, json_data)
+    }
+
+    fn get_report(&self, render_id: &RenderId) -> Result<Bytes> {
+        Carbone::get_report(self, render_id)
+    }
+
+    fn download_template(&self, template_id: &TemplateId) -> Result<Bytes> {
+        Carbone::download_template(self, template_id)
+    }
+
+    fn delete_template(&self, template_id: TemplateId) -> Result<bool> {
+        Carbone::delete_template(self, template_id)
+    }
+
+    fn get_status(&self) -> Result<Status> {
+        Carbone::get_status(self)
+    }
+
+    fn list_templates(&self, query: &TemplateListQuery) -> Result<TemplateList> {
+        Carbone::list_templates(self, query)
+    }
+
+    fn generate_report_with_file(
+        &self,
+        template: TemplateSource,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        Carbone::generate_report_with_file(self, template, json_data, payload)
+    }
+
+    fn generate_report_with_template_id(&self, template_id: TemplateId, json_data: JsonData) -> Result<Bytes> {
+        Carbone::generate_report_with_template_id(self, template_id, json_data)
+    }
+}
+
+/// Size of a template in memory or on disk, `0` if unknown.
+fn template_size(template_file: &TemplateFile) -> u64 {
+    match (&template_file.content, &template_file.metadata) {
+        (Some(content), _) => content.len() as u64,
+        (None, Some(metadata)) => metadata.len(),
+        (None, None) => 0,
+    }
+}
+
+fn template_part(template_file: &TemplateFile) -> Result<multipart::Part> {
+    let part = match &template_file.content {
+        Some(content) => {
+            let ext = template_file.extension().unwrap_or("");
+            let mime = mime_guess::from_ext(ext).first_or_octet_stream();
+
+            multipart::Part::bytes(content.clone())
+                .file_name(template_file.file_name().to_string())
+                .mime_str(mime.as_ref())?
+        }
+        None => multipart::Part::file(template_file.path_as_str())?,
+    };
+
+    Ok(part)
+}
+
+/// `HttpError` of a response with a non-2xx status.
+fn http_error(response: Response) -> CarboneError {
+    let status_code = response.status();
+
+    match response.bytes() {
+        Ok(body) => ErrorResponse::http_error(status_code, &body),
+        Err(e) => e.into(),
+    }
 }
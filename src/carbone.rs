@@ -1,22 +1,39 @@
 use bytes::Bytes;
 
 use std::path::Path;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use async_trait::async_trait;
 use reqwest::header;
 use reqwest::header::HeaderValue;
 use reqwest::multipart;
 use reqwest::Client;
 use reqwest::ClientBuilder;
+use reqwest::Request;
+use reqwest::RequestBuilder;
+use reqwest::Response;
 use reqwest::StatusCode;
 
 
-use crate::carbone_response::APIResponse;
+use crate::api::CarboneApi;
+use crate::carbone_response::{
+    APIResponse, ErrorResponse, TemplateInfo, TemplateInfoResponse, TemplateList, TemplateListResponse,
+};
 use crate::config::Config;
 use crate::errors::*;
+use crate::interceptor::{self, InterceptedRequest, InterceptedResponse, Interceptor};
+use crate::metrics::MetricsRecorder;
+use crate::rate_limit::RateLimiter;
 use crate::render::*;
+use crate::status::{Status, READY_POLL_INTERVAL};
+use crate::store::TemplateStore;
 use crate::template::*;
-use crate::types::{ApiJsonToken, JsonData};
+use crate::template::validate::DataValidator;
+use crate::trace;
+#[cfg(feature = "testing")]
+use crate::testing::cassette::Cassette;
+use crate::types::{unix_timestamp, ApiJsonToken, Endpoint, JsonData};
 
 use crate::types::Result;
 
@@ -24,6 +41,14 @@ use crate::types::Result;
 pub struct Carbone<'a> {
     config: &'a Config,
     http_client: Client,
+    rate_limiter: Option<RateLimiter>,
+    validator: Option<DataValidator>,
+    verify_template_ids: bool,
+    template_store: Option<Arc<dyn TemplateStore>>,
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    #[cfg(feature = "testing")]
+    cassette: Option<Arc<Cassette>>,
 }
 
 impl<'a> Carbone<'a> {
@@ -33,9 +58,9 @@ impl<'a> Carbone<'a> {
             "carbone-version",
             HeaderValue::from_str(config.api_version.as_str()).unwrap(),
         );
-        if api_token != None 
+        if let Some(api_token) = api_token
         {
-            let bearer = format!("Bearer {}", api_token.expect("REASON").as_str());
+            let bearer = format!("Bearer {}", api_token.as_str());
 
             let mut auth_value = header::HeaderValue::from_str(bearer.as_str()).unwrap();
             auth_value.set_sensitive(true);
@@ -51,14 +76,156 @@ impl<'a> Carbone<'a> {
         Ok(Self {
             config,
             http_client,
+            rate_limiter: None,
+            validator: None,
+            verify_template_ids: false,
+            template_store: None,
+            metrics: None,
+            interceptors: Vec::new(),
+            #[cfg(feature = "testing")]
+            cassette: None,
         })
     }
 
+    /// Throttle the requests of this client with the given rate limiter.
+    ///
+    /// The limiter can be cloned and shared between several clients.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Enable the strict mode: before `render_data`, the render options sent for a
+    /// template registered in the validator are checked against its markers, and a
+    /// `CarboneError::DataValidation` is returned if paths are missing or mismatched.
+    pub fn with_strict_validation(mut self, validator: DataValidator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Check that the template ID returned by `upload_template` matches the one computed
+    /// locally from the content and payload, and return `CarboneError::TemplateIdMismatch`
+    /// otherwise. Without it, a mismatch makes `generate_report` upload the template again
+    /// on every call.
+    ///
+    /// Supported with the Carbone API v3 and v4 (`Config::api_version`), see
+    /// `TemplateId::from_bytes_for_api`; with other versions `upload_template` fails.
+    pub fn with_template_id_verification(mut self) -> Self {
+        self.verify_template_ids = true;
+        self
+    }
+
+    /// Resolve the template names of `generate_report_with_template_name` with this store.
+    ///
+    /// The store can be shared between several clients.
+    pub fn with_template_store(mut self, template_store: Arc<dyn TemplateStore>) -> Self {
+        self.template_store = Some(template_store);
+        self
+    }
+
+    /// Report the requests, sizes and render durations of this client to a metrics recorder.
+    ///
+    /// The recorder can be shared between several clients.
+    pub fn with_metrics(mut self, metrics: Arc<dyn MetricsRecorder>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Add an interceptor called around every HTTP call of this client, after the
+    /// interceptors added before it.
+    pub fn with_interceptor(mut self, interceptor: Arc<dyn Interceptor>) -> Self {
+        self.interceptors.push(interceptor);
+        self
+    }
+
+    #[cfg(feature = "server")]
+    pub(crate) fn metrics(&self) -> Option<&Arc<dyn MetricsRecorder>> {
+        self.metrics.as_ref()
+    }
+
+    /// Record the interactions with the Carbone API in a cassette, or replay them from it.
+    ///
+    /// Requires the `testing` feature.
+    #[cfg(feature = "testing")]
+    pub fn with_cassette(mut self, cassette: Arc<Cassette>) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "http_request",
+            skip_all,
+            fields(
+                endpoint = ?endpoint,
+                http.status_code = tracing::field::Empty,
+                bytes = tracing::field::Empty,
+                elapsed_ms = tracing::field::Empty
+            )
+        )
+    )]
+    async fn send(&self, endpoint: Endpoint, request: RequestBuilder) -> Result<Response> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(endpoint).await;
+        }
+
+        let start = Instant::now();
+        let response = self.execute(endpoint, request).await;
+
+        if let Some(metrics) = &self.metrics {
+            let status = response.as_ref().ok().map(|response| response.status());
+            metrics.record_request(endpoint, status, start.elapsed());
+        }
+        let response = response?;
+
+        trace::record("http.status_code", response.status().as_u16());
+        if let Some(bytes) = response.content_length() {
+            trace::record("bytes", bytes);
+        }
+        trace::record("elapsed_ms", start.elapsed().as_millis() as u64);
+
+        Ok(response)
+    }
+
+    async fn execute(&self, endpoint: Endpoint, request: RequestBuilder) -> Result<Response> {
+        let mut request = request.build()?;
+
+        if self.interceptors.is_empty() {
+            return self.transport(request).await;
+        }
+
+        let mut intercepted = InterceptedRequest::from_request(&request);
+        interceptor::before_send(&self.interceptors, endpoint, &mut intercepted)?;
+        intercepted.apply(&mut request);
+
+        let response = match self.transport(request).await {
+            Ok(response) => InterceptedResponse::from_response(response).await,
+            Err(e) => Err(e),
+        };
+
+        Ok(interceptor::after_receive(&self.interceptors, endpoint, response)?.into_response())
+    }
+
+    async fn transport(&self, request: Request) -> Result<Response> {
+        #[cfg(feature = "testing")]
+        if let Some(cassette) = &self.cassette {
+            return cassette.send(&self.http_client, request).await;
+        }
+
+        Ok(self.http_client.execute(request).await?)
+    }
+
     // Delete a template from the Carbone Service.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "delete_template", skip_all, fields(template_id = template_id.as_str())))]
     pub async fn delete_template(&self, template_id: TemplateId) -> Result<bool> {
         let url = format!("{}/template/{}", self.config.api_url, template_id.as_str());
 
-        let response = self.http_client.delete(url).send().await?;
+        let response = self.send(Endpoint::Delete, self.http_client.delete(url)).await?;
+
+        if !response.status().is_success() {
+            return Err(http_error(response).await);
+        }
 
         let json = response.json::<APIResponse>().await?;
 
@@ -70,30 +237,39 @@ impl<'a> Carbone<'a> {
     }
 
     // Download a template from the Carbone Service.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "download_template", skip_all, fields(template_id = template_id.as_str(), bytes = tracing::field::Empty)))]
     pub async fn download_template(&self, template_id: &TemplateId) -> Result<Bytes> {
         let url = format!("{}/template/{}", self.config.api_url, template_id.as_str());
 
-        let response = self.http_client.get(url).send().await?;
+        let response = self.send(Endpoint::Download, self.http_client.get(url)).await?;
 
         if response.status() == StatusCode::OK {
-            Ok(response.bytes().await?)
+            let content = response.bytes().await?;
+            trace::record("bytes", content.len() as u64);
+            if let Some(metrics) = &self.metrics {
+                metrics.record_download(Endpoint::Download, content.len() as u64);
+            }
+            Ok(content)
         } else {
-            let json = response.json::<APIResponse>().await?;
-            Err(CarboneError::Error(json.error.unwrap()))
+            Err(http_error(response).await)
         }
     }
 
     /// Generate a report.
+    ///
+    /// The report is rendered with the template ID computed from `template_data`; the
+    /// template is uploaded and rendered again only if the API does not know it.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "generate_report", skip_all, fields(template.name = template_name.as_str(), template_id = tracing::field::Empty, uploaded = false)))]
     pub async fn generate_report(
         &self,
         template_name: String,
         template_data: Vec<u8>,
         json_data: JsonData,
         payload: Option<&str>,
-        salt: Option<&str>
     ) -> Result<Bytes> {
 
         let template_id_generated = TemplateId::from_bytes(template_data.to_owned(), payload)?;
+        trace::record("template_id", template_id_generated.as_str());
         let mut template_id = template_id_generated;
         let render_id;
     
@@ -104,7 +280,8 @@ impl<'a> Carbone<'a> {
             Err(e) => match e {
                 CarboneError::HttpError { status_code, error_message } => {
                     if status_code == reqwest::StatusCode::NOT_FOUND {
-                        template_id = self.upload_template(template_name.as_str(), template_data, salt).await?;
+                        trace::record("uploaded", true);
+                        template_id = self.upload_template(template_name.as_str(), template_data, payload).await?;
                         render_id = Some(self.render_data(template_id, json_data).await?);
                     } else {
                         return Err(CarboneError::HttpError { status_code, error_message });
@@ -125,11 +302,47 @@ impl<'a> Carbone<'a> {
     }
 
 
+    /// Generate a report from a template source: a file, bytes with a logical name or a reader.
+    pub async fn generate_report_with_file<T: Into<TemplateSource>>(
+        &self,
+        template: T,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        let template_file = template.into().into_template_file()?;
+        let template_data = template_file.content_bytes()?;
+
+        self.generate_report(template_file.file_name().to_string(), template_data, json_data, payload)
+            .await
+    }
+
+    /// Generate a report from a template of the store set with `with_template_store`, by name.
+    ///
+    /// The template ID is computed from the stored content and the template is uploaded
+    /// only if the Carbone API does not know it yet.
+    pub async fn generate_report_with_template_name(
+        &self,
+        name: &str,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        let template_store = self
+            .template_store
+            .as_ref()
+            .ok_or_else(|| CarboneError::Error("no template store configured".to_string()))?;
+
+        let template_file = template_store.template_file(name)?;
+
+        self.generate_report_with_file(template_file, json_data, payload)
+            .await
+    }
+
     /// Get a new report.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "get_report", skip_all, fields(render_id = render_id.as_str(), bytes = tracing::field::Empty)))]
     pub async fn get_report(&self, render_id: &RenderId) -> Result<Bytes> {
         let url = format!("{}/render/{}", self.config.api_url, render_id.as_str());
 
-        let response = self.http_client.get(url).send().await?;
+        let response = self.send(Endpoint::Report, self.http_client.get(url)).await?;
 
         // let mut report_name = None;
 
@@ -148,14 +361,19 @@ impl<'a> Carbone<'a> {
         // }
 
         if response.status() == StatusCode::OK {
-            Ok(response.bytes().await?)
+            let content = response.bytes().await?;
+            trace::record("bytes", content.len() as u64);
+            if let Some(metrics) = &self.metrics {
+                metrics.record_download(Endpoint::Report, content.len() as u64);
+            }
+            Ok(content)
         } else {
-            let json = response.json::<APIResponse>().await?;
-            Err(CarboneError::Error(json.error.unwrap()))
+            Err(http_error(response).await)
         }
     }
 
     /// Generate a report with a template_id given.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "generate_report_with_template_id", skip_all, fields(template_id = template_id.as_str())))]
     pub async fn generate_report_with_template_id(
         &self,
         template_id: TemplateId,
@@ -168,101 +386,420 @@ impl<'a> Carbone<'a> {
     }
 
     /// Render data with a given template_id.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "render_data", skip_all, fields(template_id = template_id.as_str(), render_id = tracing::field::Empty)))]
     pub async fn render_data(
         &self,
         template_id: TemplateId,
         json_data: JsonData,
     ) -> Result<RenderId> {
+        if let Some(validator) = &self.validator {
+            validator.validate(&template_id, &json_data)?;
+        }
+
         let url = format!("{}/render/{}", self.config.api_url, template_id.as_str());
 
-        let response = self
+        let request = self
             .http_client
             .post(url)
             .header("Content-Type", "application/json")
-            .body(json_data.as_str().to_owned())
-            .send()
-            .await?;
+            .body(json_data.as_str().to_owned());
+
+        let start = Instant::now();
+        let response = self.send(Endpoint::Render, request).await?;
 
         if !response.status().is_success() {
-            let status_code = response.status();
-            let json = response.json::<APIResponse>().await?;
-            return Err(CarboneError::HttpError {
-                status_code,
-                error_message: json.error.unwrap_or_else(|| "Unknown error".to_string()),
-            });
+            return Err(http_error(response).await);
         }
 
         let json = response.json::<APIResponse>().await?;
 
         if json.success {
-            Ok(json.data.unwrap().render_id.unwrap())
+            let render_id = json.data.unwrap().render_id.unwrap();
+            trace::record("render_id", render_id.as_str());
+            if let Some(metrics) = &self.metrics {
+                metrics.record_render(start.elapsed());
+            }
+            Ok(render_id)
         } else {
             Err(CarboneError::Error(json.error.unwrap()))
         }
     }
 
     /// Upload a template to the Carbone Service.
+    ///
+    /// The `payload` is sent with the template and is part of the template ID computed
+    /// by the API, like in `TemplateId::from_bytes`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "upload_template", skip_all, fields(template.name = file_name, bytes = file_content.len(), template_id = tracing::field::Empty)))]
     pub async fn upload_template(
         &self,
         file_name: &str,
         file_content: Vec<u8>,
-        salt: Option<&str>,
+        payload: Option<&str>,
     ) -> Result<TemplateId> {
-        let salt = match salt {
-            Some(s) => s.to_string(),
-            None => "".to_string(),
+        let expected_template_id = if self.verify_template_ids {
+            Some(TemplateId::from_bytes_for_api(&self.config.api_version, file_content.clone(), payload)?)
+        } else {
+            None
         };
 
-        let file_path = Path::new(file_name);
+        let bytes = file_content.len() as u64;
+        let part = template_part(file_name, file_content)?;
 
-        let file_name = file_path
-            .file_name()
-            .map(|filename| filename.to_string_lossy().into_owned());
+        let mut form = multipart::Form::new();
+        if let Some(payload) = payload {
+            form = form.text("payload", payload.to_string());
+        }
+        let form = form.part("template", part);
 
-        let file_name = match file_name {
-            Some(s) => s,
-            None => return Err(CarboneError::Error("Failed to fetch file name".to_string())),
-        };
+        let url = format!("{}/template", self.config.api_url);
+
+        let response = self
+            .send(Endpoint::Upload, self.http_client.post(url).multipart(form))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(http_error(response).await);
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_upload(bytes);
+        }
 
+        let json = response.json::<APIResponse>().await?;
+
+        if json.success {
+            let template_id = json.data.unwrap().template_id.unwrap();
+            trace::record("template_id", template_id.as_str());
+            match expected_template_id {
+                Some(expected) => verify_template_id(expected, template_id),
+                None => Ok(template_id),
+            }
+        } else {
+            Err(CarboneError::Error(json.error.unwrap()))
+        }
+    }
+
+    /// Upload a template source: a file, bytes with a logical name or a reader.
+    pub async fn upload_template_file<T: Into<TemplateSource>>(
+        &self,
+        template: T,
+        payload: Option<&str>,
+    ) -> Result<TemplateId> {
+        let template_file = template.into().into_template_file()?;
+        let file_content = template_file.content_bytes()?;
+
+        self.upload_template(template_file.file_name(), file_content, payload)
+            .await
+    }
 
-        let ext = file_path
-            .extension()
-            .and_then(|ext| ext.to_str()) .unwrap_or("");
-        let mime = mime_guess::from_ext(ext).first_or_octet_stream();
+    /// Upload a template with its metadata (name, comment, tags, category, deployment
+    /// and expiration dates) and return the template metadata stored by the Carbone API v4.
+    pub async fn upload_template_with_options<T: Into<TemplateSource>>(
+        &self,
+        template: T,
+        options: &UploadOptions,
+    ) -> Result<TemplateInfo> {
+        self.upload_template_fields(template.into(), options.form_fields())
+            .await
+    }
 
-        let part = multipart::Part::bytes(file_content)
-            .file_name(file_name)
-            .mime_str(mime.as_ref())?;
+    /// Upload a new version of an existing template, without deploying it unless
+    /// `options.deployed_at` is set.
+    pub async fn upload_template_version<T: Into<TemplateSource>>(
+        &self,
+        template_id: &TemplateId,
+        template: T,
+        options: &UploadOptions,
+    ) -> Result<TemplateInfo> {
+        let mut fields = vec![
+            ("versioning", "true".to_string()),
+            ("id", template_id.as_str().to_string()),
+        ];
+        fields.extend(options.form_fields());
+
+        self.upload_template_fields(template.into(), fields)
+            .await
+    }
 
-        let form: multipart::Form = multipart::Form::new().text("", salt).part("template", part);
+    async fn upload_template_fields(
+        &self,
+        template: TemplateSource,
+        fields: Vec<(&'static str, String)>,
+    ) -> Result<TemplateInfo> {
+        let template_file = template.into_template_file()?;
+        let file_content = template_file.content_bytes()?;
+        let bytes = file_content.len() as u64;
+        let part = template_part(template_file.file_name(), file_content)?;
+
+        let mut form = multipart::Form::new();
+        for (name, value) in fields {
+            form = form.text(name, value);
+        }
+        let form = form.part("template", part);
 
         let url = format!("{}/template", self.config.api_url);
 
+        let response = self
+            .send(Endpoint::Upload, self.http_client.post(url).multipart(form))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(http_error(response).await);
+        }
 
-        let response = self.http_client.post(url).multipart(form).send().await?;
+        if let Some(metrics) = &self.metrics {
+            metrics.record_upload(bytes);
+        }
+
+        let json = response.json::<TemplateInfoResponse>().await?;
+
+        json.into_template_info()
+    }
+
+    /// List every version of a template.
+    pub async fn list_template_versions(&self, template_id: &TemplateId) -> Result<Vec<TemplateInfo>> {
+        let mut query = TemplateListQuery {
+            id: Some(template_id.clone()),
+            include_versions: true,
+            ..Default::default()
+        };
+        let mut versions = Vec::new();
+
+        loop {
+            let page = self.list_templates(&query).await?;
+            versions.extend(page.templates);
+
+            match page.next_cursor {
+                Some(cursor) if page.has_more => query.cursor = Some(cursor),
+                _ => return Ok(versions),
+            }
+        }
+    }
+
+    /// Deploy a version of a template: the template ID renders this version from now on.
+    ///
+    /// Deploying a previous version rolls the template back.
+    pub async fn deploy_template_version(&self, version_id: &VersionId) -> Result<bool> {
+        let url = format!("{}/template/{}", self.config.api_url, version_id.as_str());
+
+        let request = self
+            .http_client
+            .patch(url)
+            .json(&serde_json::json!({ "deployedAt": unix_timestamp() }));
+
+        let response = self.send(Endpoint::Update, request).await?;
+
+        if !response.status().is_success() {
+            return Err(http_error(response).await);
+        }
 
         let json = response.json::<APIResponse>().await?;
 
         if json.success {
-            Ok(json.data.unwrap().template_id.unwrap())
+            Ok(true)
         } else {
             Err(CarboneError::Error(json.error.unwrap()))
         }
     }
 
+    /// Render data with a given version of a template, deployed or not.
+    pub async fn render_template_version(
+        &self,
+        version_id: &VersionId,
+        json_data: JsonData,
+    ) -> Result<RenderId> {
+        self.render_data(TemplateId::new(version_id.as_str())?, json_data)
+            .await
+    }
+
+    /// Generate a report with a given version of a template, deployed or not.
+    pub async fn generate_report_with_version_id(
+        &self,
+        version_id: &VersionId,
+        json_data: JsonData,
+    ) -> Result<Bytes> {
+        let render_id = self.render_template_version(version_id, json_data).await?;
+        let report_content = self.get_report(&render_id).await?;
+
+        Ok(report_content)
+    }
+
+    /// List the templates stored by the Carbone API v4, one page at a time.
+    ///
+    /// With a tag filter or a sort, every page is fetched and the templates are returned
+    /// in a single page. With a `Config::api_version` older than the v4, it fails without
+    /// sending the request.
+    pub async fn list_templates(&self, query: &TemplateListQuery) -> Result<TemplateList> {
+        TemplateListQuery::check_api_version(&self.config.api_version)?;
+
+        let mut page_query = match query.all_pages_query()? {
+            Some(page_query) => page_query,
+            None => return self.list_templates_page(query).await,
+        };
+        let mut templates = Vec::new();
+
+        loop {
+            let page = self.list_templates_page(&page_query).await?;
+            templates.extend(page.templates);
+
+            match page.next_cursor {
+                Some(cursor) if page.has_more => page_query.cursor = Some(cursor),
+                _ => return Ok(query.apply(templates)),
+            }
+        }
+    }
+
+    async fn list_templates_page(&self, query: &TemplateListQuery) -> Result<TemplateList> {
+        let url = format!("{}/templates", self.config.api_url);
+
+        let request = self.http_client.get(url).query(&query.query_params());
+
+        let response = self.send(Endpoint::List, request).await?;
+
+        if !response.status().is_success() {
+            return Err(http_error(response).await);
+        }
+
+        let json = response.json::<TemplateListResponse>().await?;
+
+        json.into_template_list()
+    }
+
+    /// Get the metadata of the deployed version of a template.
+    pub async fn get_template_info(&self, template_id: &TemplateId) -> Result<TemplateInfo> {
+        let query = TemplateListQuery {
+            id: Some(template_id.clone()),
+            limit: Some(1),
+            ..Default::default()
+        };
+
+        self.list_templates(&query)
+            .await?
+            .templates
+            .into_iter()
+            .next()
+            .ok_or_else(|| CarboneError::TemplateIdNotFound(template_id.as_str().to_string()))
+    }
 
-    pub async fn get_status(&self) -> Result<String>
-    {
+    /// Get the status and the version of the Carbone API.
+    #[cfg_attr(feature = "tracing", tracing::instrument(name = "get_status", skip_all))]
+    pub async fn get_status(&self) -> Result<Status> {
         let url = format!("{}/status", self.config.api_url);
 
-        let response = self.http_client.get(url).send().await?;
+        let response = self.send(Endpoint::Status, self.http_client.get(url)).await?;
 
         if response.status() == StatusCode::OK {
-            let body = response.text().await?;
-            Ok(body)
+            Ok(response.json::<Status>().await?)
         } else {
-            let json = response.json::<APIResponse>().await?;
-            Err(CarboneError::Error(json.error.unwrap()))
+            Err(http_error(response).await)
         }
     }
+
+    /// Poll the status of the Carbone API until it is ready or `timeout` is elapsed.
+    ///
+    /// Useful to wait for a Carbone container starting next to the application.
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<Status> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Ok(status) = self.get_status().await {
+                if status.success {
+                    return Ok(status);
+                }
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(CarboneError::NotReady(timeout));
+            }
+            tokio::time::sleep(READY_POLL_INTERVAL.min(deadline - now)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl CarboneApi for Carbone<'_> {
+    async fn upload_template(
+        &self,
+        file_name: &str,
+        file_content: Vec<u8>,
+        payload: Option<&str>,
+    ) -> Result<TemplateId> {
+        Carbone::upload_template(self, file_name, file_content, payload).await
+    }
+
+    async fn render_data(&self, template_id: TemplateId, json_data: JsonData) -> Result<RenderId> {
+        Carbone::render_data(self, template_id, json_data).await
+    }
+
+    async fn get_report(&self, render_id: &RenderId) -> Result<Bytes> {
+        Carbone::get_report(self, render_id).await
+    }
+
+    async fn download_template(&self, template_id: &TemplateId) -> Result<Bytes> {
+        Carbone::download_template(self, template_id).await
+    }
+
+    async fn delete_template(&self, template_id: TemplateId) -> Result<bool> {
+        Carbone::delete_template(self, template_id).await
+    }
+
+    async fn get_status(&self) -> Result<Status> {
+        Carbone::get_status(self).await
+    }
+
+    async fn list_templates(&self, query: &TemplateListQuery) -> Result<TemplateList> {
+        Carbone::list_templates(self, query).await
+    }
+
+    async fn generate_report(
+        &self,
+        template_name: String,
+        template_data: Vec<u8>,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        Carbone::generate_report(self, template_name, template_data, json_data, payload).await
+    }
+
+    async fn generate_report_with_template_id(
+        &self,
+        template_id: TemplateId,
+        json_data: JsonData,
+    ) -> Result<Bytes> {
+        Carbone::generate_report_with_template_id(self, template_id, json_data).await
+    }
+}
+
+fn template_part(file_name: &str, file_content: Vec<u8>) -> Result<multipart::Part> {
+    let file_path = Path::new(file_name);
+
+    let file_name = file_path
+        .file_name()
+        .map(|filename| filename.to_string_lossy().into_owned());
+
+    let file_name = match file_name {
+        Some(s) => s,
+        None => return Err(CarboneError::Error("Failed to fetch file name".to_string())),
+    };
+
+    let ext = file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+    let mime = mime_guess::from_ext(ext).first_or_octet_stream();
+
+    Ok(multipart::Part::bytes(file_content)
+        .file_name(file_name)
+        .mime_str(mime.as_ref())?)
+}
+
+/// `HttpError` of a response with a non-2xx status.
+async fn http_error(response: Response) -> CarboneError {
+    let status_code = response.status();
+
+    match response.bytes().await {
+        Ok(body) => ErrorResponse::http_error(status_code, &body),
+        Err(e) => e.into(),
+    }
 }
@@ -1,10 +1,14 @@
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
+use reqwest::StatusCode;
+
 use std::str;
 
+use crate::errors::CarboneError;
+
 use crate::render::RenderId;
-use crate::template::TemplateId;
+use crate::template::{TemplateId, VersionId};
 
 #[skip_serializing_none]
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
@@ -27,4 +31,158 @@ pub struct APIResponse {
     pub error: Option<String>,
     #[serde(default)]
     pub code: Option<String>,
-}
\ No newline at end of file
+}
+
+/// Body of an error response: routes set `error`, `GET /status` sets `message`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ErrorResponse {
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+impl ErrorResponse {
+    /// `HttpError` of a response with a non-2xx status, with the message of its body
+    /// if it is a JSON error.
+    pub(crate) fn http_error(status_code: StatusCode, body: &[u8]) -> CarboneError {
+        let error_message = serde_json::from_slice::<ErrorResponse>(body)
+            .ok()
+            .and_then(|json| json.error.or(json.message))
+            .unwrap_or_else(|| "Unknown error".to_string());
+
+        CarboneError::HttpError { status_code, error_message }
+    }
+}
+
+/// Metadata of a template stored by the Carbone API v4.
+///
+/// Dates are Unix timestamps in seconds.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", try_from = "RawTemplateInfo")]
+pub struct TemplateInfo {
+    pub id: TemplateId,
+    pub version_id: Option<VersionId>,
+    pub name: Option<String>,
+    pub comment: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    #[serde(rename = "type")]
+    pub extension: Option<String>,
+    pub size: Option<u64>,
+    pub created_at: Option<i64>,
+    pub deployed_at: Option<i64>,
+    pub expire_at: Option<i64>,
+}
+
+/// Template metadata as sent by the API: v3 servers only return `templateId`
+/// and `templateFileExtension`, v4 servers return `id`, `type` and the legacy fields.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTemplateInfo {
+    id: Option<TemplateId>,
+    template_id: Option<TemplateId>,
+    version_id: Option<VersionId>,
+    name: Option<String>,
+    comment: Option<String>,
+    category: Option<String>,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+    #[serde(rename = "type")]
+    extension: Option<String>,
+    template_file_extension: Option<String>,
+    size: Option<u64>,
+    created_at: Option<i64>,
+    deployed_at: Option<i64>,
+    expire_at: Option<i64>,
+}
+
+impl TryFrom<RawTemplateInfo> for TemplateInfo {
+    type Error = CarboneError;
+
+    fn try_from(raw: RawTemplateInfo) -> Result<Self, Self::Error> {
+        let id = raw
+            .id
+            .or(raw.template_id)
+            .ok_or_else(|| CarboneError::EmptyString("template_id".to_string()))?;
+
+        Ok(Self {
+            id,
+            version_id: raw.version_id,
+            name: raw.name,
+            comment: raw.comment,
+            category: raw.category,
+            tags: raw.tags.unwrap_or_default(),
+            extension: raw.extension.or(raw.template_file_extension),
+            size: raw.size,
+            created_at: raw.created_at,
+            deployed_at: raw.deployed_at,
+            expire_at: raw.expire_at,
+        })
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TemplateInfoResponse {
+    pub success: bool,
+    pub data: Option<TemplateInfo>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
+impl TemplateInfoResponse {
+    pub(crate) fn into_template_info(self) -> Result<TemplateInfo, CarboneError> {
+        match self.data {
+            Some(template_info) if self.success => Ok(template_info),
+            _ => Err(CarboneError::Error(
+                self.error.unwrap_or_else(|| "Unknown error".to_string()),
+            )),
+        }
+    }
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateListResponse {
+    pub success: bool,
+    #[serde(default)]
+    pub data: Option<Vec<TemplateInfo>>,
+    #[serde(default)]
+    pub has_more: bool,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
+impl TemplateListResponse {
+    pub(crate) fn into_template_list(self) -> Result<TemplateList, CarboneError> {
+        if !self.success {
+            return Err(CarboneError::Error(
+                self.error.unwrap_or_else(|| "Unknown error".to_string()),
+            ));
+        }
+
+        Ok(TemplateList {
+            templates: self.data.unwrap_or_default(),
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+        })
+    }
+}
+
+/// One page of templates returned by `list_templates`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateList {
+    pub templates: Vec<TemplateInfo>,
+    pub has_more: bool,
+    /// Cursor to pass in `TemplateListQuery::cursor` to fetch the next page.
+    pub next_cursor: Option<String>,
+}
@@ -0,0 +1,439 @@
+//! Client spreading the calls over several on-premise Carbone instances, with failover.
+//!
+//! Requires the `cluster` feature.
+
+use bytes::Bytes;
+use reqwest::StatusCode;
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::carbone::Carbone;
+use crate::config::Config;
+use crate::errors::CarboneError;
+use crate::interceptor::Interceptor;
+use crate::metrics::MetricsRecorder;
+use crate::rate_limit::RateLimiter;
+use crate::template::TemplateId;
+use crate::trace;
+use crate::types::{ApiJsonToken, JsonData, Result};
+
+/// How a [`CarboneCluster`] picks the node handling the next call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionStrategy {
+    /// Cycle through the healthy nodes.
+    #[default]
+    RoundRobin,
+    /// Pick the healthy node with the fewest calls in flight.
+    LeastInFlight,
+}
+
+/// Snapshot of the health of a node of a [`CarboneCluster`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeState {
+    pub api_url: String,
+    pub healthy: bool,
+    pub in_flight: usize,
+    pub consecutive_failures: u32,
+}
+
+#[derive(Debug, Default)]
+struct Health {
+    consecutive_failures: u32,
+    ejected_until: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Node<'a> {
+    config: &'a Config,
+    carbone: Carbone<'a>,
+    in_flight: AtomicUsize,
+    health: Mutex<Health>,
+}
+
+impl Node<'_> {
+    fn is_available(&self, now: Instant) -> bool {
+        match self.health.lock().unwrap().ejected_until {
+            Some(until) => until <= now,
+            None => true,
+        }
+    }
+}
+
+struct InFlightGuard<'n>(&'n AtomicUsize);
+
+impl<'n> InFlightGuard<'n> {
+    fn new(counter: &'n AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Client spreading the calls over several Carbone On-premise instances.
+///
+/// Every call is handled by one node chosen with the [`SelectionStrategy`].
+/// When a node fails (connection error or `5xx` response) the call is retried
+/// on another node, and a node failing `failure_threshold` times in a row is
+/// ejected for `cooldown`; calls fail with `ServerError` while every node is
+/// ejected. `check_health` and `run_health_checks` poll the status of the nodes
+/// to eject or restore them. Templates are stored per instance, so the
+/// upload-on-404 fallback of `generate_report` uploads the template to the node
+/// rendering the report.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::cluster::{CarboneCluster, SelectionStrategy};
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::types::ApiVersion;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), CarboneError> {
+///
+///     let api_version = ApiVersion::new("4".to_string())?;
+///     let configs = vec![
+///         Config::new("http://carbone-1:4000".to_string(), 60, api_version.clone())?,
+///         Config::new("http://carbone-2:4000".to_string(), 60, api_version.clone())?,
+///         Config::new("http://carbone-3:4000".to_string(), 60, api_version)?,
+///     ];
+///
+///     let cluster = CarboneCluster::new(&configs, None)?
+///         .with_strategy(SelectionStrategy::LeastInFlight);
+///
+///     cluster.check_health().await;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CarboneCluster<'a> {
+    nodes: Vec<Node<'a>>,
+    strategy: SelectionStrategy,
+    failure_threshold: u32,
+    cooldown: Duration,
+    next: AtomicUsize,
+    metrics: Option<Arc<dyn MetricsRecorder>>,
+}
+
+impl<'a> CarboneCluster<'a> {
+    pub fn new(configs: &'a [Config], api_token: Option<&'a ApiJsonToken>) -> Result<Self> {
+        if configs.is_empty() {
+            return Err(CarboneError::EmptyString("configs".to_string()));
+        }
+
+        let nodes = configs
+            .iter()
+            .map(|config| {
+                Ok(Node {
+                    config,
+                    carbone: Carbone::new(config, api_token)?,
+                    in_flight: AtomicUsize::new(0),
+                    health: Mutex::new(Health::default()),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            nodes,
+            strategy: SelectionStrategy::default(),
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(30),
+            next: AtomicUsize::new(0),
+            metrics: None,
+        })
+    }
+
+    pub fn with_strategy(mut self, strategy: SelectionStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Number of consecutive failures ejecting a node (3 by default).
+    pub fn with_failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Duration a failing node is ejected for (30 seconds by default).
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Throttle all the nodes with the given rate limiter.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        for node in self.nodes.iter_mut() {
+            node.carbone = node.carbone.clone().with_rate_limiter(rate_limiter.clone());
+        }
+        self
+    }
+
+    /// Add an interceptor called around every HTTP call of all the nodes.
+    pub fn with_interceptor(mut self, interceptor: Arc<dyn Interceptor>) -> Self {
+        for node in self.nodes.iter_mut() {
+            node.carbone = node.carbone.clone().with_interceptor(interceptor.clone());
+        }
+        self
+    }
+
+    /// Report the requests of all the nodes, and the calls retried on another node, to a
+    /// metrics recorder.
+    pub fn with_metrics(mut self, metrics: Arc<dyn MetricsRecorder>) -> Self {
+        for node in self.nodes.iter_mut() {
+            node.carbone = node.carbone.clone().with_metrics(metrics.clone());
+        }
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Health of every node, in the order of the configurations.
+    pub fn node_states(&self) -> Vec<NodeState> {
+        let now = Instant::now();
+        self.nodes
+            .iter()
+            .map(|node| NodeState {
+                api_url: node.config.api_url.clone(),
+                healthy: node.is_available(now),
+                in_flight: node.in_flight.load(Ordering::SeqCst),
+                consecutive_failures: node.health.lock().unwrap().consecutive_failures,
+            })
+            .collect()
+    }
+
+    /// Request the status of every node, eject the nodes not responding and
+    /// restore the others. Returns whether each node is healthy.
+    pub async fn check_health(&self) -> Vec<bool> {
+        let mut healthy = Vec::with_capacity(self.nodes.len());
+
+        for node in self.nodes.iter() {
+            match node.carbone.get_status().await {
+                Ok(status) if status.success => {
+                    self.record_success(node);
+                    healthy.push(true);
+                }
+                _ => {
+                    let mut health = node.health.lock().unwrap();
+                    health.consecutive_failures = health.consecutive_failures.max(1);
+                    health.ejected_until = Some(Instant::now() + self.cooldown);
+                    healthy.push(false);
+                }
+            }
+        }
+
+        healthy
+    }
+
+    /// Call `check_health` every `interval`, forever.
+    ///
+    /// The cluster borrows its configurations, so this future runs next to the
+    /// application, e.g. in `tokio::select!`, rather than in a spawned task.
+    pub async fn run_health_checks(&self, interval: Duration) {
+        let mut ticks = tokio::time::interval(interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            ticks.tick().await;
+            self.check_health().await;
+        }
+    }
+
+    /// Generate a report on one node, uploading the template to that node if it does not know it.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "cluster.generate_report",
+            skip_all,
+            fields(node = tracing::field::Empty, retries = 0)
+        )
+    )]
+    pub async fn generate_report(
+        &self,
+        template_name: String,
+        template_data: Vec<u8>,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        self.call(|carbone| {
+            carbone.generate_report(
+                template_name.clone(),
+                template_data.clone(),
+                json_data.clone(),
+                payload,
+            )
+        })
+        .await
+    }
+
+    /// Generate a report with a template_id given, on one node.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "cluster.generate_report_with_template_id",
+            skip_all,
+            fields(node = tracing::field::Empty, retries = 0)
+        )
+    )]
+    pub async fn generate_report_with_template_id(
+        &self,
+        template_id: TemplateId,
+        json_data: JsonData,
+    ) -> Result<Bytes> {
+        self.call(|carbone| {
+            carbone.generate_report_with_template_id(template_id.clone(), json_data.clone())
+        })
+        .await
+    }
+
+    /// Upload a template to every available node.
+    ///
+    /// Fails only if no node accepted the template.
+    pub async fn upload_template(
+        &self,
+        file_name: &str,
+        file_content: Vec<u8>,
+        payload: Option<&str>,
+    ) -> Result<TemplateId> {
+        let template_ids = self
+            .broadcast(|carbone| carbone.upload_template(file_name, file_content.clone(), payload))
+            .await?;
+
+        template_ids.into_iter().next().ok_or(CarboneError::ServerError)
+    }
+
+    /// Delete a template from every available node.
+    ///
+    /// Nodes not storing the template count as successes. Returns whether at least one
+    /// node deleted the template, and fails only if the deletion failed on every node.
+    pub async fn delete_template(&self, template_id: TemplateId) -> Result<bool> {
+        let deleted = self
+            .broadcast(|carbone| {
+                let template_id = template_id.clone();
+                async move {
+                    match carbone.delete_template(template_id).await {
+                        Err(CarboneError::HttpError { status_code, .. })
+                            if status_code == StatusCode::NOT_FOUND =>
+                        {
+                            Ok(false)
+                        }
+                        result => result,
+                    }
+                }
+            })
+            .await?;
+
+        Ok(deleted.into_iter().any(|deleted| deleted))
+    }
+
+    /// Run `f` on every available node and return the values of the nodes where it
+    /// succeeded, or the last error if it failed on every node.
+    async fn broadcast<'s, T, F, Fut>(&'s self, f: F) -> Result<Vec<T>>
+    where
+        F: Fn(&'s Carbone<'a>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let now = Instant::now();
+        let mut values = Vec::new();
+        let mut last_error = None;
+
+        for node in self.nodes.iter().filter(|node| node.is_available(now)) {
+            let _guard = InFlightGuard::new(&node.in_flight);
+            match self.record(node, f(&node.carbone).await) {
+                Ok(value) => values.push(value),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        if values.is_empty() {
+            Err(last_error.unwrap_or(CarboneError::ServerError))
+        } else {
+            Ok(values)
+        }
+    }
+
+    async fn call<'s, T, F, Fut>(&'s self, f: F) -> Result<T>
+    where
+        F: Fn(&'s Carbone<'a>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut tried = vec![false; self.nodes.len()];
+        let mut last_error = None;
+
+        while let Some(index) = self.select(&tried) {
+            let retries = tried.iter().filter(|tried| **tried).count() as u64;
+            if retries > 0 {
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_retry();
+                }
+            }
+            trace::record("retries", retries);
+            trace::record("node", index as u64);
+            tried[index] = true;
+            let node = &self.nodes[index];
+
+            let _guard = InFlightGuard::new(&node.in_flight);
+            match self.record(node, f(&node.carbone).await) {
+                Ok(value) => return Ok(value),
+                Err(e) if is_node_failure(&e) => last_error = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or(CarboneError::ServerError))
+    }
+
+    fn record<T>(&self, node: &Node<'a>, result: Result<T>) -> Result<T> {
+        match &result {
+            Err(e) if is_node_failure(e) => {
+                let mut health = node.health.lock().unwrap();
+                health.consecutive_failures += 1;
+                if health.consecutive_failures >= self.failure_threshold {
+                    health.ejected_until = Some(Instant::now() + self.cooldown);
+                }
+            }
+            _ => self.record_success(node),
+        }
+        result
+    }
+
+    fn record_success(&self, node: &Node<'a>) {
+        let mut health = node.health.lock().unwrap();
+        health.consecutive_failures = 0;
+        health.ejected_until = None;
+    }
+
+    fn select(&self, tried: &[bool]) -> Option<usize> {
+        let now = Instant::now();
+        let count = self.nodes.len();
+        let start = self.next.fetch_add(1, Ordering::SeqCst) % count;
+
+        let candidates: Vec<usize> = (0..count)
+            .map(|offset| (start + offset) % count)
+            .filter(|&i| !tried[i] && self.nodes[i].is_available(now))
+            .collect();
+
+        match self.strategy {
+            SelectionStrategy::RoundRobin => candidates.first().copied(),
+            SelectionStrategy::LeastInFlight => candidates
+                .iter()
+                .copied()
+                .min_by_key(|&i| self.nodes[i].in_flight.load(Ordering::SeqCst)),
+        }
+    }
+}
+
+fn is_node_failure(error: &CarboneError) -> bool {
+    match error {
+        CarboneError::RequestError(_) => true,
+        CarboneError::HttpError { status_code, .. } => status_code.is_server_error(),
+        _ => false,
+    }
+}
@@ -87,7 +87,7 @@ impl FromStr for Config {
             Ok(config) => Ok(config),
             Err(e) => Err(anyhow!(format!(
                 "CarboneSDK FromStr JsonParseError: {}",
-                e.to_string()
+                e
             ))),
         }
     }
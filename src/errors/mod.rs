@@ -1,5 +1,8 @@
 use thiserror::Error;
 
+use crate::template::validate::DataIssue;
+use crate::template::TemplateId;
+
 #[derive(Error, Debug)]
 #[non_exhaustive]
 pub enum CarboneError {
@@ -29,6 +32,15 @@ pub enum CarboneError {
     RequestBodyNotWellFormedJsonError,
     #[error("Carbone SDK {0:?} ParseError {1:?}")]
     ParseError(String, String),
+    #[error("Carbone SDK: API not ready after {0:?}")]
+    NotReady(std::time::Duration),
+    #[error("Carbone SDK DataValidation: {0:?}")]
+    DataValidation(Vec<DataIssue>),
+    #[error("Carbone SDK TemplateIdMismatch: computed {:?}, returned by the API {:?}", .expected.as_str(), .received.as_str())]
+    TemplateIdMismatch {
+        expected: TemplateId,
+        received: TemplateId,
+    },
     #[error("Carbone SDK HttpError: {status_code:?} - {error_message}")]
     HttpError {
         status_code: reqwest::StatusCode,
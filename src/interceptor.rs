@@ -0,0 +1,182 @@
+//! Hooks around the HTTP calls of the clients.
+//!
+//! Interceptors attached with `with_interceptor` run on every request of a client, in the
+//! order they were added: `before_send` can change the method, URL, headers and body of a
+//! request, and `after_receive`, called in the reverse order, can inspect or replace the
+//! response or the error. Typical uses are correlation and tenant headers, or logging the
+//! request bodies in debug builds.
+//!
+//! The default headers of the client, `Authorization` and `carbone-version`, are added
+//! when the request is sent: interceptors do not see the API token, and a header they set
+//! replaces the default one.
+
+use std::fmt;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode, Url};
+
+use crate::types::{Endpoint, Result};
+
+/// Hooks called around each HTTP call of a client.
+///
+/// Both methods have a default implementation passing the request or the response
+/// through, so interceptors only implement the hook they need.
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use carbone_sdk_rust::carbone::Carbone;
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::errors::CarboneError;
+/// use carbone_sdk_rust::interceptor::{InterceptedRequest, Interceptor};
+/// use carbone_sdk_rust::types::Endpoint;
+///
+/// #[derive(Debug)]
+/// struct TenantHeader(&'static str);
+///
+/// impl Interceptor for TenantHeader {
+///     fn before_send(&self, _endpoint: Endpoint, request: &mut InterceptedRequest) -> Result<(), CarboneError> {
+///         request.headers.insert("x-tenant-id", self.0.parse().unwrap());
+///         Ok(())
+///     }
+/// }
+///
+/// # fn main() -> Result<(), CarboneError> {
+/// let config: Config = Default::default();
+/// let carbone = Carbone::new(&config, None)?.with_interceptor(Arc::new(TenantHeader("acme")));
+/// # Ok(())
+/// # }
+/// ```
+pub trait Interceptor: fmt::Debug + Send + Sync {
+    /// Change a request before it is sent. An error cancels the request and is returned
+    /// to the caller without calling the `after_receive` hooks.
+    fn before_send(&self, _endpoint: Endpoint, _request: &mut InterceptedRequest) -> Result<()> {
+        Ok(())
+    }
+
+    /// Inspect or replace the response, or the error when no response was received.
+    fn after_receive(&self, _endpoint: Endpoint, response: Result<InterceptedResponse>) -> Result<InterceptedResponse> {
+        response
+    }
+}
+
+/// Request seen by `Interceptor::before_send`.
+#[derive(Debug, Clone)]
+pub struct InterceptedRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    /// Body of the request, `None` for the multipart uploads which are streamed.
+    pub body: Option<Vec<u8>>,
+}
+
+/// Response seen by `Interceptor::after_receive`, with its body read.
+#[derive(Debug, Clone)]
+pub struct InterceptedResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl InterceptedRequest {
+    pub(crate) fn from_request(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request.body().and_then(|body| body.as_bytes()).map(|body| body.to_vec()),
+        }
+    }
+
+    pub(crate) fn apply(self, request: &mut reqwest::Request) {
+        *request.method_mut() = self.method;
+        *request.url_mut() = self.url;
+        *request.headers_mut() = self.headers;
+        if let Some(body) = self.body {
+            *request.body_mut() = Some(body.into());
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    pub(crate) fn from_blocking_request(request: &reqwest::blocking::Request) -> Self {
+        Self {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request.body().and_then(|body| body.as_bytes()).map(|body| body.to_vec()),
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    pub(crate) fn apply_blocking(self, request: &mut reqwest::blocking::Request) {
+        *request.method_mut() = self.method;
+        *request.url_mut() = self.url;
+        *request.headers_mut() = self.headers;
+        if let Some(body) = self.body {
+            *request.body_mut() = Some(body.into());
+        }
+    }
+}
+
+impl InterceptedResponse {
+    pub(crate) async fn from_response(response: reqwest::Response) -> Result<Self> {
+        Ok(Self {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: response.bytes().await?,
+        })
+    }
+
+    pub(crate) fn into_response(self) -> reqwest::Response {
+        self.into_http_response().into()
+    }
+
+    #[cfg(feature = "blocking")]
+    pub(crate) fn from_blocking_response(response: reqwest::blocking::Response) -> Result<Self> {
+        Ok(Self {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: response.bytes()?,
+        })
+    }
+
+    #[cfg(feature = "blocking")]
+    pub(crate) fn into_blocking_response(self) -> reqwest::blocking::Response {
+        self.into_http_response().into()
+    }
+
+    fn into_http_response(self) -> http::Response<Vec<u8>> {
+        let mut response = http::Response::new(self.body.to_vec());
+        *response.status_mut() = self.status;
+        *response.headers_mut() = self.headers;
+        // The body is given as a whole.
+        response.headers_mut().remove(reqwest::header::TRANSFER_ENCODING);
+        response
+    }
+}
+
+/// Run the `before_send` hooks, in order.
+pub(crate) fn before_send(
+    interceptors: &[Arc<dyn Interceptor>],
+    endpoint: Endpoint,
+    request: &mut InterceptedRequest,
+) -> Result<()> {
+    for interceptor in interceptors {
+        interceptor.before_send(endpoint, request)?;
+    }
+    Ok(())
+}
+
+/// Run the `after_receive` hooks, in the reverse order.
+pub(crate) fn after_receive(
+    interceptors: &[Arc<dyn Interceptor>],
+    endpoint: Endpoint,
+    response: Result<InterceptedResponse>,
+) -> Result<InterceptedResponse> {
+    interceptors
+        .iter()
+        .rev()
+        .fold(response, |response, interceptor| interceptor.after_receive(endpoint, response))
+}
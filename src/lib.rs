@@ -1,9 +1,28 @@
+pub mod api;
+#[cfg(feature = "batch")]
+pub mod batch;
 #[cfg(feature = "blocking")]
 pub mod blocking;
 pub mod carbone;
 pub mod carbone_response;
+#[cfg(feature = "cluster")]
+pub mod cluster;
 pub mod config;
 pub mod errors;
+pub mod interceptor;
+pub mod metrics;
+pub mod rate_limit;
 pub mod render;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod status;
+pub mod store;
+#[cfg(feature = "sync")]
+pub mod sync;
 pub mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod trace;
 pub mod types;
+#[cfg(feature = "watch")]
+pub mod watch;
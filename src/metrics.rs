@@ -0,0 +1,265 @@
+//! Metrics of the usage of the Carbone API, for dashboards and alerts.
+//!
+//! Clients configured with `with_metrics` report each HTTP call with its endpoint, status
+//! and latency, the sizes of the uploaded templates and downloaded files and the render
+//! durations to a `MetricsRecorder`. `CarboneCluster` also reports its retries, and the
+//! render gateway its cache hits and misses. `PrometheusMetrics` keeps them in memory and
+//! renders them in the Prometheus text format; other backends implement the trait.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use crate::types::Endpoint;
+
+/// Upper bounds of the buckets of the duration histograms, in seconds.
+pub const DURATION_BUCKETS: [f64; 12] = [0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+/// Receiver of the metrics of the clients.
+///
+/// Every method has an empty default implementation, so recorders only implement the
+/// metrics they export. Implementations must be shareable between threads, as clients
+/// keep them in an `Arc`, and fast: they are called on every request.
+pub trait MetricsRecorder: fmt::Debug + Send + Sync {
+    /// An HTTP call to the Carbone API. `status` is `None` when no response was received,
+    /// e.g. on a connection error or a timeout.
+    fn record_request(&self, _endpoint: Endpoint, _status: Option<StatusCode>, _elapsed: Duration) {}
+
+    /// Size of a template accepted by the Carbone API.
+    fn record_upload(&self, _bytes: u64) {}
+
+    /// Size of a file received from the Carbone API: a template for `Endpoint::Download`,
+    /// a report for `Endpoint::Report`.
+    fn record_download(&self, _endpoint: Endpoint, _bytes: u64) {}
+
+    /// Duration of a successful `render_data`, until the render ID is received.
+    fn record_render(&self, _elapsed: Duration) {}
+
+    /// Lookup in the report cache of the render gateway.
+    fn record_cache(&self, _hit: bool) {}
+
+    /// Call of `CarboneCluster` sent again to another node after a node failure.
+    fn record_retry(&self) {}
+}
+
+/// In-memory recorder exporting the metrics in the Prometheus text format.
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use carbone_sdk_rust::carbone::Carbone;
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::metrics::PrometheusMetrics;
+///
+/// # fn main() -> Result<(), carbone_sdk_rust::errors::CarboneError> {
+/// let config: Config = Default::default();
+/// let metrics = Arc::new(PrometheusMetrics::new());
+/// let carbone = Carbone::new(&config, None)?.with_metrics(metrics.clone());
+///
+/// // Serve `metrics.render()` on the `/metrics` route of the service.
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct PrometheusMetrics {
+    state: Mutex<MetricsState>,
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    /// Requests by endpoint and status, `error` when no response was received.
+    requests: BTreeMap<(&'static str, String), u64>,
+    request_durations: BTreeMap<&'static str, Histogram>,
+    upload_bytes: Sum,
+    download_bytes: BTreeMap<&'static str, Sum>,
+    render_durations: Histogram,
+    cache_hits: u64,
+    cache_misses: u64,
+    retries: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Sum {
+    count: u64,
+    sum: u64,
+}
+
+#[derive(Debug, Default, Clone)]
+struct Histogram {
+    /// Observations per bucket of `DURATION_BUCKETS`, not cumulated.
+    buckets: [u64; DURATION_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if let Some(index) = DURATION_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[index] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+
+    fn write(&self, text: &mut String, name: &str, labels: &str) {
+        let separator = if labels.is_empty() { "" } else { "," };
+        let mut cumulated = 0;
+        for (bound, count) in DURATION_BUCKETS.iter().zip(self.buckets.iter()) {
+            cumulated += count;
+            let _ = writeln!(text, "{}_bucket{{{}{}le=\"{}\"}} {}", name, labels, separator, bound, cumulated);
+        }
+        let _ = writeln!(text, "{}_bucket{{{}{}le=\"+Inf\"}} {}", name, labels, separator, self.count);
+        let labels = if labels.is_empty() { String::new() } else { format!("{{{}}}", labels) };
+        let _ = writeln!(text, "{}_sum{} {}", name, labels, self.sum);
+        let _ = writeln!(text, "{}_count{} {}", name, labels, self.count);
+    }
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of requests to `endpoint` answered with `status`, or failed without
+    /// response when `status` is `None`.
+    pub fn requests(&self, endpoint: Endpoint, status: Option<StatusCode>) -> u64 {
+        let key = (endpoint.as_str(), status_label(status));
+        self.state.lock().unwrap().requests.get(&key).copied().unwrap_or(0)
+    }
+
+    /// Total size of the templates accepted by the Carbone API, in bytes.
+    pub fn uploaded_bytes(&self) -> u64 {
+        self.state.lock().unwrap().upload_bytes.sum
+    }
+
+    /// Total size of the files downloaded from `endpoint`, in bytes.
+    pub fn downloaded_bytes(&self, endpoint: Endpoint) -> u64 {
+        let state = self.state.lock().unwrap();
+        state.download_bytes.get(endpoint.as_str()).map(|sum| sum.sum).unwrap_or(0)
+    }
+
+    /// Number of successful renders.
+    pub fn renders(&self) -> u64 {
+        self.state.lock().unwrap().render_durations.count
+    }
+
+    pub fn cache_hits(&self) -> u64 {
+        self.state.lock().unwrap().cache_hits
+    }
+
+    pub fn cache_misses(&self) -> u64 {
+        self.state.lock().unwrap().cache_misses
+    }
+
+    pub fn retries(&self) -> u64 {
+        self.state.lock().unwrap().retries
+    }
+
+    /// Metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut text = String::new();
+
+        header(&mut text, "carbone_requests_total", "counter", "Requests to the Carbone API by endpoint and status.");
+        for ((endpoint, status), count) in &state.requests {
+            let _ = writeln!(
+                text,
+                "carbone_requests_total{{endpoint=\"{}\",status=\"{}\"}} {}",
+                endpoint, status, count
+            );
+        }
+
+        header(&mut text, "carbone_request_duration_seconds", "histogram", "Latency of the requests to the Carbone API.");
+        for (endpoint, histogram) in &state.request_durations {
+            let labels = format!("endpoint=\"{}\"", endpoint);
+            histogram.write(&mut text, "carbone_request_duration_seconds", &labels);
+        }
+
+        header(&mut text, "carbone_upload_bytes", "summary", "Size of the uploaded templates.");
+        let _ = writeln!(text, "carbone_upload_bytes_sum {}", state.upload_bytes.sum);
+        let _ = writeln!(text, "carbone_upload_bytes_count {}", state.upload_bytes.count);
+
+        header(&mut text, "carbone_download_bytes", "summary", "Size of the downloaded templates and reports.");
+        for (endpoint, sum) in &state.download_bytes {
+            let _ = writeln!(text, "carbone_download_bytes_sum{{endpoint=\"{}\"}} {}", endpoint, sum.sum);
+            let _ = writeln!(text, "carbone_download_bytes_count{{endpoint=\"{}\"}} {}", endpoint, sum.count);
+        }
+
+        header(&mut text, "carbone_render_duration_seconds", "histogram", "Duration of the successful renders.");
+        state.render_durations.write(&mut text, "carbone_render_duration_seconds", "");
+
+        let counters = [
+            ("carbone_cache_hits_total", "Renders served from the cache of the gateway.", state.cache_hits),
+            ("carbone_cache_misses_total", "Renders of the gateway sent to Carbone.", state.cache_misses),
+            ("carbone_retries_total", "Calls of a cluster sent again to another node.", state.retries),
+        ];
+        for (name, help, value) in counters {
+            header(&mut text, name, "counter", help);
+            let _ = writeln!(text, "{} {}", name, value);
+        }
+
+        text
+    }
+}
+
+impl MetricsRecorder for PrometheusMetrics {
+    fn record_request(&self, endpoint: Endpoint, status: Option<StatusCode>, elapsed: Duration) {
+        let mut state = self.state.lock().unwrap();
+        *state
+            .requests
+            .entry((endpoint.as_str(), status_label(status)))
+            .or_insert(0) += 1;
+        state
+            .request_durations
+            .entry(endpoint.as_str())
+            .or_default()
+            .observe(elapsed);
+    }
+
+    fn record_upload(&self, bytes: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.upload_bytes.count += 1;
+        state.upload_bytes.sum += bytes;
+    }
+
+    fn record_download(&self, endpoint: Endpoint, bytes: u64) {
+        let mut state = self.state.lock().unwrap();
+        let sum = state.download_bytes.entry(endpoint.as_str()).or_default();
+        sum.count += 1;
+        sum.sum += bytes;
+    }
+
+    fn record_render(&self, elapsed: Duration) {
+        self.state.lock().unwrap().render_durations.observe(elapsed);
+    }
+
+    fn record_cache(&self, hit: bool) {
+        let mut state = self.state.lock().unwrap();
+        if hit {
+            state.cache_hits += 1;
+        } else {
+            state.cache_misses += 1;
+        }
+    }
+
+    fn record_retry(&self) {
+        self.state.lock().unwrap().retries += 1;
+    }
+}
+
+fn status_label(status: Option<StatusCode>) -> String {
+    match status {
+        Some(status) => status.as_u16().to_string(),
+        None => "error".to_string(),
+    }
+}
+
+fn header(text: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(text, "# HELP {} {}", name, help);
+    let _ = writeln!(text, "# TYPE {} {}", name, kind);
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::errors::CarboneError;
+use crate::types::{Endpoint, Result};
+
+#[derive(Debug)]
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    fn take(&mut self, cost: f64) -> std::result::Result<(), Duration> {
+        self.refill(Instant::now());
+
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            Ok(())
+        } else {
+            let missing = cost - self.tokens;
+            Err(Duration::from_secs_f64(missing / self.refill_per_sec))
+        }
+    }
+}
+
+/// Client-side token bucket limiting the requests sent to the Carbone API.
+///
+/// Cloning a `RateLimiter` shares the bucket, so one limiter can be attached
+/// to several clients of the same process to respect a common plan quota.
+/// Each [`Endpoint`] consumes its own number of tokens (1 by default).
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::rate_limit::RateLimiter;
+/// use carbone_sdk_rust::types::Endpoint;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let rate_limiter = RateLimiter::per_minute(60)?
+///         .with_cost(Endpoint::Render, 2)?
+///         .with_cost(Endpoint::Status, 0)?;
+///
+///     assert!(rate_limiter.try_acquire(Endpoint::Upload).is_ok());
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+    costs: HashMap<Endpoint, u32>,
+}
+
+impl RateLimiter {
+    /// Create a limiter holding at most `capacity` tokens, refilled with
+    /// `refill` tokens every `period`. The bucket starts full.
+    pub fn new(capacity: u32, refill: u32, period: Duration) -> Result<Self> {
+        if capacity == 0 || refill == 0 || period.is_zero() {
+            return Err(CarboneError::Error(
+                "rate limiter capacity, refill and period must be greater than zero".to_string(),
+            ));
+        }
+
+        let bucket = Bucket {
+            capacity: capacity as f64,
+            tokens: capacity as f64,
+            refill_per_sec: refill as f64 / period.as_secs_f64(),
+            last_refill: Instant::now(),
+        };
+
+        Ok(Self {
+            bucket: Arc::new(Mutex::new(bucket)),
+            costs: HashMap::new(),
+        })
+    }
+
+    /// Create a limiter allowing `requests` requests per minute.
+    pub fn per_minute(requests: u32) -> Result<Self> {
+        Self::new(requests, requests, Duration::from_secs(60))
+    }
+
+    /// Set the number of tokens consumed by a request to `endpoint`.
+    pub fn with_cost(mut self, endpoint: Endpoint, cost: u32) -> Result<Self> {
+        let capacity = self.bucket.lock().unwrap().capacity;
+        if cost as f64 > capacity {
+            return Err(CarboneError::Error(format!(
+                "rate limiter cost {} of {:?} exceeds the capacity {}",
+                cost, endpoint, capacity
+            )));
+        }
+        self.costs.insert(endpoint, cost);
+        Ok(self)
+    }
+
+    /// Number of tokens consumed by a request to `endpoint`.
+    pub fn cost(&self, endpoint: Endpoint) -> u32 {
+        self.costs.get(&endpoint).copied().unwrap_or(1)
+    }
+
+    /// Take the tokens for `endpoint` without waiting.
+    ///
+    /// On failure, the duration to wait before the tokens are available is returned.
+    pub fn try_acquire(&self, endpoint: Endpoint) -> std::result::Result<(), Duration> {
+        let cost = self.cost(endpoint);
+        if cost == 0 {
+            return Ok(());
+        }
+        self.bucket.lock().unwrap().take(cost as f64)
+    }
+
+    /// Wait asynchronously until the tokens for `endpoint` are available.
+    pub async fn acquire(&self, endpoint: Endpoint) {
+        while let Err(wait) = self.try_acquire(endpoint) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Block the current thread until the tokens for `endpoint` are available.
+    pub fn acquire_blocking(&self, endpoint: Endpoint) {
+        while let Err(wait) = self.try_acquire(endpoint) {
+            std::thread::sleep(wait);
+        }
+    }
+}
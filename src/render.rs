@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
+#[cfg(any(feature = "batch", feature = "watch"))]
+use std::fs;
 use std::ops::Deref;
+#[cfg(any(feature = "batch", feature = "watch"))]
+use std::path::Path;
 
 use crate::types::*;
 
@@ -48,3 +52,15 @@ impl AsRef<str> for RenderId {
         self.0.as_ref()
     }
 }
+
+/// Write a report through a temporary file, so an interrupted batch never leaves
+/// a partial report which would be skipped when resuming.
+#[cfg(any(feature = "batch", feature = "watch"))]
+pub(crate) fn write_report(path: &Path, content: &[u8]) -> Result<()> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+
+    fs::write(&partial, content)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::template::TemplateId;
+
+/// Limits of the cache of rendered reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheOptions {
+    /// Maximum number of reports. `0` disables the cache.
+    pub max_entries: usize,
+    /// Maximum total size of the reports, in bytes. Larger reports are not cached.
+    pub max_bytes: usize,
+    /// Lifetime of a cached report, unlimited if `None`.
+    pub ttl: Option<Duration>,
+}
+
+impl Default for CacheOptions {
+    fn default() -> Self {
+        Self {
+            max_entries: 256,
+            max_bytes: 64 * 1024 * 1024,
+            ttl: Some(Duration::from_secs(3600)),
+        }
+    }
+}
+
+/// A rendered report, with the headers of its response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedReport {
+    pub content: Bytes,
+    pub content_type: String,
+    pub file_name: String,
+}
+
+#[derive(Debug)]
+struct Entry {
+    report: CachedReport,
+    inserted_at: Instant,
+    last_used: u64,
+}
+
+/// In-process cache of the rendered reports, evicting the least recently used ones.
+#[derive(Debug)]
+pub struct RenderCache {
+    options: CacheOptions,
+    entries: HashMap<String, Entry>,
+    bytes: usize,
+    clock: u64,
+}
+
+impl RenderCache {
+    pub fn new(options: CacheOptions) -> Self {
+        Self {
+            options,
+            entries: HashMap::new(),
+            bytes: 0,
+            clock: 0,
+        }
+    }
+
+    /// Key of a render: the template ID and the render options, whatever their formatting.
+    pub fn key(template_id: &TemplateId, render_options: &Value) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(template_id.as_str().as_bytes());
+        hasher.update(b"\n");
+        hasher.update(render_options.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub fn get(&mut self, key: &str) -> Option<CachedReport> {
+        let expired = match (self.entries.get(key), self.options.ttl) {
+            (None, _) => return None,
+            (Some(entry), Some(ttl)) => entry.inserted_at.elapsed() > ttl,
+            (Some(_), None) => false,
+        };
+
+        if expired {
+            self.remove(key);
+            return None;
+        }
+
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.clock;
+        Some(entry.report.clone())
+    }
+
+    pub fn insert(&mut self, key: String, report: CachedReport) {
+        let size = report.content.len();
+        if self.options.max_entries == 0 || size > self.options.max_bytes {
+            return;
+        }
+
+        self.remove(&key);
+        while self.entries.len() >= self.options.max_entries || self.bytes + size > self.options.max_bytes {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.remove(&oldest);
+        }
+
+        self.clock += 1;
+        self.bytes += size;
+        self.entries.insert(
+            key,
+            Entry {
+                report,
+                inserted_at: Instant::now(),
+                last_used: self.clock,
+            },
+        );
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.bytes -= entry.report.content.len();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Total size of the cached reports, in bytes.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::server::cache::RenderCache;
+
+/// Counters of the gateway, exposed in the Prometheus text format on `GET /metrics`.
+#[derive(Debug, Default)]
+pub struct GatewayMetrics {
+    responses: Mutex<BTreeMap<u16, u64>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    render_count: AtomicU64,
+    render_micros: AtomicU64,
+}
+
+impl GatewayMetrics {
+    pub(crate) fn record_response(&self, status: u16) {
+        *self.responses.lock().unwrap().entry(status).or_insert(0) += 1;
+    }
+
+    pub(crate) fn record_cache(&self, hit: bool) {
+        let counter = if hit { &self.cache_hits } else { &self.cache_misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_render(&self, elapsed: Duration) {
+        self.render_count.fetch_add(1, Ordering::Relaxed);
+        self.render_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    pub fn cache_misses(&self) -> u64 {
+        self.cache_misses.load(Ordering::Relaxed)
+    }
+
+    /// Metrics in the Prometheus text format.
+    pub fn render(&self, cache: &RenderCache) -> String {
+        let mut text = String::new();
+
+        text.push_str("# HELP carbone_gateway_responses_total Responses of the gateway by status code.\n");
+        text.push_str("# TYPE carbone_gateway_responses_total counter\n");
+        for (status, count) in self.responses.lock().unwrap().iter() {
+            let _ = writeln!(text, "carbone_gateway_responses_total{{status=\"{}\"}} {}", status, count);
+        }
+
+        let render_seconds = self.render_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let metrics = [
+            ("cache_hits_total", "counter", "Renders served from the cache.", self.cache_hits().to_string()),
+            ("cache_misses_total", "counter", "Renders sent to Carbone.", self.cache_misses().to_string()),
+            ("cache_entries", "gauge", "Reports in the cache.", cache.len().to_string()),
+            ("cache_bytes", "gauge", "Size of the reports in the cache.", cache.bytes().to_string()),
+            ("render_duration_seconds_sum", "counter", "Time spent rendering with Carbone.", render_seconds.to_string()),
+            (
+                "render_duration_seconds_count",
+                "counter",
+                "Successful renders with Carbone.",
+                self.render_count.load(Ordering::Relaxed).to_string(),
+            ),
+        ];
+        for (name, kind, help, value) in metrics {
+            let _ = writeln!(text, "# HELP carbone_gateway_{} {}", name, help);
+            let _ = writeln!(text, "# TYPE carbone_gateway_{} {}", name, kind);
+            let _ = writeln!(text, "carbone_gateway_{} {}", name, value);
+        }
+
+        text
+    }
+}
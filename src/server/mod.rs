@@ -0,0 +1,365 @@
+//! HTTP gateway exposing a simplified render API on top of the SDK.
+//!
+//! Services which can not use the SDK render a template of the gateway by name with
+//! `POST /render/{template-name}` and the render options as body, and receive the
+//! document. The gateway holds the Carbone API token, uploads the templates on demand
+//! and caches the rendered reports.
+//!
+//! Requires the `server` feature.
+
+pub mod cache;
+pub mod metrics;
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, AUTHORIZATION, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde_json::{json, Value};
+
+use crate::carbone::Carbone;
+use crate::errors::CarboneError;
+use crate::store::TemplateStore;
+use crate::types::{JsonData, Result};
+
+use cache::{CacheOptions, CachedReport, RenderCache};
+use metrics::GatewayMetrics;
+
+/// Options of the gateway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayOptions {
+    /// Keys accepted in the `Authorization: Bearer <key>` header of the internal callers.
+    /// Without keys, the render API is open to every caller reaching the gateway.
+    pub api_keys: Vec<String>,
+    pub cache: CacheOptions,
+    /// Payload used to compute the template IDs and sent with the uploads.
+    pub payload: Option<String>,
+    /// Maximum size of the render options sent by the callers, in bytes.
+    pub max_body_bytes: usize,
+}
+
+impl Default for GatewayOptions {
+    fn default() -> Self {
+        Self {
+            api_keys: Vec::new(),
+            cache: CacheOptions::default(),
+            payload: None,
+            max_body_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// The render gateway.
+///
+/// Routes:
+/// - `POST /render/{template-name}`: render a template of the store with the JSON render
+///   options of the body (`data`, `convertTo`, ...), and return the document.
+/// - `GET /metrics`: metrics in the Prometheus text format.
+/// - `GET /health`: `200` while the gateway runs.
+///
+/// The client must live for the whole program, e.g. with a leaked `Config`.
+/// The cache hits and misses are also reported to the `MetricsRecorder` of the client,
+/// if it has one.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::net::TcpListener;
+/// use std::sync::Arc;
+///
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::carbone::Carbone;
+/// use carbone_sdk_rust::server::{Gateway, GatewayOptions};
+/// use carbone_sdk_rust::store::FsTemplateStore;
+/// use carbone_sdk_rust::types::ApiJsonToken;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), CarboneError> {
+///
+///     let config: &'static Config = Box::leak(Box::new(Config::default()));
+///     let api_token: &'static ApiJsonToken =
+///         Box::leak(Box::new(ApiJsonToken::new(std::env::var("CARBONE_TOKEN").unwrap())?));
+///
+///     let carbone = Carbone::new(config, Some(api_token))?;
+///
+///     let options = GatewayOptions {
+///         api_keys: vec![std::env::var("GATEWAY_KEY").unwrap()],
+///         ..Default::default()
+///     };
+///     let gateway = Gateway::new(carbone, Arc::new(FsTemplateStore::new("templates")), options);
+///
+///     let listener = TcpListener::bind("127.0.0.1:3000")?;
+///     gateway.serve(listener, std::future::pending()).await
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Gateway {
+    carbone: Carbone<'static>,
+    store: Arc<dyn TemplateStore>,
+    options: GatewayOptions,
+    cache: Mutex<RenderCache>,
+    metrics: GatewayMetrics,
+}
+
+impl Gateway {
+    pub fn new(carbone: Carbone<'static>, store: Arc<dyn TemplateStore>, options: GatewayOptions) -> Self {
+        Self {
+            carbone,
+            store,
+            cache: Mutex::new(RenderCache::new(options.cache.clone())),
+            options,
+            metrics: GatewayMetrics::default(),
+        }
+    }
+
+    pub fn metrics(&self) -> &GatewayMetrics {
+        &self.metrics
+    }
+
+    /// Serve the requests of a listener until `shutdown` completes, then finish the
+    /// requests in progress.
+    pub async fn serve<F>(self, listener: TcpListener, shutdown: F) -> Result<()>
+    where
+        F: Future<Output = ()>,
+    {
+        listener.set_nonblocking(true)?;
+
+        let gateway = Arc::new(self);
+        let make_service = make_service_fn(move |_| {
+            let gateway = gateway.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let gateway = gateway.clone();
+                    async move { Ok::<_, Infallible>(gateway.handle(request).await) }
+                }))
+            }
+        });
+
+        Server::from_tcp(listener)
+            .map_err(|e| CarboneError::Error(e.to_string()))?
+            .serve(make_service)
+            .with_graceful_shutdown(shutdown)
+            .await
+            .map_err(|e| CarboneError::Error(e.to_string()))
+    }
+
+    /// Answer a request.
+    pub async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let response = match self.route(request).await {
+            Ok(response) => response,
+            Err((status, message)) => error_response(status, &message),
+        };
+
+        self.metrics.record_response(response.status().as_u16());
+        response
+    }
+
+    async fn route(&self, request: Request<Body>) -> std::result::Result<Response<Body>, (StatusCode, String)> {
+        let path = request.uri().path().to_string();
+
+        match (request.method(), path.as_str()) {
+            (&Method::GET, "/health") => Ok(json_response(StatusCode::OK, &json!({ "success": true }))),
+            (&Method::GET, "/metrics") => {
+                let text = self.metrics.render(&self.cache.lock().unwrap());
+                Ok(Response::builder()
+                    .header(CONTENT_TYPE, "text/plain; version=0.0.4")
+                    .body(Body::from(text))
+                    .unwrap())
+            }
+            (&Method::POST, _) if path.starts_with("/render/") => {
+                self.authorize(&request)?;
+
+                let name = percent_decode(&path["/render/".len()..])
+                    .filter(|name| name.split('/').all(|part| !matches!(part, "" | "." | "..")))
+                    .ok_or_else(|| (StatusCode::BAD_REQUEST, "invalid template name".to_string()))?;
+
+                let max_body_bytes = self.options.max_body_bytes;
+                let body = read_body(request.into_body(), max_body_bytes).await?;
+
+                self.render(&name, &body).await.map_err(|e| (error_status(&e), e.to_string()))
+            }
+            (_, "/health" | "/metrics") => Err(method_not_allowed()),
+            (_, _) if path.starts_with("/render/") => Err(method_not_allowed()),
+            _ => Err((StatusCode::NOT_FOUND, "not found".to_string())),
+        }
+    }
+
+    fn authorize(&self, request: &Request<Body>) -> std::result::Result<(), (StatusCode, String)> {
+        if self.options.api_keys.is_empty() {
+            return Ok(());
+        }
+
+        let key = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        match key {
+            Some(key) if self.options.api_keys.iter().any(|api_key| constant_time_eq(api_key, key)) => Ok(()),
+            _ => Err((StatusCode::UNAUTHORIZED, "missing or invalid API key".to_string())),
+        }
+    }
+
+    async fn render(&self, name: &str, body: &[u8]) -> Result<Response<Body>> {
+        let render_options: Value =
+            serde_json::from_slice(body).map_err(|_| CarboneError::RequestBodyNotWellFormedJsonError)?;
+        if !render_options.is_object() {
+            return Err(CarboneError::RequestBodyNotWellFormedJsonError);
+        }
+
+        let payload = self.options.payload.as_deref();
+        let template_file = self.store.template_file(name)?;
+        let template_id = template_file.generate_id(payload)?;
+
+        let key = RenderCache::key(&template_id, &render_options);
+        let cached = self.cache.lock().unwrap().get(&key);
+        self.metrics.record_cache(cached.is_some());
+        if let Some(metrics) = self.carbone.metrics() {
+            metrics.record_cache(cached.is_some());
+        }
+
+        if let Some(report) = cached {
+            return Ok(report_response(&report, template_id.as_str(), "hit"));
+        }
+
+        let start = Instant::now();
+        let content = self
+            .carbone
+            .generate_report(
+                template_file.file_name().to_string(),
+                template_file.content_bytes()?,
+                JsonData::new(render_options.to_string())?,
+                payload,
+            )
+            .await?;
+        self.metrics.record_render(start.elapsed());
+
+        let extension = match render_options.get("convertTo") {
+            Some(Value::String(format)) => Some(format.as_str()),
+            Some(Value::Object(options)) => options.get("formatName").and_then(|format| format.as_str()),
+            _ => None,
+        }
+        .or_else(|| template_file.extension())
+        .unwrap_or("bin")
+        .to_string();
+
+        let stem = template_file
+            .file_name()
+            .rsplit_once('.')
+            .map(|(stem, _)| stem)
+            .unwrap_or(template_file.file_name());
+
+        let report = CachedReport {
+            content,
+            content_type: mime_guess::from_ext(&extension).first_or_octet_stream().to_string(),
+            file_name: format!("{}.{}", stem, extension),
+        };
+        self.cache.lock().unwrap().insert(key, report.clone());
+
+        Ok(report_response(&report, template_id.as_str(), "miss"))
+    }
+}
+
+/// HTTP status of the response to a failed render.
+fn error_status(error: &CarboneError) -> StatusCode {
+    match error {
+        CarboneError::TemplateFileNotFound(_) => StatusCode::NOT_FOUND,
+        CarboneError::RequestBodyNotWellFormedJsonError
+        | CarboneError::EmptyString(_)
+        | CarboneError::DataValidation(_) => StatusCode::BAD_REQUEST,
+        CarboneError::RequestError(_) | CarboneError::NotReady(_) => StatusCode::SERVICE_UNAVAILABLE,
+        CarboneError::IoError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        _ => StatusCode::BAD_GATEWAY,
+    }
+}
+
+fn method_not_allowed() -> (StatusCode, String) {
+    (StatusCode::METHOD_NOT_ALLOWED, "method not allowed".to_string())
+}
+
+async fn read_body(mut body: Body, max_bytes: usize) -> std::result::Result<Vec<u8>, (StatusCode, String)> {
+    let too_large = || (StatusCode::PAYLOAD_TOO_LARGE, "request body too large".to_string());
+
+    if body.size_hint().lower() > max_bytes as u64 {
+        return Err(too_large());
+    }
+
+    let mut content = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+        if content.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        content.extend_from_slice(&chunk);
+    }
+
+    Ok(content)
+}
+
+fn report_response(report: &CachedReport, template_id: &str, cache_status: &'static str) -> Response<Body> {
+    let mut response = Response::new(Body::from(report.content.clone()));
+    let headers = response.headers_mut();
+
+    if let Ok(content_type) = HeaderValue::from_str(&report.content_type) {
+        headers.insert(CONTENT_TYPE, content_type);
+    }
+    if let Ok(disposition) = HeaderValue::from_str(&format!("inline; filename=\"{}\"", report.file_name)) {
+        headers.insert(CONTENT_DISPOSITION, disposition);
+    }
+    headers.insert(CONTENT_LENGTH, HeaderValue::from(report.content.len()));
+    if let Ok(template_id) = HeaderValue::from_str(template_id) {
+        headers.insert("x-carbone-template-id", template_id);
+    }
+    headers.insert("x-carbone-cache", HeaderValue::from_static(cache_status));
+
+    response
+}
+
+fn json_response(status: StatusCode, value: &Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(value.to_string()))
+        .unwrap()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    json_response(status, &json!({ "success": false, "error": message }))
+}
+
+/// Decode the `%XX` escapes of a path, e.g. `invoices%2Finvoice.docx`.
+fn percent_decode(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+/// Compare two keys in a time independent of the position of the first difference.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a
+            .bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// Delay between two status requests of `wait_until_ready`.
+pub(crate) const READY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Status of the Carbone API, as returned by `GET /status`.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Status {
+    pub success: bool,
+    #[serde(default)]
+    pub code: Option<u16>,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+impl Status {
+    /// Major version of the Carbone server, e.g. `"4"` for `"4.22.11"`.
+    pub fn major_version(&self) -> Option<&str> {
+        self.version
+            .as_deref()
+            .and_then(|version| version.split('.').next())
+            .filter(|major| !major.is_empty())
+    }
+
+    /// Check that the server major version matches the `api_version` of the config.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use carbone_sdk_rust::config::Config;
+    /// use carbone_sdk_rust::status::Status;
+    ///
+    /// let status: Status = serde_json::from_str(r#"{
+    ///     "success": true,
+    ///     "code": 200,
+    ///     "message": "OK",
+    ///     "version": "4.22.11"
+    /// }"#).unwrap();
+    ///
+    /// let config: Config = Default::default();
+    ///
+    /// assert!(status.is_compatible(&config));
+    /// ```
+    pub fn is_compatible(&self, config: &Config) -> bool {
+        let expected = config.api_version.as_str().split('.').next();
+        self.major_version().is_some() && self.major_version() == expected
+    }
+}
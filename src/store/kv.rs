@@ -0,0 +1,106 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use redb::{Database, ReadableTable, TableDefinition};
+
+use crate::errors::CarboneError;
+use crate::store::TemplateStore;
+use crate::types::Result;
+
+const TEMPLATES: TableDefinition<&str, &[u8]> = TableDefinition::new("templates");
+
+/// Templates stored in an embedded key-value database (redb), in a single file.
+///
+/// Requires the `kv-store` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::store::{KvTemplateStore, TemplateStore};
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let store = KvTemplateStore::open("templates.redb")?;
+///
+///     store.put("invoice.docx", &std::fs::read("invoice.docx")?)?;
+///
+///     Ok(())
+/// }
+/// ```
+pub struct KvTemplateStore {
+    path: PathBuf,
+    db: Database,
+}
+
+impl KvTemplateStore {
+    /// Open the database, creating it if the file does not exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let db = Database::create(&path).map_err(kv_error)?;
+
+        let write = db.begin_write().map_err(kv_error)?;
+        write.open_table(TEMPLATES).map_err(kv_error)?;
+        write.commit().map_err(kv_error)?;
+
+        Ok(Self { path, db })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Debug for KvTemplateStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KvTemplateStore")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl TemplateStore for KvTemplateStore {
+    fn get(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let read = self.db.begin_read().map_err(kv_error)?;
+        let table = read.open_table(TEMPLATES).map_err(kv_error)?;
+        let content = table.get(name).map_err(kv_error)?;
+        Ok(content.map(|content| content.value().to_vec()))
+    }
+
+    fn put(&self, name: &str, content: &[u8]) -> Result<()> {
+        let write = self.db.begin_write().map_err(kv_error)?;
+        {
+            let mut table = write.open_table(TEMPLATES).map_err(kv_error)?;
+            table.insert(name, content).map_err(kv_error)?;
+        }
+        write.commit().map_err(kv_error)
+    }
+
+    fn remove(&self, name: &str) -> Result<bool> {
+        let write = self.db.begin_write().map_err(kv_error)?;
+        let removed = {
+            let mut table = write.open_table(TEMPLATES).map_err(kv_error)?;
+            let removed = table.remove(name).map_err(kv_error)?;
+            removed.is_some()
+        };
+        write.commit().map_err(kv_error)?;
+        Ok(removed)
+    }
+
+    fn names(&self) -> Result<Vec<String>> {
+        let read = self.db.begin_read().map_err(kv_error)?;
+        let table = read.open_table(TEMPLATES).map_err(kv_error)?;
+        table
+            .iter()
+            .map_err(kv_error)?
+            .map(|entry| {
+                let (name, _) = entry.map_err(kv_error)?;
+                Ok(name.value().to_string())
+            })
+            .collect()
+    }
+}
+
+fn kv_error<E: fmt::Display>(e: E) -> CarboneError {
+    CarboneError::Error(format!("template store: {}", e))
+}
@@ -0,0 +1,225 @@
+//! Storage backends resolving templates by logical name, e.g. `invoices/invoice.docx`.
+//!
+//! Clients configured with `with_template_store` render templates by name with
+//! `generate_report_with_template_name`, and `sync::sync_store` uploads the content of a store.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::errors::CarboneError;
+use crate::template::TemplateFile;
+use crate::types::Result;
+
+#[cfg(feature = "kv-store")]
+mod kv;
+#[cfg(feature = "kv-store")]
+pub use kv::KvTemplateStore;
+
+/// A storage of templates, read by logical name.
+///
+/// Names use `/` separators. Implementations must be shareable between threads,
+/// as clients keep them in an `Arc`.
+pub trait TemplateStore: fmt::Debug + Send + Sync {
+    /// Content of a template, `None` if the store has no template with this name.
+    fn get(&self, name: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Add or replace a template.
+    fn put(&self, name: &str, content: &[u8]) -> Result<()>;
+
+    /// Remove a template, returning whether it existed.
+    fn remove(&self, name: &str) -> Result<bool>;
+
+    /// Names of the templates, sorted.
+    fn names(&self) -> Result<Vec<String>>;
+
+    /// In-memory template file of a template, or `TemplateFileNotFound`.
+    fn template_file(&self, name: &str) -> Result<TemplateFile> {
+        match self.get(name)? {
+            Some(content) => TemplateFile::from_bytes(name, content),
+            None => Err(CarboneError::TemplateFileNotFound(name.to_string())),
+        }
+    }
+}
+
+/// Templates stored in a folder, named by their path relative to the folder.
+///
+/// Hidden files and the lock files of office suites (`~$name.docx`) are not listed.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::store::{FsTemplateStore, TemplateStore};
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let store = FsTemplateStore::new("templates");
+///
+///     let template_file = store.template_file("invoices/invoice.docx")?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsTemplateStore {
+    root: PathBuf,
+}
+
+impl FsTemplateStore {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Path of a template, rejecting the names going out of the folder.
+    pub fn path(&self, name: &str) -> Result<PathBuf> {
+        let relative = Path::new(name);
+        let is_valid = !name.is_empty()
+            && relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+
+        if !is_valid {
+            return Err(CarboneError::Error(format!("invalid template name: {}", name)));
+        }
+
+        Ok(self.root.join(relative))
+    }
+}
+
+impl TemplateStore for FsTemplateStore {
+    fn get(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(name)?;
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Ok(Some(fs::read(path)?))
+    }
+
+    fn put(&self, name: &str, content: &[u8]) -> Result<()> {
+        let path = self.path(name)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    fn remove(&self, name: &str) -> Result<bool> {
+        let path = self.path(name)?;
+        if !path.is_file() {
+            return Ok(false);
+        }
+        fs::remove_file(path)?;
+        Ok(true)
+    }
+
+    fn names(&self) -> Result<Vec<String>> {
+        if !self.root.is_dir() {
+            return Err(CarboneError::FileNotFound(
+                self.root.to_string_lossy().into_owned(),
+            ));
+        }
+
+        let mut files = Vec::new();
+        collect_files(&self.root, &mut files)?;
+
+        let mut names: Vec<String> = files
+            .iter()
+            .map(|file| {
+                file.strip_prefix(&self.root)
+                    .unwrap_or(file)
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .collect();
+        names.sort();
+
+        Ok(names)
+    }
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        if name.starts_with('.') || name.starts_with("~$") {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Templates kept in memory, e.g. loaded at startup from a database.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::store::{MemoryTemplateStore, TemplateStore};
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let store = MemoryTemplateStore::default()
+///         .with_template("letter.html", b"<p>{d.firstname}</p>".to_vec());
+///
+///     assert_eq!(store.names()?, vec!["letter.html".to_string()]);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct MemoryTemplateStore {
+    templates: RwLock<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryTemplateStore {
+    pub fn with_template<T: Into<String>>(self, name: T, content: Vec<u8>) -> Self {
+        self.templates
+            .write()
+            .unwrap()
+            .insert(name.into(), content);
+        self
+    }
+}
+
+impl TemplateStore for MemoryTemplateStore {
+    fn get(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        let templates = self.templates.read().unwrap();
+        Ok(templates.get(name).cloned())
+    }
+
+    fn put(&self, name: &str, content: &[u8]) -> Result<()> {
+        let mut templates = self.templates.write().unwrap();
+        templates.insert(name.to_string(), content.to_vec());
+        Ok(())
+    }
+
+    fn remove(&self, name: &str) -> Result<bool> {
+        let mut templates = self.templates.write().unwrap();
+        Ok(templates.remove(name).is_some())
+    }
+
+    fn names(&self) -> Result<Vec<String>> {
+        let templates = self.templates.read().unwrap();
+        Ok(templates.keys().cloned().collect())
+    }
+}
@@ -0,0 +1,524 @@
+//! Sync of a local templates folder or of a template store with the Carbone API.
+//!
+//! `sync_templates` and `sync_store` work with any `CarboneApi`, and their `_blocking`
+//! versions with any `CarboneApiBlocking`.
+//!
+//! Requires the `sync` feature.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::api::CarboneApi;
+#[cfg(feature = "blocking")]
+use crate::api::CarboneApiBlocking;
+use crate::carbone_response::TemplateList;
+use crate::errors::CarboneError;
+use crate::store::{FsTemplateStore, TemplateStore};
+#[cfg(feature = "blocking")]
+use crate::template::TemplateSource;
+use crate::template::{TemplateFile, TemplateId, TemplateListQuery, TEMPLATE_LISTING_UNSUPPORTED};
+use crate::types::Result;
+
+/// Options of `sync_templates`.
+///
+/// When `manifest_path` points to an existing manifest, the local templates are
+/// compared with it; otherwise they are compared with the templates stored by the
+/// Carbone API, listed with the `GET /templates` route of the API v4. The new
+/// manifest is written to `manifest_path` after the sync.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncOptions {
+    /// Compute the changes without uploading, deleting or writing the manifest.
+    pub dry_run: bool,
+    /// Delete the templates of the previous manifest no longer matching a local file.
+    /// Requires an existing manifest at `manifest_path`.
+    pub delete_orphans: bool,
+    pub manifest_path: Option<PathBuf>,
+    /// Payload used to compute the template IDs and sent with the uploads.
+    pub payload: Option<String>,
+}
+
+/// Map of the template paths, relative to the synced folder, to their template IDs.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::sync::Manifest;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let manifest = Manifest::load("templates/carbone-manifest.json")?;
+///
+///     let template_id = manifest.get("invoices/invoice.docx");
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Manifest(BTreeMap<String, TemplateId>);
+
+impl Manifest {
+    /// Load a manifest, or return an empty one if the file does not exist.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| {
+            CarboneError::ParseError(path.to_string_lossy().into_owned(), e.to_string())
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| CarboneError::Error(e.to_string()))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    pub fn get(&self, path: &str) -> Option<&TemplateId> {
+        self.0.get(path)
+    }
+
+    pub fn insert(&mut self, path: String, template_id: TemplateId) {
+        self.0.insert(path, template_id);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &TemplateId)> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn template_ids(&self) -> HashSet<String> {
+        self.0.values().map(|id| id.as_str().to_string()).collect()
+    }
+}
+
+/// A template file and its template ID.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncEntry {
+    /// Path relative to the synced folder, with `/` separators.
+    pub path: String,
+    pub template_id: TemplateId,
+}
+
+/// Outcome of `sync_templates`. In dry-run mode, it describes what would be done.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub dry_run: bool,
+    pub uploaded: Vec<SyncEntry>,
+    pub unchanged: Vec<SyncEntry>,
+    pub deleted: Vec<SyncEntry>,
+    pub manifest: Manifest,
+}
+
+impl SyncReport {
+    fn new(options: &SyncOptions, unchanged: Vec<SyncEntry>) -> Self {
+        Self {
+            dry_run: options.dry_run,
+            unchanged,
+            ..Default::default()
+        }
+    }
+
+    /// Build the new manifest and write it unless in dry-run mode.
+    fn finish(mut self, options: &SyncOptions) -> Result<Self> {
+        for entry in self.uploaded.iter().chain(self.unchanged.iter()) {
+            self.manifest
+                .insert(entry.path.clone(), entry.template_id.clone());
+        }
+
+        if !self.dry_run {
+            if let Some(path) = &options.manifest_path {
+                self.manifest.save(path)?;
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+/// Upload the templates of a local folder which are unknown to the Carbone API
+/// (or to the manifest), optionally delete the orphans, and write the manifest
+/// mapping the file paths to their template IDs.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::PathBuf;
+///
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::carbone::Carbone;
+/// use carbone_sdk_rust::sync::{sync_templates, SyncOptions};
+/// use carbone_sdk_rust::types::ApiJsonToken;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), CarboneError> {
+///
+///     let config: Config = Default::default();
+///     let api_token = ApiJsonToken::new(std::env::var("CARBONE_TOKEN").unwrap())?;
+///
+///     let carbone = Carbone::new(&config, Some(&api_token))?;
+///
+///     let options = SyncOptions {
+///         delete_orphans: true,
+///         manifest_path: Some(PathBuf::from("carbone-manifest.json")),
+///         ..Default::default()
+///     };
+///
+///     let report = sync_templates(&carbone, "templates", &options).await?;
+///
+///     for entry in report.uploaded.iter() {
+///         println!("uploaded {} as {}", entry.path, entry.template_id.as_str());
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub async fn sync_templates<C, P>(carbone: &C, dir: P, options: &SyncOptions) -> Result<SyncReport>
+where
+    C: CarboneApi + ?Sized,
+    P: AsRef<Path>,
+{
+    let local = scan_templates(dir.as_ref(), options)?;
+    sync_local_templates(carbone, local, options).await
+}
+
+/// Same as [`sync_templates`] with the templates of a store, named by their store name
+/// in the manifest.
+pub async fn sync_store<C: CarboneApi + ?Sized>(
+    carbone: &C,
+    template_store: &dyn TemplateStore,
+    options: &SyncOptions,
+) -> Result<SyncReport> {
+    let local = scan_store(template_store, options)?;
+    sync_local_templates(carbone, local, options).await
+}
+
+/// Blocking version of [`sync_templates`].
+///
+/// Requires the `blocking` feature.
+#[cfg(feature = "blocking")]
+pub fn sync_templates_blocking<C, P>(carbone: &C, dir: P, options: &SyncOptions) -> Result<SyncReport>
+where
+    C: CarboneApiBlocking + ?Sized,
+    P: AsRef<Path>,
+{
+    let local = scan_templates(dir.as_ref(), options)?;
+    sync_local_templates_blocking(carbone, local, options)
+}
+
+/// Blocking version of [`sync_store`].
+///
+/// Requires the `blocking` feature.
+#[cfg(feature = "blocking")]
+pub fn sync_store_blocking<C: CarboneApiBlocking + ?Sized>(
+    carbone: &C,
+    template_store: &dyn TemplateStore,
+    options: &SyncOptions,
+) -> Result<SyncReport> {
+    let local = scan_store(template_store, options)?;
+    sync_local_templates_blocking(carbone, local, options)
+}
+
+async fn sync_local_templates<C: CarboneApi + ?Sized>(
+    carbone: &C,
+    local: Vec<LocalTemplate>,
+    options: &SyncOptions,
+) -> Result<SyncReport> {
+    let previous = options.previous_manifest()?;
+
+    let known_ids = match &previous {
+        Some(manifest) => manifest.template_ids(),
+        None => {
+            let mut query = remote_listing_query();
+            let mut ids = HashSet::new();
+            loop {
+                let page = carbone.list_templates(&query).await.map_err(remote_listing_error)?;
+                match insert_template_ids(&mut ids, page) {
+                    Some(cursor) => query.cursor = Some(cursor),
+                    None => break ids,
+                }
+            }
+        }
+    };
+
+    let plan = SyncPlan::new(local, &known_ids, previous.as_ref());
+    let payload = options.payload.as_deref();
+    let mut report = SyncReport::new(options, plan.unchanged);
+
+    for template in plan.uploads {
+        let template_id = if options.dry_run {
+            template.template_id.clone()
+        } else {
+            let content = template.file.content.clone().unwrap_or_default();
+            carbone
+                .upload_template(template.file_name(), content, payload)
+                .await?
+        };
+        report.uploaded.push(SyncEntry {
+            path: template.path,
+            template_id,
+        });
+    }
+
+    if options.delete_orphans {
+        for orphan in plan.orphans {
+            if !options.dry_run {
+                orphan_deleted(carbone.delete_template(orphan.template_id.clone()).await)?;
+            }
+            report.deleted.push(orphan);
+        }
+    }
+
+    report.finish(options)
+}
+
+#[cfg(feature = "blocking")]
+fn sync_local_templates_blocking<C: CarboneApiBlocking + ?Sized>(
+    carbone: &C,
+    local: Vec<LocalTemplate>,
+    options: &SyncOptions,
+) -> Result<SyncReport> {
+    let previous = options.previous_manifest()?;
+
+    let known_ids = match &previous {
+        Some(manifest) => manifest.template_ids(),
+        None => {
+            let mut query = remote_listing_query();
+            let mut ids = HashSet::new();
+            loop {
+                let page = carbone.list_templates(&query).map_err(remote_listing_error)?;
+                match insert_template_ids(&mut ids, page) {
+                    Some(cursor) => query.cursor = Some(cursor),
+                    None => break ids,
+                }
+            }
+        }
+    };
+
+    let plan = SyncPlan::new(local, &known_ids, previous.as_ref());
+    let payload = options.payload.as_deref();
+    let mut report = SyncReport::new(options, plan.unchanged);
+
+    for template in plan.uploads {
+        let template_id = if options.dry_run {
+            template.template_id.clone()
+        } else {
+            carbone.upload_template(TemplateSource::from(&template.file), payload)?
+        };
+        report.uploaded.push(SyncEntry {
+            path: template.path,
+            template_id,
+        });
+    }
+
+    if options.delete_orphans {
+        for orphan in plan.orphans {
+            if !options.dry_run {
+                orphan_deleted(carbone.delete_template(orphan.template_id.clone()))?;
+            }
+            report.deleted.push(orphan);
+        }
+    }
+
+    report.finish(options)
+}
+
+/// Query of the stored templates and of all their versions, as a local template may
+/// match a version which is not deployed.
+fn remote_listing_query() -> TemplateListQuery {
+    TemplateListQuery {
+        include_versions: true,
+        ..Default::default()
+    }
+}
+
+/// Add the template and version IDs of a page, and return the cursor of the next page.
+fn insert_template_ids(ids: &mut HashSet<String>, page: TemplateList) -> Option<String> {
+    for template in page.templates {
+        ids.insert(template.id.as_str().to_string());
+        if let Some(version_id) = template.version_id {
+            ids.insert(version_id.as_str().to_string());
+        }
+    }
+
+    match page.next_cursor {
+        Some(cursor) if page.has_more => Some(cursor),
+        _ => None,
+    }
+}
+
+#[derive(Debug)]
+struct LocalTemplate {
+    pub path: String,
+    pub file: TemplateFile,
+    pub template_id: TemplateId,
+}
+
+impl LocalTemplate {
+    fn new(path: String, file: TemplateFile, options: &SyncOptions) -> Result<Self> {
+        let template_id = file.generate_id(options.payload.as_deref())?;
+        Ok(Self {
+            path,
+            file,
+            template_id,
+        })
+    }
+
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+}
+
+#[derive(Debug)]
+struct SyncPlan {
+    pub uploads: Vec<LocalTemplate>,
+    pub unchanged: Vec<SyncEntry>,
+    pub orphans: Vec<SyncEntry>,
+}
+
+impl SyncPlan {
+    /// Split the local templates between the ones to upload and the ones already
+    /// known, and find the templates of the previous manifest without a local file.
+    pub fn new(
+        local: Vec<LocalTemplate>,
+        known_ids: &HashSet<String>,
+        previous: Option<&Manifest>,
+    ) -> Self {
+        let local_ids: HashSet<String> = local
+            .iter()
+            .map(|template| template.template_id.as_str().to_string())
+            .collect();
+
+        let mut uploads = Vec::new();
+        let mut unchanged = Vec::new();
+
+        for template in local {
+            if known_ids.contains(template.template_id.as_str()) {
+                unchanged.push(SyncEntry {
+                    path: template.path,
+                    template_id: template.template_id,
+                });
+            } else {
+                uploads.push(template);
+            }
+        }
+
+        let orphans = previous
+            .map(|manifest| {
+                manifest
+                    .iter()
+                    .filter(|(_, template_id)| !local_ids.contains(template_id.as_str()))
+                    .map(|(path, template_id)| SyncEntry {
+                        path: path.clone(),
+                        template_id: template_id.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            uploads,
+            unchanged,
+            orphans,
+        }
+    }
+}
+
+impl SyncOptions {
+    /// Previous manifest, if `manifest_path` points to an existing file.
+    ///
+    /// Fails when `delete_orphans` is set without it, as the orphans are the templates
+    /// of the previous manifest.
+    fn previous_manifest(&self) -> Result<Option<Manifest>> {
+        match &self.manifest_path {
+            Some(path) if path.exists() => Ok(Some(Manifest::load(path)?)),
+            _ if self.delete_orphans => Err(CarboneError::Error(
+                "delete_orphans requires the manifest of a previous sync at manifest_path".to_string(),
+            )),
+            _ => Ok(None),
+        }
+    }
+}
+
+const REMOTE_COMPARISON_UNSUPPORTED: &str =
+    "comparing with the templates of the Carbone API requires the API v4 (GET /templates), set manifest_path to sync with an older API";
+
+/// Error of `GET /templates`, explaining the `404` of the servers older than the API v4
+/// and the refusal of the clients configured for them.
+fn remote_listing_error(error: CarboneError) -> CarboneError {
+    match error {
+        CarboneError::HttpError { status_code, .. } if status_code == StatusCode::NOT_FOUND => {
+            CarboneError::Error(REMOTE_COMPARISON_UNSUPPORTED.to_string())
+        }
+        CarboneError::Error(message) if message == TEMPLATE_LISTING_UNSUPPORTED => {
+            CarboneError::Error(REMOTE_COMPARISON_UNSUPPORTED.to_string())
+        }
+        error => error,
+    }
+}
+
+/// Outcome of the deletion of an orphan: a template the server no longer stores (`404`)
+/// was already deleted, e.g. by a previous sync which failed before writing its manifest.
+fn orphan_deleted(result: Result<bool>) -> Result<()> {
+    match result {
+        Ok(_) => Ok(()),
+        Err(CarboneError::HttpError { status_code, .. }) if status_code == StatusCode::NOT_FOUND => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Walk `dir` and compute the template ID of every file, skipping the manifest,
+/// hidden files and the lock files of office suites (`~$name.docx`, `.~lock.name.odt#`).
+fn scan_templates(dir: &Path, options: &SyncOptions) -> Result<Vec<LocalTemplate>> {
+    let store = FsTemplateStore::new(dir);
+
+    let manifest_path = options
+        .manifest_path
+        .as_ref()
+        .and_then(|path| path.canonicalize().ok());
+
+    let mut templates = Vec::new();
+    for name in store.names()? {
+        let file_path = store.path(&name)?;
+        if manifest_path.is_some() && file_path.canonicalize().ok() == manifest_path {
+            continue;
+        }
+
+        let content = fs::read(&file_path)?;
+        let file = TemplateFile::new(file_path.to_string_lossy().into_owned(), Some(content))?;
+        templates.push(LocalTemplate::new(name, file, options)?);
+    }
+
+    Ok(templates)
+}
+
+/// Read every template of a store and compute its template ID.
+fn scan_store(store: &dyn TemplateStore, options: &SyncOptions) -> Result<Vec<LocalTemplate>> {
+    store
+        .names()?
+        .into_iter()
+        .map(|name| {
+            let file = store.template_file(&name)?;
+            LocalTemplate::new(name, file, options)
+        })
+        .collect()
+}
@@ -0,0 +1,751 @@
+//! Offline inspection of the Carbone markers used by a template.
+//!
+//! Office Open XML (DOCX, XLSX, PPTX) and OpenDocument (ODT, ODS, ODP) templates
+//! are unzipped and the text of each paragraph is scanned, so markers split over
+//! several runs by the editor are found too. Other templates (HTML, XML, CSV, TXT,
+//! Markdown...) are scanned line by line.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Cursor, Read};
+
+use crate::errors::CarboneError;
+use crate::template::TemplateFile;
+use crate::types::Result;
+
+/// Kind of a Carbone marker, given by its prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarkerKind {
+    /// `{d.path}`: data of the report.
+    Data,
+    /// `{c.path}`: complement of the report.
+    Complement,
+    /// `{t(text)}`: translation.
+    Translation,
+    /// `{#alias = d.path}`: alias definition.
+    AliasDefinition,
+    /// `{$alias}`: alias usage.
+    Alias,
+    /// `{o.option}`: rendering option set in the template.
+    Option,
+}
+
+/// Where a marker was found in a template.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarkerLocation {
+    /// Name of the template file.
+    pub file: String,
+    /// Entry of the zip archive (`word/document.xml`, `content.xml`...), for office templates.
+    pub part: Option<String>,
+    /// Sheet name, for spreadsheets.
+    pub sheet: Option<String>,
+    /// Cell reference (`B2`), for spreadsheets.
+    pub cell: Option<String>,
+    /// 1-based index of the paragraph in the part, for office templates.
+    pub paragraph: Option<usize>,
+    /// 1-based line number, for text templates.
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for MarkerLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file)?;
+        if let Some(part) = &self.part {
+            write!(f, ":{}", part)?;
+        }
+        if let Some(sheet) = &self.sheet {
+            write!(f, " sheet {}", sheet)?;
+        }
+        if let Some(cell) = &self.cell {
+            write!(f, " cell {}", cell)?;
+        }
+        if let Some(paragraph) = self.paragraph {
+            write!(f, " paragraph {}", paragraph)?;
+        }
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// A Carbone marker found in a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    /// Full marker with its braces, e.g. `{d.date:formatD(YYYY)}`.
+    pub text: String,
+    pub kind: MarkerKind,
+    pub location: MarkerLocation,
+    /// The marker is spread over several runs (formatted spans) of an office document,
+    /// usually because the editor inserted spell-check or formatting tags inside it.
+    pub split_across_runs: bool,
+}
+
+impl Marker {
+    /// Content of the marker without its braces.
+    pub fn content(&self) -> &str {
+        &self.text[1..self.text.len() - 1]
+    }
+
+    /// Path of the marker before the formatters, e.g. `d.products[i].name`.
+    pub fn path(&self) -> &str {
+        let content = self.content();
+        let end = find_outside_parentheses(content, ':').unwrap_or(content.len());
+        content[..end].trim()
+    }
+
+    /// Formatters of the marker, e.g. `["formatD(YYYY)", "upperCase"]`.
+    pub fn formatters(&self) -> Vec<&str> {
+        let content = self.content();
+        let mut formatters = Vec::new();
+        let mut rest = match find_outside_parentheses(content, ':') {
+            Some(start) => &content[start + 1..],
+            None => return formatters,
+        };
+
+        loop {
+            match find_outside_parentheses(rest, ':') {
+                Some(end) => {
+                    formatters.push(rest[..end].trim());
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    formatters.push(rest.trim());
+                    return formatters;
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn find_outside_parentheses(s: &str, needle: char) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match c {
+            '\'' | '"' if quote == Some(c) => quote = None,
+            '\'' | '"' if quote.is_none() => quote = Some(c),
+            '(' if quote.is_none() => depth += 1,
+            ')' if quote.is_none() && depth > 0 => depth -= 1,
+            _ if c == needle && depth == 0 && quote.is_none() => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Extract the markers of a template file.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::template::TemplateFile;
+/// use carbone_sdk_rust::template::inspect::{inspect, MarkerKind};
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let template_file = TemplateFile::new("invoice.docx".to_string(), None)?;
+///
+///     for marker in inspect(&template_file)? {
+///         if marker.kind == MarkerKind::Data {
+///             println!("{} at {}", marker.path(), marker.location);
+///         }
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub fn inspect(template_file: &TemplateFile) -> Result<Vec<Marker>> {
+    let content = template_file.content_bytes()?;
+    inspect_bytes(template_file.path_as_str(), &content)
+}
+
+/// Extract the markers of a template given as bytes, `file_name` being reported in the locations.
+pub fn inspect_bytes(file_name: &str, content: &[u8]) -> Result<Vec<Marker>> {
+    let file = file_name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(file_name)
+        .to_string();
+
+    if content.starts_with(b"PK\x03\x04") {
+        inspect_archive(&file, content)
+    } else {
+        let text = std::str::from_utf8(content).map_err(|_| {
+            CarboneError::ParseError(file.clone(), "unsupported binary template".to_string())
+        })?;
+        Ok(inspect_text(&file, text))
+    }
+}
+
+fn inspect_text(file: &str, text: &str) -> Vec<Marker> {
+    let mut markers = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let location = MarkerLocation {
+            file: file.to_string(),
+            line: Some(index + 1),
+            ..Default::default()
+        };
+        markers.extend(find_markers(line, &location));
+    }
+    markers
+}
+
+/// Find the markers of a text. Braces not starting with a Carbone prefix
+/// (CSS rules, JSON...) are ignored.
+pub(crate) fn find_markers(text: &str, location: &MarkerLocation) -> Vec<Marker> {
+    find_marker_ranges(text)
+        .into_iter()
+        .map(|(start, end, kind)| Marker {
+            text: text[start..end].to_string(),
+            kind,
+            location: location.clone(),
+            split_across_runs: false,
+        })
+        .collect()
+}
+
+/// Byte ranges and kinds of the markers of a text.
+pub(crate) fn find_marker_ranges(text: &str) -> Vec<(usize, usize, MarkerKind)> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+
+    while let Some(start) = text[offset..].find('{') {
+        let start = offset + start;
+        let end = match text[start + 1..].find(['}', '{']) {
+            Some(end) if text.as_bytes()[start + 1 + end] == b'}' => start + 1 + end,
+            Some(end) => {
+                offset = start + 1 + end;
+                continue;
+            }
+            None => break,
+        };
+
+        if let Some(kind) = marker_kind(&text[start + 1..end]) {
+            ranges.push((start, end + 1, kind));
+        }
+        offset = end + 1;
+    }
+
+    ranges
+}
+
+fn marker_kind(content: &str) -> Option<MarkerKind> {
+    let content = content.trim_start();
+    let mut chars = content.chars();
+    let first = chars.next()?;
+    let second = chars.next();
+
+    let is_path_start = |c: Option<char>| matches!(c, None | Some('.' | '[' | ':' | ' '));
+
+    match first {
+        'd' if is_path_start(second) => Some(MarkerKind::Data),
+        'c' if is_path_start(second) => Some(MarkerKind::Complement),
+        'o' if second == Some('.') => Some(MarkerKind::Option),
+        't' if second == Some('(') => Some(MarkerKind::Translation),
+        '#' => Some(MarkerKind::AliasDefinition),
+        '$' => Some(MarkerKind::Alias),
+        _ => None,
+    }
+}
+
+fn inspect_archive(file: &str, content: &[u8]) -> Result<Vec<Marker>> {
+    let zip_error =
+        |e: zip::result::ZipError| CarboneError::ParseError(file.to_string(), e.to_string());
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(content)).map_err(zip_error)?;
+
+    let mut names: Vec<String> = archive
+        .file_names()
+        .filter(|name| is_content_part(name))
+        .map(|name| name.to_string())
+        .collect();
+    names.sort_by_key(|name| part_order(name));
+
+    let mut to_read = names.clone();
+    if archive.file_names().any(|name| name == WORKBOOK_RELS) {
+        to_read.push(WORKBOOK_RELS.to_string());
+    }
+
+    let mut parts = HashMap::new();
+    for name in to_read.iter() {
+        let mut xml = String::new();
+        archive
+            .by_name(name)
+            .map_err(zip_error)?
+            .read_to_string(&mut xml)
+            .map_err(|e| CarboneError::ParseError(name.clone(), e.to_string()))?;
+        parts.insert(name.clone(), xml);
+    }
+
+    let workbook = Workbook::new(&parts);
+    let mut markers = Vec::new();
+    let mut shared_strings = Vec::new();
+
+    for name in names.iter() {
+        let part_markers = scan_part(file, name, &parts[name], &workbook);
+        if name == "xl/sharedStrings.xml" {
+            shared_strings = part_markers;
+        } else {
+            markers.extend(part_markers);
+        }
+    }
+
+    // Markers of the shared strings are reported in every cell using the string.
+    for marker in shared_strings {
+        let index = marker.location.paragraph.unwrap_or_default() - 1;
+        match workbook.shared_string_cells.get(&index) {
+            Some(cells) => {
+                for (sheet, cell) in cells {
+                    let mut marker = marker.clone();
+                    marker.location.sheet = Some(sheet.clone());
+                    marker.location.cell = Some(cell.clone());
+                    markers.push(marker);
+                }
+            }
+            None => markers.push(marker),
+        }
+    }
+
+    Ok(markers)
+}
+
+const WORKBOOK_RELS: &str = "xl/_rels/workbook.xml.rels";
+
+fn is_content_part(name: &str) -> bool {
+    name.ends_with(".xml")
+        && name != "[Content_Types].xml"
+        && name != "settings.xml"
+        && name != "meta.xml"
+        && name != "manifest.xml"
+        && !name.starts_with("META-INF/")
+        && !name.starts_with("docProps/")
+        && !name.starts_with("customXml/")
+        && !name.contains("/theme/")
+        && !name.contains("_rels/")
+}
+
+fn part_order(name: &str) -> (u8, String) {
+    let rank = match name {
+        "word/document.xml" | "content.xml" => 0,
+        _ if name.starts_with("ppt/slides/") || name.starts_with("xl/worksheets/") => 1,
+        _ => 2,
+    };
+    (rank, natural_key(name))
+}
+
+/// Sort key putting `slide2.xml` before `slide10.xml`.
+fn natural_key(name: &str) -> String {
+    let mut key = String::new();
+    let mut digits = String::new();
+    for c in name.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+        } else {
+            if !digits.is_empty() {
+                key.push_str(&format!("{:0>10}", digits));
+                digits.clear();
+            }
+            key.push(c);
+        }
+    }
+    if !digits.is_empty() {
+        key.push_str(&format!("{:0>10}", digits));
+    }
+    key
+}
+
+/// Sheets of an XLSX workbook: names by part and cells using each shared string.
+#[derive(Default)]
+struct Workbook {
+    sheet_names: HashMap<String, String>,
+    shared_string_cells: HashMap<usize, Vec<(String, String)>>,
+}
+
+impl Workbook {
+    fn new(parts: &HashMap<String, String>) -> Self {
+        let mut workbook = Workbook::default();
+
+        let (Some(sheets), Some(rels)) = (parts.get("xl/workbook.xml"), parts.get(WORKBOOK_RELS))
+        else {
+            return workbook.with_shared_strings(parts);
+        };
+
+        let mut targets = HashMap::new();
+        for event in XmlEvents::new(rels) {
+            if let XmlEvent::Open { name, attrs, .. } = event {
+                if local_name(name) == "Relationship" {
+                    if let (Some(id), Some(target)) = (attr(attrs, "Id"), attr(attrs, "Target")) {
+                        targets.insert(id, format!("xl/{}", target.trim_start_matches("/xl/")));
+                    }
+                }
+            }
+        }
+        for event in XmlEvents::new(sheets) {
+            if let XmlEvent::Open { name, attrs, .. } = event {
+                if local_name(name) == "sheet" {
+                    if let (Some(sheet), Some(id)) = (attr(attrs, "name"), attr(attrs, "r:id")) {
+                        if let Some(target) = targets.get(&id) {
+                            workbook.sheet_names.insert(target.clone(), sheet);
+                        }
+                    }
+                }
+            }
+        }
+
+        workbook.with_shared_strings(parts)
+    }
+
+    fn with_shared_strings(mut self, parts: &HashMap<String, String>) -> Self {
+        let mut sheets: Vec<&String> = parts
+            .keys()
+            .filter(|name| name.starts_with("xl/worksheets/"))
+            .collect();
+        sheets.sort_by_key(|name| natural_key(name));
+
+        for part in sheets {
+            let sheet = self.sheet_name(part);
+            let mut cell: Option<(String, bool)> = None;
+            let mut in_value = false;
+
+            for event in XmlEvents::new(&parts[part]) {
+                match event {
+                    XmlEvent::Open {
+                        name,
+                        attrs,
+                        self_closing,
+                    } => match local_name(name) {
+                        "c" if !self_closing => {
+                            let shared = attr(attrs, "t").as_deref() == Some("s");
+                            cell = attr(attrs, "r").map(|r| (r, shared));
+                        }
+                        "v" => in_value = !self_closing,
+                        _ => {}
+                    },
+                    XmlEvent::Close { name } => match local_name(name) {
+                        "c" => cell = None,
+                        "v" => in_value = false,
+                        _ => {}
+                    },
+                    XmlEvent::Text(text) if in_value => {
+                        if let (Some((reference, true)), Ok(index)) = (&cell, text.trim().parse()) {
+                            self.shared_string_cells
+                                .entry(index)
+                                .or_default()
+                                .push((sheet.clone(), reference.clone()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        self
+    }
+
+    fn sheet_name(&self, part: &str) -> String {
+        self.sheet_names.get(part).cloned().unwrap_or_else(|| {
+            part.rsplit('/')
+                .next()
+                .unwrap_or(part)
+                .trim_end_matches(".xml")
+                .to_string()
+        })
+    }
+}
+
+struct Paragraph {
+    index: usize,
+    text: String,
+    cell: Option<String>,
+    /// Offsets of the text where a run starts or ends.
+    run_boundaries: Vec<usize>,
+}
+
+impl Paragraph {
+    fn markers(&self, location: &MarkerLocation) -> Vec<Marker> {
+        find_marker_ranges(&self.text)
+            .into_iter()
+            .map(|(start, end, kind)| Marker {
+                text: self.text[start..end].to_string(),
+                kind,
+                location: location.clone(),
+                split_across_runs: self
+                    .run_boundaries
+                    .iter()
+                    .any(|&offset| start < offset && offset < end),
+            })
+            .collect()
+    }
+}
+
+/// Scan the paragraphs of an XML part of an office document.
+fn scan_part(file: &str, part: &str, xml: &str, workbook: &Workbook) -> Vec<Marker> {
+    let is_xlsx_sheet = part.starts_with("xl/worksheets/");
+    let mut markers = Vec::new();
+    let mut stack: Vec<Paragraph> = Vec::new();
+    let mut paragraph_count = 0;
+
+    // XLSX inline cells and ODS cells
+    let mut cell: Option<String> = None;
+    let mut sheet = if is_xlsx_sheet {
+        Some(workbook.sheet_name(part))
+    } else {
+        None
+    };
+    let mut row = 0;
+    let mut column = 0;
+
+    let location = |paragraph: &Paragraph, sheet: &Option<String>| MarkerLocation {
+        file: file.to_string(),
+        part: Some(part.to_string()),
+        sheet: paragraph.cell.as_ref().and(sheet.clone()),
+        cell: paragraph.cell.clone(),
+        paragraph: Some(paragraph.index),
+        line: None,
+    };
+
+    for event in XmlEvents::new(xml) {
+        match event {
+            XmlEvent::Open {
+                name,
+                attrs,
+                self_closing,
+            } => match local_name(name) {
+                "p" | "h" | "si" | "is" => {
+                    paragraph_count += 1;
+                    if !self_closing {
+                        stack.push(Paragraph {
+                            index: paragraph_count,
+                            text: String::new(),
+                            cell: cell.clone(),
+                            run_boundaries: Vec::new(),
+                        });
+                    }
+                }
+                "c" if is_xlsx_sheet && !self_closing => cell = attr(attrs, "r"),
+                "table" if name == "table:table" => {
+                    sheet = attr(attrs, "table:name");
+                    row = 0;
+                }
+                "table-row" if name == "table:table-row" => {
+                    row += 1;
+                    column = 0;
+                    if self_closing {
+                        row += repeat(attrs, "table:number-rows-repeated") - 1;
+                    }
+                }
+                "table-cell" | "covered-table-cell" if name.starts_with("table:") => {
+                    column += 1;
+                    cell = Some(format!("{}{}", column_name(column), row));
+                    column += repeat(attrs, "table:number-columns-repeated") - 1;
+                    if self_closing {
+                        cell = None;
+                    }
+                }
+                "tab" | "line-break" | "br" | "s" if self_closing => {
+                    if let Some(paragraph) = stack.last_mut() {
+                        paragraph.text.push(' ');
+                    }
+                }
+                "r" | "span" if !self_closing => {
+                    if let Some(paragraph) = stack.last_mut() {
+                        paragraph.run_boundaries.push(paragraph.text.len());
+                    }
+                }
+                _ => {}
+            },
+            XmlEvent::Close { name } => match local_name(name) {
+                "p" | "h" | "si" | "is" => {
+                    if let Some(paragraph) = stack.pop() {
+                        markers.extend(paragraph.markers(&location(&paragraph, &sheet)));
+                    }
+                }
+                "r" | "span" => {
+                    if let Some(paragraph) = stack.last_mut() {
+                        paragraph.run_boundaries.push(paragraph.text.len());
+                    }
+                }
+                "c" if is_xlsx_sheet => cell = None,
+                "table-cell" | "covered-table-cell" if name.starts_with("table:") => cell = None,
+                _ => {}
+            },
+            XmlEvent::Text(text) => {
+                if let Some(paragraph) = stack.last_mut() {
+                    paragraph.text.push_str(&decode_entities(text));
+                }
+            }
+        }
+    }
+
+    markers
+}
+
+fn repeat(attrs: &str, name: &str) -> usize {
+    attr(attrs, name)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// Spreadsheet column name: 1 => `A`, 27 => `AA`.
+fn column_name(mut column: usize) -> String {
+    let mut name = Vec::new();
+    while column > 0 {
+        let rem = (column - 1) % 26;
+        name.push(b'A' + rem as u8);
+        column = (column - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+pub(crate) enum XmlEvent<'a> {
+    Open {
+        name: &'a str,
+        attrs: &'a str,
+        self_closing: bool,
+    },
+    Close {
+        name: &'a str,
+    },
+    Text(&'a str),
+}
+
+/// Minimal XML tokenizer, enough to follow the paragraphs of office documents.
+pub(crate) struct XmlEvents<'a> {
+    xml: &'a str,
+    offset: usize,
+}
+
+impl<'a> XmlEvents<'a> {
+    pub fn new(xml: &'a str) -> Self {
+        Self { xml, offset: 0 }
+    }
+}
+
+impl<'a> Iterator for XmlEvents<'a> {
+    type Item = XmlEvent<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = &self.xml[self.offset..];
+            if rest.is_empty() {
+                return None;
+            }
+
+            if !rest.starts_with('<') {
+                let end = rest.find('<').unwrap_or(rest.len());
+                self.offset += end;
+                return Some(XmlEvent::Text(&rest[..end]));
+            }
+
+            if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>").unwrap_or(cdata.len());
+                self.offset += "<![CDATA[".len() + end + "]]>".len().min(cdata.len() - end);
+                return Some(XmlEvent::Text(&cdata[..end]));
+            }
+
+            let (skip_end, skip) = if rest.starts_with("<!--") {
+                ("-->", true)
+            } else if rest.starts_with("<?") {
+                ("?>", true)
+            } else if rest.starts_with("<!") {
+                (">", true)
+            } else {
+                (">", false)
+            };
+
+            let end = match rest.find(skip_end) {
+                Some(end) => end + skip_end.len(),
+                None => {
+                    self.offset = self.xml.len();
+                    return None;
+                }
+            };
+            self.offset += end;
+
+            if skip {
+                continue;
+            }
+
+            let tag = &rest[1..end - 1];
+            if let Some(name) = tag.strip_prefix('/') {
+                return Some(XmlEvent::Close { name: name.trim() });
+            }
+
+            let self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+
+            return Some(XmlEvent::Open {
+                name: &tag[..name_end],
+                attrs: &tag[name_end..],
+                self_closing,
+            });
+        }
+    }
+}
+
+pub(crate) fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Value of an attribute in the attributes of a tag.
+pub(crate) fn attr(attrs: &str, name: &str) -> Option<String> {
+    let mut rest = attrs;
+    loop {
+        rest = rest.trim_start();
+        let eq = rest.find('=')?;
+        let attr_name = rest[..eq].trim();
+        let after = rest[eq + 1..].trim_start();
+        let quote = after.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value_end = after[1..].find(quote)?;
+        if attr_name == name {
+            return Some(decode_entities(&after[1..1 + value_end]));
+        }
+        rest = &after[value_end + 2..];
+    }
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| &rest[1..end]);
+        let value = match entity {
+            Some("amp") => Some('&'),
+            Some("lt") => Some('<'),
+            Some("gt") => Some('>'),
+            Some("quot") => Some('"'),
+            Some("apos") => Some('\''),
+            Some(e) if e.starts_with("#x") => u32::from_str_radix(&e[2..], 16)
+                .ok()
+                .and_then(char::from_u32),
+            Some(e) if e.starts_with('#') => e[1..].parse().ok().and_then(char::from_u32),
+            _ => None,
+        };
+        match (value, entity) {
+            (Some(c), Some(e)) => {
+                decoded.push(c);
+                rest = &rest[e.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
@@ -0,0 +1,442 @@
+//! Offline checks of the common mistakes of Carbone templates, built on [`inspect`](super::inspect).
+//!
+//! Detected issues:
+//! - loops with a `[i]` marker and no `[i+1]` marker, or the opposite;
+//! - `showBegin`/`hideBegin` without a matching `showEnd`/`hideEnd`, or the opposite;
+//! - markers split across several runs by the editor;
+//! - unknown formatters, reported as errors when they look like a typo of a Carbone formatter;
+//! - invalid arguments of `formatN` and `formatC`, and unbalanced parentheses.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::template::inspect::{inspect_bytes, Marker, MarkerKind, MarkerLocation};
+use crate::template::TemplateFile;
+use crate::types::Result;
+
+/// Formatters of Carbone v4.
+const KNOWN_FORMATTERS: &[&str] = &[
+    // text
+    "lowerCase", "upperCase", "ucFirst", "ucWords", "print", "printJSON", "convEnum", "convCRLF",
+    "unaccent", "substr", "split", "padl", "padr", "ellipsis", "prepend", "append", "replace",
+    "len", "t", "html",
+    // number
+    "formatN", "formatC", "convCurr", "round", "add", "sub", "mul", "div", "mod", "abs", "ceil",
+    "floor", "int", "toFixed",
+    // date and interval
+    "formatD", "formatI", "convDate", "addD", "subD", "startOfD", "endOfD", "diffD",
+    // condition
+    "ifEQ", "ifNE", "ifGT", "ifGTE", "ifLT", "ifLTE", "ifIN", "ifNIN", "ifEM", "ifNEM", "ifTE",
+    "and", "or", "show", "elseShow", "showBegin", "showEnd", "hideBegin", "hideEnd", "ifEmpty",
+    "ifEqual", "ifContain",
+    // array
+    "arrayJoin", "arrayMap", "count", "aggSum", "aggAvg", "aggMin", "aggMax", "aggCount",
+    "aggCountD", "aggStr", "cumSum", "cumCount",
+    // other
+    "barcode", "imageFit", "color", "defaultURL", "set", "drop", "keep",
+];
+
+/// Severity of a [`LintIssue`]. An error makes the template fail or render wrongly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Check which raised a [`LintIssue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    UnbalancedLoop,
+    UnbalancedCondition,
+    SplitMarker,
+    UnknownFormatter,
+    InvalidFormatterArguments,
+}
+
+impl LintRule {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintRule::UnbalancedLoop => "unbalanced-loop",
+            LintRule::UnbalancedCondition => "unbalanced-condition",
+            LintRule::SplitMarker => "split-marker",
+            LintRule::UnknownFormatter => "unknown-formatter",
+            LintRule::InvalidFormatterArguments => "invalid-formatter-arguments",
+        }
+    }
+}
+
+/// An issue found in a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    pub severity: Severity,
+    pub rule: LintRule,
+    pub message: String,
+    /// Marker raising the issue, with its braces.
+    pub marker: String,
+    pub location: MarkerLocation,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "{}[{}] {}: {} {}",
+            severity,
+            self.rule.as_str(),
+            self.location,
+            self.marker,
+            self.message
+        )
+    }
+}
+
+/// Options of [`lint`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintOptions {
+    /// Custom formatters registered on the Carbone On-premise server.
+    pub custom_formatters: Vec<String>,
+    /// Do not report the markers split across runs.
+    pub allow_split_markers: bool,
+}
+
+/// Issues of a template: the issues of each marker in the document order, then the unbalanced blocks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintReport {
+    pub issues: Vec<LintIssue>,
+}
+
+impl LintReport {
+    pub fn errors(&self) -> impl Iterator<Item = &LintIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &LintIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Check a template file.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::template::TemplateFile;
+/// use carbone_sdk_rust::template::lint::{lint, LintOptions};
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let template_file = TemplateFile::new("invoice.docx".to_string(), None)?;
+///
+///     let report = lint(&template_file, &LintOptions::default())?;
+///
+///     for issue in report.issues.iter() {
+///         println!("{}", issue);
+///     }
+///
+///     if report.has_errors() {
+///         std::process::exit(1);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub fn lint(template_file: &TemplateFile, options: &LintOptions) -> Result<LintReport> {
+    let content = template_file.content_bytes()?;
+    lint_bytes(template_file.path_as_str(), &content, options)
+}
+
+/// Check a template given as bytes, `file_name` being reported in the locations.
+pub fn lint_bytes(file_name: &str, content: &[u8], options: &LintOptions) -> Result<LintReport> {
+    let markers = inspect_bytes(file_name, content)?;
+    Ok(lint_markers(&markers, options))
+}
+
+/// Check the markers returned by [`inspect`](super::inspect::inspect).
+pub fn lint_markers(markers: &[Marker], options: &LintOptions) -> LintReport {
+    let mut issues = Vec::new();
+    let mut loops: BTreeMap<String, LoopMarkers> = BTreeMap::new();
+    let mut conditions: Vec<&Marker> = Vec::new();
+
+    for marker in markers {
+        if marker.split_across_runs && !options.allow_split_markers {
+            issues.push(issue(
+                marker,
+                Severity::Warning,
+                LintRule::SplitMarker,
+                "is split across several runs, retype it in one go or clear its formatting"
+                    .to_string(),
+            ));
+        }
+
+        if !matches!(
+            marker.kind,
+            MarkerKind::Data | MarkerKind::Complement | MarkerKind::Alias
+        ) {
+            continue;
+        }
+
+        for (key, is_end) in loop_keys(marker.path()) {
+            let entry = loops.entry(key).or_default();
+            let markers = if is_end {
+                &mut entry.ends
+            } else {
+                &mut entry.starts
+            };
+            markers.push(marker);
+        }
+
+        for formatter in marker.formatters() {
+            issues.extend(check_formatter(marker, formatter, options));
+
+            match formatter_name(formatter) {
+                "showBegin" | "hideBegin" => conditions.push(marker),
+                "showEnd" | "hideEnd" if conditions.pop().is_none() => {
+                    issues.push(issue(
+                        marker,
+                        Severity::Error,
+                        LintRule::UnbalancedCondition,
+                        "ends a conditional block which is not started".to_string(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for marker in conditions {
+        issues.push(issue(
+            marker,
+            Severity::Error,
+            LintRule::UnbalancedCondition,
+            "starts a conditional block which is not ended by showEnd or hideEnd".to_string(),
+        ));
+    }
+
+    for (key, markers) in loops {
+        match (markers.starts.first(), markers.ends.first()) {
+            (Some(start), None) => issues.push(issue(
+                start,
+                Severity::Error,
+                LintRule::UnbalancedLoop,
+                format!("starts the loop {} but no marker ends it with +1", key),
+            )),
+            (None, Some(end)) => issues.push(issue(
+                end,
+                Severity::Error,
+                LintRule::UnbalancedLoop,
+                format!("ends the loop {} but no marker starts it", key),
+            )),
+            _ => {}
+        }
+    }
+
+    LintReport { issues }
+}
+
+#[derive(Default)]
+struct LoopMarkers<'a> {
+    starts: Vec<&'a Marker>,
+    ends: Vec<&'a Marker>,
+}
+
+fn issue(marker: &Marker, severity: Severity, rule: LintRule, message: String) -> LintIssue {
+    LintIssue {
+        severity,
+        rule,
+        message,
+        marker: marker.text.clone(),
+        location: marker.location.clone(),
+    }
+}
+
+/// Loops iterated by a marker path, normalized as `d.products[i].items[j]`, and
+/// whether the marker is on the `+1` side of the loop.
+///
+/// `d.products[i].items[i+1].name` returns `[("d.products[i]", false), ("d.products[i].items[i]", true)]`.
+fn loop_keys(path: &str) -> Vec<(String, bool)> {
+    let mut keys = Vec::new();
+    let mut normalized = String::new();
+    let mut rest = path;
+
+    while let Some(open) = rest.find('[') {
+        normalized.push_str(&rest[..open]);
+        let close = match rest[open..].find(']') {
+            Some(close) => open + close,
+            None => return keys,
+        };
+        let inner = &rest[open + 1..close];
+
+        let tokens: Vec<&str> = inner.split(',').map(str::trim).collect();
+        let iterators: Vec<&str> = tokens
+            .iter()
+            .filter(|token| is_iterator(token))
+            .copied()
+            .collect();
+
+        if iterators.is_empty() {
+            normalized.push_str(&rest[open..=close]);
+        } else {
+            let is_end = iterators.iter().any(|token| token.ends_with("+1"));
+            let names: Vec<&str> = iterators
+                .iter()
+                .map(|token| token.trim_end_matches("+1").trim())
+                .collect();
+            normalized.push_str(&format!("[{}]", names.join(",")));
+            keys.push((normalized.clone(), is_end));
+        }
+
+        rest = &rest[close + 1..];
+    }
+
+    keys
+}
+
+/// `i`, `i+1`, `sort`, `.id+1`... but not an index (`0`) or a filter (`type='a'`).
+fn is_iterator(token: &str) -> bool {
+    let name = token.trim_end_matches("+1").trim();
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+}
+
+fn formatter_name(formatter: &str) -> &str {
+    formatter
+        .split('(')
+        .next()
+        .unwrap_or(formatter)
+        .trim()
+}
+
+fn formatter_arguments(formatter: &str) -> Option<Vec<&str>> {
+    let open = formatter.find('(')?;
+    let inner = formatter[open + 1..].strip_suffix(')')?;
+    if inner.trim().is_empty() {
+        return Some(Vec::new());
+    }
+    Some(inner.split(',').map(str::trim).collect())
+}
+
+fn check_formatter(marker: &Marker, formatter: &str, options: &LintOptions) -> Option<LintIssue> {
+    let name = formatter_name(formatter);
+
+    if formatter.contains('(') && !formatter.ends_with(')') {
+        return Some(issue(
+            marker,
+            Severity::Error,
+            LintRule::InvalidFormatterArguments,
+            format!("has unbalanced parentheses in the formatter {}", name),
+        ));
+    }
+
+    if KNOWN_FORMATTERS.contains(&name)
+        || options
+            .custom_formatters
+            .iter()
+            .any(|custom| custom == name)
+    {
+        return check_formatter_arguments(marker, name, formatter);
+    }
+
+    match closest_formatter(name) {
+        Some(suggestion) => Some(issue(
+            marker,
+            Severity::Error,
+            LintRule::UnknownFormatter,
+            format!("uses the unknown formatter {}, did you mean {}?", name, suggestion),
+        )),
+        None => Some(issue(
+            marker,
+            Severity::Warning,
+            LintRule::UnknownFormatter,
+            format!(
+                "uses the unknown formatter {}, add it to the custom formatters if it is registered on the server",
+                name
+            ),
+        )),
+    }
+}
+
+fn check_formatter_arguments(marker: &Marker, name: &str, formatter: &str) -> Option<LintIssue> {
+    let arguments = formatter_arguments(formatter).unwrap_or_default();
+
+    let message = match name {
+        "formatN" if arguments.len() > 1 => {
+            Some("formatN takes one argument: the number of decimals".to_string())
+        }
+        "formatN" => arguments
+            .first()
+            .filter(|argument| !is_integer_or_dynamic(argument))
+            .map(|argument| format!("formatN expects a number of decimals, not {}", argument)),
+        "formatC" if arguments.len() > 2 => Some(
+            "formatC takes two arguments: the number of decimals or the format, and the currency"
+                .to_string(),
+        ),
+        _ if arguments.iter().any(|argument| argument.is_empty()) => {
+            Some(format!("has an empty argument in the formatter {}", name))
+        }
+        _ => None,
+    };
+
+    message.map(|message| {
+        issue(
+            marker,
+            Severity::Error,
+            LintRule::InvalidFormatterArguments,
+            message,
+        )
+    })
+}
+
+fn is_integer_or_dynamic(argument: &str) -> bool {
+    argument.parse::<u32>().is_ok()
+        || argument.starts_with('.')
+        || argument.starts_with('$')
+        || argument.starts_with("d.")
+        || argument.starts_with("c.")
+}
+
+/// Known formatter within two edits of `name`, ignoring the case.
+fn closest_formatter(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    KNOWN_FORMATTERS
+        .iter()
+        .map(|known| (levenshtein(&name, &known.to_lowercase()), *known))
+        .filter(|(distance, known)| *distance <= 2 && *distance < known.len())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
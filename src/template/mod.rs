@@ -0,0 +1,576 @@
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str;
+
+use std::fs::Metadata;
+
+use std::ops::Deref;
+
+use sha2::{Digest, Sha256};
+
+use serde::{Deserialize, Serialize};
+
+use crate::carbone_response::{TemplateInfo, TemplateList};
+use crate::errors::CarboneError;
+use crate::types::*;
+
+use crate::types::Result;
+
+pub mod inspect;
+pub mod lint;
+pub mod schema;
+pub mod validate;
+
+/// A template file, on disk or in memory.
+///
+/// With `content`, the template does not need to exist on disk: `path` is only the
+/// logical name of the template, whose extension gives the MIME type of the upload.
+#[derive(Debug, Clone)]
+pub struct TemplateFile {
+    path: String,
+    pub content: Option<Vec<u8>>,
+    /// Metadata of the file on disk, if any.
+    pub metadata: Option<Metadata>,
+}
+
+impl TemplateFile {
+    pub fn new(path: String, content: Option<Vec<u8>>) -> Result<Self> {
+        if Path::new(path.as_str()).is_dir() {
+            return Err(CarboneError::IsADirectory(path));
+        }
+
+        if content.is_none() && !Path::new(path.as_str()).is_file() {
+            return Err(CarboneError::TemplateFileNotFound(path));
+        }
+
+        let metadata = fs::metadata(path.as_str()).ok();
+
+        Ok(Self {
+            path,
+            content,
+            metadata,
+        })
+    }
+
+    /// Create an in-memory template, `name` being its logical file name, e.g. `invoice.docx`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use carbone_sdk_rust::template::TemplateFile;
+    /// use carbone_sdk_rust::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let content = b"<p>{d.firstname}</p>".to_vec();
+    ///
+    ///     let template_file = TemplateFile::from_bytes("letter.html", content)?;
+    ///
+    ///     assert_eq!(template_file.file_name(), "letter.html");
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_bytes<T: Into<String>>(name: T, content: Vec<u8>) -> Result<Self> {
+        let name = name.into();
+        if name.is_empty() {
+            return Err(CarboneError::EmptyString("template name".to_string()));
+        }
+
+        Ok(Self {
+            path: name,
+            content: Some(content),
+            metadata: None,
+        })
+    }
+
+    pub fn generate_id(&self, payload: Option<&str>) -> Result<TemplateId> {
+        TemplateId::from_bytes(self.content_bytes()?, payload)
+    }
+
+    /// Content of the template, read from the file if not given.
+    pub(crate) fn content_bytes(&self) -> Result<Vec<u8>> {
+        match self.content.to_owned() {
+            Some(c) => Ok(c),
+            None => Ok(fs::read(self.path_as_str())?),
+        }
+    }
+
+    pub fn path_as_str(&self) -> &str {
+        &self.path
+    }
+
+    /// Last component of the path, e.g. `invoice.docx` for `templates/invoice.docx`.
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path)
+    }
+
+    /// Extension of the file name, without the dot.
+    pub fn extension(&self) -> Option<&str> {
+        Path::new(self.file_name()).extension().and_then(|ext| ext.to_str())
+    }
+}
+
+/// Where a template comes from: a file, bytes with a logical name, or a reader.
+///
+/// Functions accepting `impl Into<TemplateSource>` also accept a `&TemplateFile`,
+/// a `TemplateFile`, a `PathBuf` or a `&Path`.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::template::{TemplateId, TemplateSource};
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     // e.g. a template stored in a database
+///     let content = b"<p>{d.firstname}</p>".to_vec();
+///
+///     let source = TemplateSource::from_bytes("letter.html", content);
+///
+///     let template_id = TemplateId::from_source(source, None)?;
+///
+///     Ok(())
+/// }
+/// ```
+pub enum TemplateSource {
+    Path(PathBuf),
+    Bytes {
+        name: String,
+        content: Vec<u8>,
+    },
+    Reader {
+        name: String,
+        reader: Box<dyn Read + Send>,
+    },
+}
+
+impl TemplateSource {
+    pub fn from_path<P: Into<PathBuf>>(path: P) -> Self {
+        TemplateSource::Path(path.into())
+    }
+
+    pub fn from_bytes<T: Into<String>>(name: T, content: Vec<u8>) -> Self {
+        TemplateSource::Bytes {
+            name: name.into(),
+            content,
+        }
+    }
+
+    pub fn from_reader<T: Into<String>, R: Read + Send + 'static>(name: T, reader: R) -> Self {
+        TemplateSource::Reader {
+            name: name.into(),
+            reader: Box::new(reader),
+        }
+    }
+
+    /// Logical name of the template, or its path.
+    pub fn name(&self) -> String {
+        match self {
+            TemplateSource::Path(path) => path.to_string_lossy().into_owned(),
+            TemplateSource::Bytes { name, .. } => name.clone(),
+            TemplateSource::Reader { name, .. } => name.clone(),
+        }
+    }
+
+    /// Load the template. A file is checked but read only when needed, a reader is read to the end.
+    /// A path which is not valid UTF-8 is an error.
+    pub fn into_template_file(self) -> Result<TemplateFile> {
+        match self {
+            TemplateSource::Path(path) => match path.to_str() {
+                Some(path) => TemplateFile::new(path.to_string(), None),
+                None => Err(CarboneError::Error(format!(
+                    "template path {:?} is not valid UTF-8",
+                    path
+                ))),
+            },
+            TemplateSource::Bytes { name, content } => TemplateFile::from_bytes(name, content),
+            TemplateSource::Reader { name, mut reader } => {
+                let mut content = Vec::new();
+                reader.read_to_end(&mut content)?;
+                TemplateFile::from_bytes(name, content)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for TemplateSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateSource::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            TemplateSource::Bytes { name, content } => f
+                .debug_struct("Bytes")
+                .field("name", name)
+                .field("len", &content.len())
+                .finish(),
+            TemplateSource::Reader { name, .. } => {
+                f.debug_struct("Reader").field("name", name).finish_non_exhaustive()
+            }
+        }
+    }
+}
+
+impl From<TemplateFile> for TemplateSource {
+    fn from(template_file: TemplateFile) -> Self {
+        match template_file.content {
+            Some(content) => TemplateSource::Bytes {
+                name: template_file.path,
+                content,
+            },
+            None => TemplateSource::Path(PathBuf::from(template_file.path)),
+        }
+    }
+}
+
+impl From<&TemplateFile> for TemplateSource {
+    fn from(template_file: &TemplateFile) -> Self {
+        template_file.clone().into()
+    }
+}
+
+impl From<PathBuf> for TemplateSource {
+    fn from(path: PathBuf) -> Self {
+        TemplateSource::Path(path)
+    }
+}
+
+impl From<&Path> for TemplateSource {
+    fn from(path: &Path) -> Self {
+        TemplateSource::Path(path.to_path_buf())
+    }
+}
+
+/// Identifier of a template.
+///
+/// With the Carbone API v3 and non-versioned v4 uploads, it is the SHA-256 hash of
+/// the template content. With v4 versioning, one template ID groups several
+/// [`VersionId`]s and renders the deployed one.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct TemplateId(Id);
+
+impl TemplateId {
+    /// Create a new template_id.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::env;
+    ///
+    /// use carbone_sdk_rust::template::TemplateId;
+    /// use carbone_sdk_rust::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///    
+    ///     let template_id = TemplateId::new("0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114".to_string())?;
+    ///
+    ///     assert_eq!(template_id.as_str().is_empty(), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn new<T: Into<String>>(id: T) -> Result<Self> {
+        let id = Id::new(id, "template_id")?;
+        Ok(TemplateId(id))
+    }
+
+    /// Compute the template ID the Carbone API v3 and v4 return for a template uploaded
+    /// without versioning: the SHA-256 hash of the `payload` followed by the content.
+    /// The same `payload` must be sent with `upload_template`.
+    pub fn from_bytes(data: Vec<u8>, payload: Option<&str>) -> Result<Self> {
+        let mut sha256 = Sha256::new();
+
+        let payload = payload.unwrap_or("");
+
+        sha256.update(payload);
+        sha256.update(data);
+
+        // convert [u8] to String
+        let result: String = format!("{:X}", sha256.finalize());
+
+        Self::new(result.to_lowercase())
+    }
+
+    /// Compute the template ID `api_version` of the Carbone API returns for a template
+    /// uploaded without versioning. The API v3 and v4 both derive it like
+    /// [`from_bytes`](Self::from_bytes); the derivation of other versions is unknown.
+    pub fn from_bytes_for_api(api_version: &ApiVersion, data: Vec<u8>, payload: Option<&str>) -> Result<Self> {
+        match api_version.as_str().split('.').next() {
+            Some("3") | Some("4") => Self::from_bytes(data, payload),
+            _ => Err(CarboneError::Error(format!(
+                "unknown template ID derivation for the Carbone API v{}",
+                api_version.as_str()
+            ))),
+        }
+    }
+
+    /// Compute the template ID of a template source, like [`from_bytes`](Self::from_bytes).
+    pub fn from_source<S: Into<TemplateSource>>(source: S, payload: Option<&str>) -> Result<Self> {
+        source.into().into_template_file()?.generate_id(payload)
+    }
+}
+
+/// Check the template ID returned by the API against the one computed locally.
+pub(crate) fn verify_template_id(expected: TemplateId, received: TemplateId) -> Result<TemplateId> {
+    if expected == received {
+        Ok(received)
+    } else {
+        Err(CarboneError::TemplateIdMismatch { expected, received })
+    }
+}
+
+impl Deref for TemplateId {
+    type Target = Id;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for TemplateId {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+/// Identifier of one version of a template (Carbone API v4).
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct VersionId(Id);
+
+impl VersionId {
+    /// Create a new version_id.
+    ///
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use carbone_sdk_rust::template::VersionId;
+    /// use carbone_sdk_rust::errors::CarboneError;
+    ///
+    /// fn main() -> Result<(), CarboneError> {
+    ///
+    ///     let version_id = VersionId::new("0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114")?;
+    ///
+    ///     assert_eq!(version_id.as_str().is_empty(), false);
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn new<T: Into<String>>(id: T) -> Result<Self> {
+        let id = Id::new(id, "version_id")?;
+        Ok(VersionId(id))
+    }
+}
+
+impl Deref for VersionId {
+    type Target = Id;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for VersionId {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+/// Metadata sent along with a template by `upload_template_with_options` (Carbone API v4).
+///
+/// Dates are Unix timestamps in seconds.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::template::UploadOptions;
+///
+/// let options = UploadOptions {
+///     name: Some("Invoice".to_string()),
+///     category: Some("accounting".to_string()),
+///     tags: vec!["invoice".to_string(), "2024".to_string()],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UploadOptions {
+    pub name: Option<String>,
+    pub comment: Option<String>,
+    pub tags: Vec<String>,
+    pub category: Option<String>,
+    pub deployed_at: Option<i64>,
+    pub expire_at: Option<i64>,
+}
+
+impl UploadOptions {
+    /// Multipart text fields describing these options.
+    pub(crate) fn form_fields(&self) -> Vec<(&'static str, String)> {
+        let mut fields = Vec::new();
+
+        if let Some(name) = &self.name {
+            fields.push(("name", name.clone()));
+        }
+        if let Some(comment) = &self.comment {
+            fields.push(("comment", comment.clone()));
+        }
+        if !self.tags.is_empty() {
+            fields.push(("tags", serde_json::to_string(&self.tags).unwrap()));
+        }
+        if let Some(category) = &self.category {
+            fields.push(("category", category.clone()));
+        }
+        if let Some(deployed_at) = self.deployed_at {
+            fields.push(("deployedAt", deployed_at.to_string()));
+        }
+        if let Some(expire_at) = self.expire_at {
+            fields.push(("expireAt", expire_at.to_string()));
+        }
+
+        fields
+    }
+}
+
+/// Field used to sort the templates returned by `list_templates`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateSortBy {
+    Name,
+    CreatedAt,
+    DeployedAt,
+    Size,
+}
+
+/// Filters, sort and pagination of `list_templates`.
+///
+/// `id`, `category`, `search`, `include_versions`, `limit` and `cursor` are sent to
+/// the Carbone API, which neither filters by tag nor sorts. With a `tag` or a `sort_by`,
+/// `list_templates` fetches every page, then filters and sorts all the templates and
+/// returns the first `limit` of them in a single page; `cursor` is rejected.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::template::{TemplateListQuery, TemplateSortBy};
+///
+/// let query = TemplateListQuery {
+///     category: Some("accounting".to_string()),
+///     tag: Some("2024".to_string()),
+///     sort_by: Some(TemplateSortBy::CreatedAt),
+///     descending: true,
+///     limit: Some(50),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateListQuery {
+    pub id: Option<TemplateId>,
+    pub category: Option<String>,
+    pub tag: Option<String>,
+    /// Search in the template names.
+    pub search: Option<String>,
+    /// Return every version of the templates instead of the deployed ones.
+    pub include_versions: bool,
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+    pub sort_by: Option<TemplateSortBy>,
+    pub descending: bool,
+}
+
+/// Error of `list_templates` with a configuration of the API v3 or older.
+pub(crate) const TEMPLATE_LISTING_UNSUPPORTED: &str =
+    "listing the templates requires the Carbone API v4 (GET /templates)";
+
+impl TemplateListQuery {
+    /// Fail with an API version older than the v4, which added `GET /templates`.
+    pub(crate) fn check_api_version(api_version: &ApiVersion) -> Result<()> {
+        let major = api_version
+            .as_str()
+            .split('.')
+            .next()
+            .and_then(|major| major.parse::<u32>().ok());
+
+        match major {
+            Some(major) if major < 4 => Err(CarboneError::Error(TEMPLATE_LISTING_UNSUPPORTED.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Query string parameters sent to `GET /templates`.
+    pub(crate) fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+
+        if let Some(id) = &self.id {
+            params.push(("id", id.as_str().to_string()));
+        }
+        if let Some(category) = &self.category {
+            params.push(("category", category.clone()));
+        }
+        if let Some(search) = &self.search {
+            params.push(("search", search.clone()));
+        }
+        if self.include_versions {
+            params.push(("includeVersions", "true".to_string()));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(cursor) = &self.cursor {
+            params.push(("cursor", cursor.clone()));
+        }
+
+        params
+    }
+
+    /// Query of the pages to fetch when the tag filter or the sort is set, as they apply to
+    /// all the templates: every page from the first one, with the page size of the API.
+    /// `None` when the query is sent as is.
+    pub(crate) fn all_pages_query(&self) -> Result<Option<TemplateListQuery>> {
+        if self.tag.is_none() && self.sort_by.is_none() {
+            return Ok(None);
+        }
+        if self.cursor.is_some() {
+            return Err(CarboneError::Error(
+                "cursor can not be combined with tag or sort_by, which apply to every page".to_string(),
+            ));
+        }
+
+        Ok(Some(TemplateListQuery {
+            tag: None,
+            limit: None,
+            sort_by: None,
+            descending: false,
+            ..self.clone()
+        }))
+    }
+
+    /// Apply the tag filter, the sort and the limit on all the templates.
+    pub(crate) fn apply(&self, mut templates: Vec<TemplateInfo>) -> TemplateList {
+        if let Some(tag) = &self.tag {
+            templates.retain(|template| template.tags.iter().any(|t| t == tag));
+        }
+
+        if let Some(sort_by) = self.sort_by {
+            templates.sort_by(|a, b| match sort_by {
+                TemplateSortBy::Name => a.name.cmp(&b.name),
+                TemplateSortBy::CreatedAt => a.created_at.cmp(&b.created_at),
+                TemplateSortBy::DeployedAt => a.deployed_at.cmp(&b.deployed_at),
+                TemplateSortBy::Size => a.size.cmp(&b.size),
+            });
+            if self.descending {
+                templates.reverse();
+            }
+        }
+
+        if let Some(limit) = self.limit {
+            templates.truncate(limit as usize);
+        }
+
+        TemplateList {
+            templates,
+            has_more: false,
+            next_cursor: None,
+        }
+    }
+}
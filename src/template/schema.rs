@@ -0,0 +1,365 @@
+//! Expected shape of the render data of a template, derived from its markers.
+//!
+//! Each `{d.path}` marker adds a path to the shape: `.field` is an object property,
+//! `[i]`, `[i+1]`, `[0]` or `[]` is an array. The first formatter of a marker gives
+//! the type of the value when it expects a number (`formatN`, `formatC`...) or a date
+//! (`formatD`, `convDate`...). `{c.path}` markers build the shape of the complement.
+//! An alias usage, `{$name.path}`, is expanded with the path of its `{#name = d.path}`
+//! definition.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::{json, Map, Value};
+
+use crate::template::inspect::{find_outside_parentheses, inspect_bytes, Marker, MarkerKind};
+use crate::template::TemplateFile;
+use crate::types::Result;
+
+const NUMBER_FORMATTERS: &[&str] = &[
+    "formatN", "formatC", "convCurr", "round", "add", "sub", "mul", "div", "mod", "abs", "ceil",
+    "floor", "int", "toFixed", "aggSum", "aggAvg", "aggMin", "aggMax", "cumSum",
+];
+
+const DATE_FORMATTERS: &[&str] = &[
+    "formatD", "convDate", "addD", "subD", "startOfD", "endOfD", "diffD",
+];
+
+const ARRAY_FORMATTERS: &[&str] = &["arrayJoin", "arrayMap"];
+
+/// Type of a value expected by a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    /// Printed as is: a string, a number or a boolean.
+    Any,
+    Number,
+    /// A date string or a timestamp.
+    Date,
+}
+
+/// Shape of the data expected by a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataShape {
+    Value(ValueType),
+    Object(BTreeMap<String, DataShape>),
+    Array(Box<DataShape>),
+}
+
+impl Default for DataShape {
+    fn default() -> Self {
+        DataShape::Object(BTreeMap::new())
+    }
+}
+
+/// A step of a marker path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PathSegment {
+    Field(String),
+    /// `[i]`, `[i+1]`, `[0]`, `[]`, `[i, type='a']`...
+    Item,
+}
+
+/// Split a marker path, without its `d.` or `c.` prefix, into segments.
+/// `..` goes back to the parent object.
+pub(crate) fn parse_path(path: &str) -> Vec<PathSegment> {
+    let mut segments = Vec::new();
+    let mut field = String::new();
+    let mut chars = path.chars();
+    let mut previous_dot = false;
+
+    let push_field = |field: &mut String, segments: &mut Vec<PathSegment>| {
+        let name = field.trim();
+        if !name.is_empty() {
+            segments.push(PathSegment::Field(name.to_string()));
+        }
+        field.clear();
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                push_field(&mut field, &mut segments);
+                if previous_dot {
+                    while let Some(PathSegment::Item) = segments.last() {
+                        segments.pop();
+                    }
+                    segments.pop();
+                }
+                previous_dot = true;
+                continue;
+            }
+            '[' => {
+                push_field(&mut field, &mut segments);
+                let mut depth = 1;
+                for c in chars.by_ref() {
+                    match c {
+                        '[' => depth += 1,
+                        ']' if depth == 1 => break,
+                        ']' => depth -= 1,
+                        _ => {}
+                    }
+                }
+                segments.push(PathSegment::Item);
+            }
+            _ => field.push(c),
+        }
+        previous_dot = false;
+    }
+    push_field(&mut field, &mut segments);
+
+    segments
+}
+
+/// Data and complement root and segments of a marker, or `None` for other markers.
+pub(crate) fn marker_segments(marker: &Marker) -> Option<(MarkerKind, Vec<PathSegment>)> {
+    let path = marker.path();
+    let rest = match marker.kind {
+        MarkerKind::Data => path.strip_prefix('d')?,
+        MarkerKind::Complement => path.strip_prefix('c')?,
+        _ => return None,
+    };
+    Some((marker.kind, parse_path(rest)))
+}
+
+impl DataShape {
+    fn insert(&mut self, segments: &[PathSegment], leaf: &DataShape) {
+        let Some((segment, rest)) = segments.split_first() else {
+            if let DataShape::Value(ValueType::Any) = self {
+                *self = leaf.clone();
+            }
+            return;
+        };
+
+        match (segment, &mut *self) {
+            (PathSegment::Field(name), DataShape::Object(properties)) => {
+                properties
+                    .entry(name.clone())
+                    .or_insert(DataShape::Value(ValueType::Any))
+                    .insert(rest, leaf);
+            }
+            (PathSegment::Field(_), DataShape::Array(item)) => item.insert(segments, leaf),
+            (PathSegment::Field(_), DataShape::Value(_)) => {
+                *self = DataShape::default();
+                self.insert(segments, leaf);
+            }
+            (PathSegment::Item, DataShape::Array(item)) => item.insert(rest, leaf),
+            (PathSegment::Item, _) => {
+                let item = match std::mem::take(self) {
+                    DataShape::Value(_) => DataShape::Value(ValueType::Any),
+                    shape => shape,
+                };
+                *self = DataShape::Array(Box::new(item));
+                self.insert(segments, leaf);
+            }
+        }
+    }
+
+    /// JSON Schema of the shape. Every object property is required.
+    pub fn json_schema(&self) -> Value {
+        match self {
+            DataShape::Value(ValueType::Any) => json!({}),
+            DataShape::Value(ValueType::Number) => json!({ "type": "number" }),
+            DataShape::Value(ValueType::Date) => json!({ "type": ["string", "number"] }),
+            DataShape::Array(item) => json!({ "type": "array", "items": item.json_schema() }),
+            DataShape::Object(properties) => {
+                let schemas: Map<String, Value> = properties
+                    .iter()
+                    .map(|(name, shape)| (name.clone(), shape.json_schema()))
+                    .collect();
+                json!({
+                    "type": "object",
+                    "properties": schemas,
+                    "required": properties.keys().collect::<Vec<_>>(),
+                })
+            }
+        }
+    }
+
+    /// Example value with the shape: the property name for the text values,
+    /// `0` for the numbers, a date for the dates and one item per array.
+    pub fn sample(&self) -> Value {
+        self.sample_named("value")
+    }
+
+    fn sample_named(&self, name: &str) -> Value {
+        match self {
+            DataShape::Value(ValueType::Any) => Value::String(name.to_string()),
+            DataShape::Value(ValueType::Number) => json!(0),
+            DataShape::Value(ValueType::Date) => json!("2024-01-01T00:00:00.000Z"),
+            DataShape::Array(item) => json!([item.sample_named(name)]),
+            DataShape::Object(properties) => Value::Object(
+                properties
+                    .iter()
+                    .map(|(name, shape)| (name.clone(), shape.sample_named(name)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Expected shape of the `data` and `complement` of the render options of a template.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::template::TemplateFile;
+/// use carbone_sdk_rust::template::schema::TemplateSchema;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let template_file = TemplateFile::new("invoice.docx".to_string(), None)?;
+///
+///     let schema = TemplateSchema::from_template_file(&template_file)?;
+///
+///     println!("{}", schema.json_schema());
+///     println!("{}", schema.sample_data());
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateSchema {
+    pub data: DataShape,
+    /// Shape of the complement, `None` when the template has no `{c.path}` marker.
+    pub complement: Option<DataShape>,
+    /// The template uses aliases which could not be expanded: unknown aliases, aliases
+    /// with parameters or aliases of something else than a `d` or `c` path. Their paths
+    /// are missing from the shapes.
+    pub unresolved_aliases: bool,
+}
+
+impl TemplateSchema {
+    pub fn from_template_file(template_file: &TemplateFile) -> Result<Self> {
+        let content = template_file.content_bytes()?;
+        Self::from_bytes(template_file.path_as_str(), &content)
+    }
+
+    pub fn from_bytes(file_name: &str, content: &[u8]) -> Result<Self> {
+        Ok(Self::from_markers(&inspect_bytes(file_name, content)?))
+    }
+
+    pub fn from_markers(markers: &[Marker]) -> Self {
+        let mut schema = Self::default();
+        let aliases = alias_definitions(markers);
+
+        for marker in markers {
+            let expanded;
+            let marker = match marker.kind {
+                MarkerKind::Alias => match expand_alias(marker, &aliases) {
+                    Some(alias) => {
+                        expanded = alias;
+                        &expanded
+                    }
+                    None => {
+                        schema.unresolved_aliases = true;
+                        continue;
+                    }
+                },
+                _ => marker,
+            };
+
+            let Some((kind, segments)) = marker_segments(marker) else {
+                continue;
+            };
+
+            let leaf = leaf_shape(marker);
+            let root = match kind {
+                MarkerKind::Complement => schema.complement.get_or_insert_with(DataShape::default),
+                _ => &mut schema.data,
+            };
+            root.insert(&segments, &leaf);
+        }
+
+        schema
+    }
+
+    /// JSON Schema of the `data` object.
+    pub fn data_schema(&self) -> Value {
+        self.data.json_schema()
+    }
+
+    /// JSON Schema of the render options (`JsonData`) of the template.
+    pub fn json_schema(&self) -> Value {
+        let mut properties = Map::new();
+        properties.insert("data".to_string(), self.data.json_schema());
+        if let Some(complement) = &self.complement {
+            properties.insert("complement".to_string(), complement.json_schema());
+        }
+
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": properties,
+            "required": ["data"],
+        })
+    }
+
+    /// Example `data` object.
+    pub fn sample_data(&self) -> Value {
+        self.data.sample()
+    }
+
+    /// Example `complement` object, if the template uses one.
+    pub fn sample_complement(&self) -> Option<Value> {
+        self.complement.as_ref().map(DataShape::sample)
+    }
+}
+
+/// Expressions of the alias definitions, `{#name = d.path:formatter}`, by alias name.
+/// Aliases with parameters, `{#name($x) = ...}`, are left out.
+fn alias_definitions(markers: &[Marker]) -> HashMap<&str, &str> {
+    markers
+        .iter()
+        .filter(|marker| marker.kind == MarkerKind::AliasDefinition)
+        .filter_map(|marker| {
+            let (name, expression) = marker.content().trim().strip_prefix('#')?.split_once('=')?;
+            let name = name.trim();
+            (!name.contains('(')).then_some((name, expression.trim()))
+        })
+        .collect()
+}
+
+/// Data or complement marker of an alias usage, `{$name.path:formatter}`, with the alias
+/// replaced by the path of its definition, followed by the formatters of the usage and then
+/// of the definition. `None` when the alias is unknown or not a `d` or `c` path.
+fn expand_alias(marker: &Marker, aliases: &HashMap<&str, &str>) -> Option<Marker> {
+    let usage = marker.content().trim().strip_prefix('$')?;
+    let name_end = usage.find(['.', '[', ':']).unwrap_or(usage.len());
+    let expression = aliases.get(usage[..name_end].trim())?;
+
+    let path_end = find_outside_parentheses(expression, ':').unwrap_or(expression.len());
+    let (path, formatters) = expression.split_at(path_end);
+    let path = path.trim();
+
+    let mut root = path.chars();
+    let kind = match (root.next(), root.next()) {
+        (Some('d'), None | Some('.' | '[')) => MarkerKind::Data,
+        (Some('c'), None | Some('.' | '[')) => MarkerKind::Complement,
+        _ => return None,
+    };
+
+    Some(Marker {
+        text: format!("{{{}{}{}}}", path, &usage[name_end..], formatters),
+        kind,
+        location: marker.location.clone(),
+        split_across_runs: marker.split_across_runs,
+    })
+}
+
+fn leaf_shape(marker: &Marker) -> DataShape {
+    let formatter = marker
+        .formatters()
+        .first()
+        .map(|formatter| formatter.split('(').next().unwrap_or(formatter).trim())
+        .unwrap_or_default();
+
+    if NUMBER_FORMATTERS.contains(&formatter) {
+        DataShape::Value(ValueType::Number)
+    } else if DATE_FORMATTERS.contains(&formatter) {
+        DataShape::Value(ValueType::Date)
+    } else if ARRAY_FORMATTERS.contains(&formatter) {
+        DataShape::Array(Box::new(DataShape::Value(ValueType::Any)))
+    } else {
+        DataShape::Value(ValueType::Any)
+    }
+}
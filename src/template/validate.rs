@@ -0,0 +1,266 @@
+//! Validation of the render data against the markers of a template.
+//!
+//! Carbone renders an empty string for a missing path. These checks find the
+//! missing paths, the type mismatches and the unused keys before rendering.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::errors::CarboneError;
+use crate::template::schema::{DataShape, TemplateSchema, ValueType};
+use crate::template::{TemplateFile, TemplateId};
+use crate::types::{JsonData, Result};
+
+/// A difference between the render data and the paths used by a template.
+///
+/// Paths are written like the markers, with the array indexes of the data: `d.rows[2].price`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataIssue {
+    /// The template uses a path absent from the data.
+    MissingPath(String),
+    /// The data has another type than the one used by the template, e.g. an object
+    /// where the template loops over an array.
+    TypeMismatch {
+        path: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The data has a key used by no marker of the template.
+    UnusedKey(String),
+}
+
+impl DataIssue {
+    pub fn path(&self) -> &str {
+        match self {
+            DataIssue::MissingPath(path) => path,
+            DataIssue::TypeMismatch { path, .. } => path,
+            DataIssue::UnusedKey(path) => path,
+        }
+    }
+}
+
+impl fmt::Display for DataIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataIssue::MissingPath(path) => write!(f, "missing path {}", path),
+            DataIssue::TypeMismatch {
+                path,
+                expected,
+                found,
+            } => write!(f, "{} is {} {}, expected {}", path, article(found), found, expected),
+            DataIssue::UnusedKey(path) => write!(f, "unused key {}", path),
+        }
+    }
+}
+
+fn article(word: &str) -> &'static str {
+    if word.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an"
+    } else {
+        "a"
+    }
+}
+
+/// Compare the paths used by a template with the `data` and `complement` of the render options.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::template::TemplateFile;
+/// use carbone_sdk_rust::template::validate::validate_render_data;
+/// use carbone_sdk_rust::types::JsonData;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let template_file = TemplateFile::new("invoice.docx".to_string(), None)?;
+///
+///     let json_data = JsonData::new(r#"{ "data": { "customer": { "name": "John" } } }"#.to_string())?;
+///
+///     for issue in validate_render_data(&template_file, &json_data)? {
+///         println!("{}", issue);
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub fn validate_render_data(template_file: &TemplateFile, json_data: &JsonData) -> Result<Vec<DataIssue>> {
+    let schema = TemplateSchema::from_template_file(template_file)?;
+    schema.validate(json_data)
+}
+
+impl TemplateSchema {
+    /// Compare the shape of the template with the `data` and `complement` of the render options.
+    pub fn validate(&self, json_data: &JsonData) -> Result<Vec<DataIssue>> {
+        let render_options: Value = serde_json::from_str(json_data.as_str())
+            .map_err(|_| CarboneError::RequestBodyNotWellFormedJsonError)?;
+
+        Ok(self.validate_value(&render_options))
+    }
+
+    /// Same as [`validate`](Self::validate) with the render options already parsed.
+    /// The unused keys are not reported when the template has
+    /// [unresolved aliases](TemplateSchema::unresolved_aliases), which may use them.
+    pub fn validate_value(&self, render_options: &Value) -> Vec<DataIssue> {
+        let mut issues = Vec::new();
+
+        check_root(&self.data, render_options.get("data"), "d", &mut issues);
+        if let Some(complement) = &self.complement {
+            check_root(complement, render_options.get("complement"), "c", &mut issues);
+        }
+        if self.unresolved_aliases {
+            issues.retain(|issue| !matches!(issue, DataIssue::UnusedKey(_)));
+        }
+
+        issues
+    }
+}
+
+fn check_root(shape: &DataShape, value: Option<&Value>, path: &str, issues: &mut Vec<DataIssue>) {
+    match value {
+        Some(value) => check(shape, value, path.to_string(), issues),
+        None if *shape == DataShape::default() => {}
+        None => issues.push(DataIssue::MissingPath(path.to_string())),
+    }
+}
+
+fn check(shape: &DataShape, value: &Value, path: String, issues: &mut Vec<DataIssue>) {
+    match (shape, value) {
+        (DataShape::Object(properties), Value::Object(map)) => {
+            for (name, property) in properties {
+                let property_path = format!("{}.{}", path, name);
+                match map.get(name) {
+                    Some(value) => check(property, value, property_path, issues),
+                    None => issues.push(DataIssue::MissingPath(property_path)),
+                }
+            }
+            for name in map.keys().filter(|name| !properties.contains_key(*name)) {
+                issues.push(DataIssue::UnusedKey(format!("{}.{}", path, name)));
+            }
+        }
+        (DataShape::Array(item), Value::Array(values)) => {
+            for (index, value) in values.iter().enumerate() {
+                check(item, value, format!("{}[{}]", path, index), issues);
+            }
+        }
+        (DataShape::Value(value_type), value) if accepts(*value_type, value) => {}
+        (_, Value::Null) => issues.push(DataIssue::MissingPath(path)),
+        (shape, value) => issues.push(DataIssue::TypeMismatch {
+            path,
+            expected: shape_name(shape),
+            found: value_name(value),
+        }),
+    }
+}
+
+fn accepts(value_type: ValueType, value: &Value) -> bool {
+    match (value_type, value) {
+        (_, Value::Null) => true,
+        (_, Value::Object(_) | Value::Array(_)) => false,
+        (ValueType::Any, _) => true,
+        (ValueType::Number, Value::Number(_)) => true,
+        (ValueType::Number, Value::String(s)) => s.trim().parse::<f64>().is_ok(),
+        (ValueType::Date, Value::String(_) | Value::Number(_)) => true,
+        _ => false,
+    }
+}
+
+fn shape_name(shape: &DataShape) -> &'static str {
+    match shape {
+        DataShape::Object(_) => "an object",
+        DataShape::Array(_) => "an array",
+        DataShape::Value(ValueType::Any) => "a value",
+        DataShape::Value(ValueType::Number) => "a number",
+        DataShape::Value(ValueType::Date) => "a date",
+    }
+}
+
+fn value_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Schemas of the templates checked by the strict mode of the clients, by template ID.
+///
+/// Before `render_data`, the render options sent for a known template are validated, and
+/// the missing paths and type mismatches are returned as a `CarboneError::DataValidation`
+/// instead of rendering a report with empty values. The renders of other templates are
+/// not checked.
+///
+/// # Example
+///
+/// ```no_run
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::carbone::Carbone;
+/// use carbone_sdk_rust::template::TemplateFile;
+/// use carbone_sdk_rust::template::validate::DataValidator;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let config: Config = Default::default();
+///
+///     let mut validator = DataValidator::default();
+///     validator.add_template(&TemplateFile::new("invoice.docx".to_string(), None)?, None)?;
+///
+///     let carbone = Carbone::new(&config, None)?.with_strict_validation(validator);
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DataValidator {
+    schemas: HashMap<String, TemplateSchema>,
+    deny_unused_keys: bool,
+}
+
+impl DataValidator {
+    /// Also fail on the keys of the data used by no marker.
+    pub fn with_unused_keys_denied(mut self) -> Self {
+        self.deny_unused_keys = true;
+        self
+    }
+
+    /// Register a template under the template ID computed with `payload`.
+    pub fn add_template(&mut self, template_file: &TemplateFile, payload: Option<&str>) -> Result<TemplateId> {
+        let template_id = template_file.generate_id(payload)?;
+        let schema = TemplateSchema::from_template_file(template_file)?;
+        self.insert(template_id.clone(), schema);
+        Ok(template_id)
+    }
+
+    pub fn insert(&mut self, template_id: TemplateId, schema: TemplateSchema) {
+        self.schemas.insert(template_id.as_str().to_string(), schema);
+    }
+
+    pub fn get(&self, template_id: &TemplateId) -> Option<&TemplateSchema> {
+        self.schemas.get(template_id.as_str())
+    }
+
+    /// Validate the render options of a render of `template_id`, if the template is registered.
+    pub fn validate(&self, template_id: &TemplateId, json_data: &JsonData) -> Result<()> {
+        let Some(schema) = self.get(template_id) else {
+            return Ok(());
+        };
+
+        let issues: Vec<DataIssue> = schema
+            .validate(json_data)?
+            .into_iter()
+            .filter(|issue| self.deny_unused_keys || !matches!(issue, DataIssue::UnusedKey(_)))
+            .collect();
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(CarboneError::DataValidation(issues))
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Record and replay of the HTTP interactions of the clients.
+//!
+//! A `Cassette` attached with `with_cassette` either sends the requests to the Carbone API
+//! and records them with their responses in a JSON file, or answers them from the file
+//! without network access. The `Authorization` header is never written to the file.
+//!
+//! Requests are matched on their method and path, in the recorded order: replayed tests
+//! must call the API in the same order as the recording.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use data_encoding::BASE64;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::CarboneError;
+use crate::types::Result;
+
+/// Environment variable selecting the mode of `CassetteMode::from_env`.
+pub const CASSETTE_MODE_ENV: &str = "CARBONE_CASSETTE";
+
+const REDACTED: &str = "[REDACTED]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send the requests to the Carbone API and write them to the cassette file.
+    Record,
+    /// Answer the requests from the cassette file, without network access.
+    Replay,
+}
+
+impl CassetteMode {
+    /// `Record` when `CARBONE_CASSETTE` is `record`, `Replay` when it is `replay` or unset.
+    pub fn from_env() -> Result<Self> {
+        match env::var(CASSETTE_MODE_ENV) {
+            Ok(mode) if mode == "record" => Ok(CassetteMode::Record),
+            Ok(mode) if mode == "replay" || mode.is_empty() => Ok(CassetteMode::Replay),
+            Ok(mode) => Err(CarboneError::Error(format!(
+                "invalid {}: {} (expected record or replay)",
+                CASSETTE_MODE_ENV, mode
+            ))),
+            Err(_) => Ok(CassetteMode::Replay),
+        }
+    }
+}
+
+/// Request and response recorded in a cassette.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path and query of the URL, without the host, so cassettes replay against any `api_url`.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Missing for streamed bodies, like the multipart uploads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<RecordedBody>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: RecordedBody,
+}
+
+/// Body of a recorded message: UTF-8 text as is, other content in base64.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordedBody {
+    Text(String),
+    Base64(String),
+}
+
+impl RecordedBody {
+    fn new(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => RecordedBody::Text(text.to_string()),
+            Err(_) => RecordedBody::Base64(BASE64.encode(bytes)),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        match self {
+            RecordedBody::Text(text) => Ok(text.as_bytes().to_vec()),
+            RecordedBody::Base64(content) => BASE64
+                .decode(content.as_bytes())
+                .map_err(|e| CarboneError::Error(format!("invalid base64 body in cassette: {}", e))),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+/// Interactions recorded in, or replayed from, a JSON file.
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use carbone_sdk_rust::carbone::Carbone;
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::testing::cassette::{Cassette, CassetteMode};
+/// use carbone_sdk_rust::types::ApiJsonToken;
+///
+/// # fn main() -> Result<(), carbone_sdk_rust::errors::CarboneError> {
+/// let config: Config = Default::default();
+/// let api_token = ApiJsonToken::new(std::env::var("CARBONE_TOKEN").unwrap_or_default())?;
+///
+/// let cassette = Cassette::open("tests/cassettes/render.json", CassetteMode::from_env()?)?;
+/// let carbone = Carbone::new(&config, Some(&api_token))?.with_cassette(Arc::new(cassette));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    interactions: Mutex<Vec<Interaction>>,
+    /// Replayed interactions, by index.
+    replayed: Mutex<Vec<bool>>,
+}
+
+impl Cassette {
+    /// Open a cassette. In `Record` mode, the file is replaced by the first recorded
+    /// interaction; in `Replay` mode, it must exist.
+    pub fn open<P: AsRef<Path>>(path: P, mode: CassetteMode) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let interactions = match mode {
+            CassetteMode::Record => Vec::new(),
+            CassetteMode::Replay => {
+                if !path.is_file() {
+                    return Err(CarboneError::FileNotFound(path.display().to_string()));
+                }
+                let content = fs::read_to_string(&path)?;
+                let file: CassetteFile = serde_json::from_str(&content).map_err(|e| {
+                    CarboneError::ParseError(path.to_string_lossy().into_owned(), e.to_string())
+                })?;
+                file.interactions
+            }
+        };
+
+        Ok(Self {
+            path,
+            mode,
+            replayed: Mutex::new(vec![false; interactions.len()]),
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.interactions.lock().unwrap().clone()
+    }
+
+    /// Number of recorded interactions which were not replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.replayed.lock().unwrap().iter().filter(|replayed| !**replayed).count()
+    }
+
+    pub(crate) async fn send(
+        &self,
+        http_client: &reqwest::Client,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Response> {
+        let recorded_request = recorded_request(
+            request.method().as_str(),
+            request.url(),
+            request.headers(),
+            request.body().and_then(|body| body.as_bytes()),
+        );
+
+        match self.mode {
+            CassetteMode::Replay => Ok(self.replay(&recorded_request)?.into()),
+            CassetteMode::Record => {
+                let response = http_client.execute(request).await?;
+                let status = response.status();
+                let headers = response.headers().clone();
+                let body = response.bytes().await?;
+
+                Ok(self.record(recorded_request, status.as_u16(), &headers, &body)?.into())
+            }
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    pub(crate) fn send_blocking(
+        &self,
+        http_client: &reqwest::blocking::Client,
+        request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response> {
+        let recorded_request = recorded_request(
+            request.method().as_str(),
+            request.url(),
+            request.headers(),
+            request.body().and_then(|body| body.as_bytes()),
+        );
+
+        match self.mode {
+            CassetteMode::Replay => Ok(self.replay(&recorded_request)?.into()),
+            CassetteMode::Record => {
+                let response = http_client.execute(request)?;
+                let status = response.status();
+                let headers = response.headers().clone();
+                let body = response.bytes()?;
+
+                Ok(self.record(recorded_request, status.as_u16(), &headers, &body)?.into())
+            }
+        }
+    }
+
+    /// Answer with the first interaction of the same method and path not replayed yet.
+    fn replay(&self, request: &RecordedRequest) -> Result<http::Response<Vec<u8>>> {
+        let interactions = self.interactions.lock().unwrap();
+        let mut replayed = self.replayed.lock().unwrap();
+
+        let index = (0..interactions.len())
+            .find(|&index| {
+                !replayed[index]
+                    && interactions[index].request.method == request.method
+                    && interactions[index].request.path == request.path
+            })
+            .ok_or_else(|| {
+                CarboneError::Error(format!(
+                    "no recorded interaction for {} {} in {}",
+                    request.method,
+                    request.path,
+                    self.path.display()
+                ))
+            })?;
+        replayed[index] = true;
+
+        http_response(&interactions[index].response)
+    }
+
+    /// Save an interaction and rebuild the response consumed to record it.
+    fn record(
+        &self,
+        request: RecordedRequest,
+        status: u16,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<http::Response<Vec<u8>>> {
+        let response = RecordedResponse {
+            status,
+            headers: recorded_headers(headers),
+            body: RecordedBody::new(body),
+        };
+
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions.push(Interaction {
+            request,
+            response: response.clone(),
+        });
+        self.replayed.lock().unwrap().push(true);
+
+        let file = CassetteFile {
+            interactions: interactions.clone(),
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| CarboneError::Error(e.to_string()))?;
+        fs::write(&self.path, content)?;
+
+        http_response(&response)
+    }
+}
+
+fn recorded_request(method: &str, url: &reqwest::Url, headers: &HeaderMap, body: Option<&[u8]>) -> RecordedRequest {
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+
+    RecordedRequest {
+        method: method.to_string(),
+        path,
+        headers: recorded_headers(headers),
+        body: body.map(RecordedBody::new),
+    }
+}
+
+fn recorded_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if name == reqwest::header::AUTHORIZATION {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.as_str().to_string(), value)
+        })
+        .collect()
+}
+
+fn http_response(recorded: &RecordedResponse) -> Result<http::Response<Vec<u8>>> {
+    let mut response = http::Response::builder().status(recorded.status);
+    for (name, value) in &recorded.headers {
+        // The body is given as a whole.
+        if name != "transfer-encoding" {
+            response = response.header(name.as_str(), value.as_str());
+        }
+    }
+
+    response
+        .body(recorded.body.to_bytes()?)
+        .map_err(|e| CarboneError::Error(format!("invalid response in cassette: {}", e)))
+}
@@ -0,0 +1,405 @@
+//! Offline stand-in for `carbone::Carbone` in unit tests.
+//!
+//! `MockCarbone` has the methods of the client to upload, render, download and delete
+//! templates, but keeps the templates in memory and renders them itself: the markers of
+//! text templates (HTML, XML, CSV, TXT, Markdown) are replaced by the values of `data`
+//! and `complement`, so tests can assert on the content of the reports. Other templates
+//! and conversions return a fixture.
+//!
+//! The substitution is naive: formatters are ignored, and loops, aliases and options are
+//! not supported. Their markers, like the markers of missing values, are replaced by an
+//! empty string.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use reqwest::StatusCode;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::api::CarboneApi;
+use crate::carbone_response::{TemplateInfo, TemplateList};
+use crate::errors::CarboneError;
+use crate::render::RenderId;
+use crate::status::Status;
+use crate::template::inspect::{find_marker_ranges, Marker, MarkerKind, MarkerLocation};
+use crate::template::{TemplateId, TemplateListQuery, TemplateSource};
+use crate::testing::{placeholder_report, FAKE_CARBONE_VERSION};
+use crate::types::{JsonData, Result};
+
+/// Extensions of the templates rendered by marker substitution.
+pub const TEXT_EXTENSIONS: [&str; 8] = ["html", "htm", "xhtml", "xml", "csv", "txt", "md", "markdown"];
+
+/// In-memory client rendering text templates by marker substitution.
+///
+/// ```no_run
+/// use carbone_sdk_rust::testing::mock::MockCarbone;
+/// use carbone_sdk_rust::types::JsonData;
+///
+/// # async fn run() -> Result<(), carbone_sdk_rust::errors::CarboneError> {
+/// let carbone = MockCarbone::new().with_fixture("pdf", b"%PDF-1.4 fixture".to_vec());
+///
+/// let json_data = JsonData::new(r#"{ "data": { "name": "John" } }"#.to_string())?;
+/// let report = carbone
+///     .generate_report("letter.html".to_string(), b"<p>{d.name}</p>".to_vec(), json_data, None)
+///     .await?;
+///
+/// assert_eq!(report, "<p>John</p>");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct MockCarbone {
+    templates: Mutex<BTreeMap<String, MockTemplate>>,
+    reports: Mutex<HashMap<String, Bytes>>,
+    fixtures: HashMap<String, Bytes>,
+}
+
+#[derive(Debug, Clone)]
+struct MockTemplate {
+    content: Vec<u8>,
+    extension: String,
+}
+
+impl MockCarbone {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Content of the reports in the `extension` format which are not rendered by marker
+    /// substitution, e.g. `pdf` or `docx`. Without fixture, those reports are placeholder
+    /// documents listing the template ID, the format and the data.
+    pub fn with_fixture<T: Into<String>, B: Into<Bytes>>(mut self, extension: T, content: B) -> Self {
+        self.fixtures.insert(extension.into().to_lowercase(), content.into());
+        self
+    }
+
+    /// IDs of the uploaded templates, sorted.
+    pub fn template_ids(&self) -> Vec<TemplateId> {
+        self.templates
+            .lock()
+            .unwrap()
+            .keys()
+            .filter_map(|id| TemplateId::new(id.as_str()).ok())
+            .collect()
+    }
+
+    pub fn has_template(&self, template_id: &TemplateId) -> bool {
+        self.templates.lock().unwrap().contains_key(template_id.as_str())
+    }
+
+    // Delete a template. An unknown template fails with `HttpError` 404.
+    pub async fn delete_template(&self, template_id: TemplateId) -> Result<bool> {
+        match self.templates.lock().unwrap().remove(template_id.as_str()) {
+            Some(_) => Ok(true),
+            None => Err(not_found("Template not found")),
+        }
+    }
+
+    // Download a template. An unknown template fails with `HttpError` 404.
+    pub async fn download_template(&self, template_id: &TemplateId) -> Result<Bytes> {
+        match self.templates.lock().unwrap().get(template_id.as_str()) {
+            Some(template) => Ok(Bytes::from(template.content.clone())),
+            None => Err(not_found("Template not found")),
+        }
+    }
+
+    /// Generate a report, uploading the template if it is unknown.
+    pub async fn generate_report(
+        &self,
+        template_name: String,
+        template_data: Vec<u8>,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        let template_id = self.upload_template(&template_name, template_data, payload).await?;
+
+        self.generate_report_with_template_id(template_id, json_data).await
+    }
+
+    /// Generate a report from a template source: a file, bytes with a logical name or a reader.
+    pub async fn generate_report_with_file<T: Into<TemplateSource>>(
+        &self,
+        template: T,
+        json_data: JsonData,
+        payload: Option<&str>,
+    ) -> Result<Bytes> {
+        let template_file = template.into().into_template_file()?;
+        let template_data = template_file.content_bytes()?;
+
+        self.generate_report(template_file.file_name().to_string(), template_data, json_data, payload)
+            .await
+    }
+
+    /// Get a rendered report. Like the Carbone API, a report can only be downloaded once,
+    /// and an unknown report fails with `HttpError` 404.
+    pub async fn get_report(&self, render_id: &RenderId) -> Result<Bytes> {
+        self.reports
+            .lock()
+            .unwrap()
+            .remove(render_id.as_str())
+            .ok_or_else(|| not_found("Report not found"))
+    }
+
+    /// Generate a report with a template_id given.
+    pub async fn generate_report_with_template_id(
+        &self,
+        template_id: TemplateId,
+        json_data: JsonData,
+    ) -> Result<Bytes> {
+        let render_id = self.render_data(template_id, json_data).await?;
+
+        self.get_report(&render_id).await
+    }
+
+    /// Render data with a given template_id. An unknown template fails with
+    /// `HttpError` 404, like with the Carbone API.
+    pub async fn render_data(&self, template_id: TemplateId, json_data: JsonData) -> Result<RenderId> {
+        let template = self
+            .templates
+            .lock()
+            .unwrap()
+            .get(template_id.as_str())
+            .cloned()
+            .ok_or_else(|| not_found("Template not found"))?;
+
+        let render_options: Value = serde_json::from_str(json_data.as_str())
+            .map_err(|_| CarboneError::RequestBodyNotWellFormedJsonError)?;
+
+        let extension = match render_options.get("convertTo") {
+            Some(Value::String(format)) => format.to_lowercase(),
+            Some(Value::Object(options)) => match options.get("formatName").and_then(Value::as_str) {
+                Some(format) => format.to_lowercase(),
+                None => template.extension.clone(),
+            },
+            _ => template.extension.clone(),
+        };
+
+        let report = if extension == template.extension && TEXT_EXTENSIONS.contains(&extension.as_str()) {
+            let text = String::from_utf8_lossy(&template.content);
+            let escape = matches!(extension.as_str(), "html" | "htm" | "xhtml" | "xml");
+            Bytes::from(substitute(&text, &render_options, escape))
+        } else {
+            match self.fixtures.get(&extension) {
+                Some(fixture) => fixture.clone(),
+                None => {
+                    let data = render_options.get("data").cloned().unwrap_or(Value::Null);
+                    Bytes::from(placeholder_report(template_id.as_str(), &extension, &data))
+                }
+            }
+        };
+
+        let mut sha256 = Sha256::new();
+        sha256.update(template_id.as_str());
+        sha256.update(&report);
+        let render_id = RenderId::new(format!("{:x}.{}", sha256.finalize(), extension))?;
+
+        self.reports
+            .lock()
+            .unwrap()
+            .insert(render_id.as_str().to_string(), report);
+
+        Ok(render_id)
+    }
+
+    /// Store a template under the ID computed by `TemplateId::from_bytes`.
+    pub async fn upload_template(
+        &self,
+        file_name: &str,
+        file_content: Vec<u8>,
+        payload: Option<&str>,
+    ) -> Result<TemplateId> {
+        let template_id = TemplateId::from_bytes(file_content.clone(), payload)?;
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_lowercase())
+            .unwrap_or_default();
+
+        self.templates.lock().unwrap().insert(
+            template_id.as_str().to_string(),
+            MockTemplate {
+                content: file_content,
+                extension,
+            },
+        );
+
+        Ok(template_id)
+    }
+
+    /// Upload a template source: a file, bytes with a logical name or a reader.
+    pub async fn upload_template_file<T: Into<TemplateSource>>(
+        &self,
+        template: T,
+        payload: Option<&str>,
+    ) -> Result<TemplateId> {
+        let template_file = template.into().into_template_file()?;
+        let file_content = template_file.content_bytes()?;
+
+        self.upload_template(template_file.file_name(), file_content, payload)
+            .await
+    }
+
+    /// Status of a ready Carbone API.
+    pub async fn get_status(&self) -> Result<Status> {
+        Ok(Status {
+            success: true,
+            code: Some(200),
+            message: Some("OK".to_string()),
+            version: Some(FAKE_CARBONE_VERSION.to_string()),
+        })
+    }
+
+    /// List the uploaded templates. They have no name, category or tags, so a query
+    /// filtering on them returns no template.
+    pub async fn list_templates(&self, query: &TemplateListQuery) -> Result<TemplateList> {
+        if query.category.is_some() || query.search.is_some() {
+            return Ok(query.apply(Vec::new()));
+        }
+
+        let templates = self
+            .templates
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(id, _)| query.id.as_ref().is_none_or(|expected| expected.as_str() == id.as_str()))
+            .filter_map(|(id, template)| {
+                Some(TemplateInfo {
+                    id: TemplateId::new(id.as_str()).ok()?,
+                    version_id: None,
+                    name: None,
+                    comment: None,
+                    category: None,
+                    tags: Vec::new(),
+                    extension: Some(template.extension.clone()),
+                    size: Some(template.content.len() as u64),
+                    created_at: None,
+                    deployed_at: None,
+                    expire_at: None,
+                })
+            })
+            .collect();
+
+        Ok(query.apply(templates))
+    }
+}
+
+#[async_trait]
+impl CarboneApi for MockCarbone {
+    async fn upload_template(
+        &self,
+        file_name: &str,
+        file_content: Vec<u8>,
+        payload: Option<&str>,
+    ) -> Result<TemplateId> {
+        MockCarbone::upload_template(self, file_name, file_content, payload).await
+    }
+
+    async fn render_data(&self, template_id: TemplateId, json_data: JsonData) -> Result<RenderId> {
+        MockCarbone::render_data(self, template_id, json_data).await
+    }
+
+    async fn get_report(&self, render_id: &RenderId) -> Result<Bytes> {
+        MockCarbone::get_report(self, render_id).await
+    }
+
+    async fn download_template(&self, template_id: &TemplateId) -> Result<Bytes> {
+        MockCarbone::download_template(self, template_id).await
+    }
+
+    async fn delete_template(&self, template_id: TemplateId) -> Result<bool> {
+        MockCarbone::delete_template(self, template_id).await
+    }
+
+    async fn get_status(&self) -> Result<Status> {
+        MockCarbone::get_status(self).await
+    }
+
+    async fn list_templates(&self, query: &TemplateListQuery) -> Result<TemplateList> {
+        MockCarbone::list_templates(self, query).await
+    }
+}
+
+/// Replace the markers of a text template by their values.
+fn substitute(text: &str, render_options: &Value, escape: bool) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut offset = 0;
+
+    for (start, end, kind) in find_marker_ranges(text) {
+        output.push_str(&text[offset..start]);
+        offset = end;
+
+        let marker = Marker {
+            text: text[start..end].to_string(),
+            kind,
+            location: MarkerLocation::default(),
+            split_across_runs: false,
+        };
+        let path = marker.path();
+
+        let value = match kind {
+            MarkerKind::Data => lookup(render_options.get("data"), &path[1..]),
+            MarkerKind::Complement => lookup(render_options.get("complement"), &path[1..]),
+            MarkerKind::Translation => {
+                let content = marker.content().trim();
+                let text = content
+                    .strip_prefix("t(")
+                    .and_then(|content| content.strip_suffix(')'))
+                    .unwrap_or_default();
+                Some(text.to_string())
+            }
+            MarkerKind::AliasDefinition | MarkerKind::Alias | MarkerKind::Option => None,
+        };
+
+        let value = value.unwrap_or_default();
+        if escape {
+            output.push_str(
+                &value
+                    .replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;"),
+            );
+        } else {
+            output.push_str(&value);
+        }
+    }
+
+    output.push_str(&text[offset..]);
+    output
+}
+
+/// Text of the value at `path`, e.g. `.customer.name` or `.products[0].price`. Loop
+/// iterators like `[i]` are not resolved.
+fn lookup(root: Option<&Value>, path: &str) -> Option<String> {
+    let mut value = root?;
+    let mut rest = path.trim();
+
+    while !rest.is_empty() {
+        if let Some(next) = rest.strip_prefix('.') {
+            let end = next.find(['.', '[']).unwrap_or(next.len());
+            value = value.get(next[..end].trim())?;
+            rest = &next[end..];
+        } else if let Some(next) = rest.strip_prefix('[') {
+            let end = next.find(']')?;
+            let index: usize = next[..end].trim().parse().ok()?;
+            value = value.get(index)?;
+            rest = &next[end + 1..];
+        } else {
+            return None;
+        }
+    }
+
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+fn not_found(message: &str) -> CarboneError {
+    CarboneError::HttpError {
+        status_code: StatusCode::NOT_FOUND,
+        error_message: message.to_string(),
+    }
+}
@@ -0,0 +1,572 @@
+//! In-process fake Carbone server, to test code using the SDK without the Carbone API.
+//!
+//! `FakeCarbone` answers `POST /template`, `GET /template/{id}`, `DELETE /template/{id}`,
+//! `POST /render/{id}`, `GET /render/{id}` and `GET /status` like the Carbone API, with
+//! templates kept in memory under the same SHA-256 template IDs as `TemplateId::from_bytes`.
+//! Reports are placeholder documents which only depend on the template ID and the render
+//! options, so tests can compare them.
+//!
+//! Faults are injected per endpoint with `fail_next`.
+//!
+//! The `cassette` module records the interactions of the clients with the Carbone API
+//! and replays them offline, and `mock::MockCarbone` renders text templates without
+//! any HTTP.
+//!
+//! Requires the `testing` feature.
+
+pub mod cassette;
+pub mod mock;
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::config::{Config, CARBONE_API_VERSION};
+use crate::template::TemplateId;
+use crate::types::{ApiVersion, Endpoint, Result};
+
+/// Version returned by `GET /status`.
+pub const FAKE_CARBONE_VERSION: &str = "4.0.0";
+
+/// Fault answered by the fake server instead of the normal response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Wait before answering normally.
+    Latency(Duration),
+    /// Answer with this HTTP status and a Carbone error body, e.g. `500` or `503`.
+    Status(u16),
+    /// Answer `429 Too Many Requests` with a `Retry-After` header, in seconds.
+    TooManyRequests { retry_after: u64 },
+    /// Answer `200 OK` with a body which is not valid JSON.
+    MalformedJson,
+}
+
+/// Fake Carbone API listening on a free local port, in a background thread.
+///
+/// ```no_run
+/// use carbone_sdk_rust::carbone::Carbone;
+/// use carbone_sdk_rust::testing::FakeCarbone;
+/// use carbone_sdk_rust::types::ApiJsonToken;
+///
+/// # fn main() -> Result<(), carbone_sdk_rust::errors::CarboneError> {
+/// let fake = FakeCarbone::start()?;
+/// let config = fake.config()?;
+/// let api_token = ApiJsonToken::new("test_".repeat(60))?;
+///
+/// let carbone = Carbone::new(&config, Some(&api_token))?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FakeCarbone {
+    addr: SocketAddr,
+    state: Arc<Mutex<FakeState>>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    templates: BTreeMap<String, StoredTemplate>,
+    reports: HashMap<String, Vec<u8>>,
+    hits: HashMap<Endpoint, usize>,
+    faults: HashMap<Endpoint, VecDeque<Fault>>,
+    latency: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct StoredTemplate {
+    content: Vec<u8>,
+    extension: String,
+    fields: BTreeMap<String, String>,
+    created_at: u64,
+}
+
+impl FakeCarbone {
+    /// Listen on `127.0.0.1` with a port chosen by the system.
+    pub fn start() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let server = Self {
+            addr: listener.local_addr()?,
+            state: Arc::new(Mutex::new(FakeState::default())),
+        };
+
+        let state = server.state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let state = state.clone();
+                thread::spawn(move || {
+                    // A client closing the connection early is not an error of the server.
+                    let _ = handle_connection(stream, &state);
+                });
+            }
+        });
+
+        Ok(server)
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Base URL of the fake API, e.g. `http://127.0.0.1:43567`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Configuration of a client calling the fake API.
+    pub fn config(&self) -> Result<Config> {
+        let api_version = ApiVersion::new(CARBONE_API_VERSION.to_string())?;
+        Ok(Config::new(self.url(), 10, api_version)?)
+    }
+
+    /// Store a template as if it was uploaded with `payload`.
+    pub fn add_template(&self, file_name: &str, content: Vec<u8>, payload: Option<&str>) -> Result<TemplateId> {
+        let template_id = TemplateId::from_bytes(content.clone(), payload)?;
+        let template = StoredTemplate::new(file_name, content, BTreeMap::new());
+
+        self.state
+            .lock()
+            .unwrap()
+            .templates
+            .insert(template_id.as_str().to_string(), template);
+
+        Ok(template_id)
+    }
+
+    pub fn has_template(&self, template_id: &TemplateId) -> bool {
+        self.state.lock().unwrap().templates.contains_key(template_id.as_str())
+    }
+
+    /// IDs of the stored templates, sorted.
+    pub fn template_ids(&self) -> Vec<TemplateId> {
+        self.state
+            .lock()
+            .unwrap()
+            .templates
+            .keys()
+            .filter_map(|id| TemplateId::new(id.as_str()).ok())
+            .collect()
+    }
+
+    /// Number of requests received on an endpoint, faults included.
+    pub fn hits(&self, endpoint: Endpoint) -> usize {
+        self.state.lock().unwrap().hits.get(&endpoint).copied().unwrap_or(0)
+    }
+
+    /// Delay every response, e.g. to test timeouts.
+    pub fn set_latency(&self, latency: Duration) {
+        self.state.lock().unwrap().latency = latency;
+    }
+
+    /// Answer the next request of `endpoint` with `fault`. Faults of the same endpoint
+    /// are answered in the order they are added.
+    pub fn fail_next(&self, endpoint: Endpoint, fault: Fault) {
+        self.state
+            .lock()
+            .unwrap()
+            .faults
+            .entry(endpoint)
+            .or_default()
+            .push_back(fault);
+    }
+}
+
+impl StoredTemplate {
+    fn new(file_name: &str, content: Vec<u8>, fields: BTreeMap<String, String>) -> Self {
+        let extension = file_name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_lowercase())
+            .unwrap_or_default();
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            content,
+            extension,
+            fields,
+            created_at,
+        }
+    }
+}
+
+/// Request read from a connection.
+struct FakeRequest {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl FakeRequest {
+    fn read(stream: &TcpStream) -> std::io::Result<Self> {
+        let mut reader = BufReader::new(stream);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let target = parts.next().unwrap_or("/");
+        let path = target.split('?').next().unwrap_or("/").to_string();
+
+        let mut headers = Vec::new();
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+            }
+        }
+
+        let mut request = Self {
+            method,
+            path,
+            headers,
+            body: Vec::new(),
+        };
+
+        if let Some(length) = request.header("content-length") {
+            let length = length.parse::<usize>().unwrap_or(0);
+            request.body = vec![0; length];
+            reader.read_exact(&mut request.body)?;
+        } else if request
+            .header("transfer-encoding")
+            .is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked"))
+        {
+            loop {
+                let mut size = String::new();
+                reader.read_line(&mut size)?;
+                let size = usize::from_str_radix(size.trim(), 16).unwrap_or(0);
+                let mut chunk = vec![0; size + 2];
+                reader.read_exact(&mut chunk)?;
+                if size == 0 {
+                    break;
+                }
+                request.body.extend_from_slice(&chunk[..size]);
+            }
+        }
+
+        Ok(request)
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Response written to a connection.
+struct FakeResponse {
+    status: StatusCode,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl FakeResponse {
+    fn json(status: StatusCode, body: Value) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    fn error(status: StatusCode, error: &str) -> Self {
+        Self::json(status, json!({ "success": false, "error": error }))
+    }
+
+    fn write(self, stream: &mut TcpStream) -> std::io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\n",
+            self.status.as_u16(),
+            self.status.canonical_reason().unwrap_or("")
+        )?;
+        for (name, value) in &self.headers {
+            write!(stream, "{}: {}\r\n", name, value)?;
+        }
+        write!(stream, "Content-Length: {}\r\nConnection: close\r\n\r\n", self.body.len())?;
+        stream.write_all(&self.body)?;
+        stream.flush()
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, state: &Mutex<FakeState>) -> std::io::Result<()> {
+    let request = FakeRequest::read(&stream)?;
+
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    let route = match (request.method.as_str(), segments.as_slice()) {
+        ("POST", ["template"]) => Some((Endpoint::Upload, None)),
+        ("GET", ["template", id]) => Some((Endpoint::Download, Some(*id))),
+        ("DELETE", ["template", id]) => Some((Endpoint::Delete, Some(*id))),
+        ("PATCH", ["template", id]) => Some((Endpoint::Update, Some(*id))),
+        ("POST", ["render", id]) => Some((Endpoint::Render, Some(*id))),
+        ("GET", ["render", id]) => Some((Endpoint::Report, Some(*id))),
+        ("GET", ["status"]) => Some((Endpoint::Status, None)),
+        ("GET", ["templates"]) => Some((Endpoint::List, None)),
+        _ => None,
+    };
+
+    let response = match route {
+        Some((endpoint, id)) => {
+            let (fault, latency) = {
+                let mut state = state.lock().unwrap();
+                *state.hits.entry(endpoint).or_insert(0) += 1;
+                let fault = state.faults.get_mut(&endpoint).and_then(|faults| faults.pop_front());
+                (fault, state.latency)
+            };
+            thread::sleep(latency);
+
+            match fault {
+                Some(Fault::Status(status)) => {
+                    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+                    FakeResponse::error(status, "Fake Carbone error")
+                }
+                Some(Fault::TooManyRequests { retry_after }) => {
+                    let mut response = FakeResponse::error(StatusCode::TOO_MANY_REQUESTS, "Too many requests");
+                    response.headers.push(("Retry-After", retry_after.to_string()));
+                    response
+                }
+                Some(Fault::MalformedJson) => FakeResponse {
+                    status: StatusCode::OK,
+                    headers: vec![("Content-Type", "application/json".to_string())],
+                    body: br#"{"success": tru"#.to_vec(),
+                },
+                Some(Fault::Latency(latency)) => {
+                    thread::sleep(latency);
+                    respond(endpoint, id.unwrap_or_default(), &request, state)
+                }
+                None => respond(endpoint, id.unwrap_or_default(), &request, state),
+            }
+        }
+        None => FakeResponse::error(StatusCode::NOT_FOUND, "Route not found"),
+    };
+
+    response.write(&mut stream)
+}
+
+fn respond(endpoint: Endpoint, id: &str, request: &FakeRequest, state: &Mutex<FakeState>) -> FakeResponse {
+    let mut state = state.lock().unwrap();
+
+    match endpoint {
+        Endpoint::Upload => upload(request, &mut state),
+        Endpoint::Download => match state.templates.get(id) {
+            Some(template) => FakeResponse {
+                status: StatusCode::OK,
+                headers: vec![(
+                    "Content-Type",
+                    mime_guess::from_ext(&template.extension)
+                        .first_or_octet_stream()
+                        .to_string(),
+                )],
+                body: template.content.clone(),
+            },
+            None => FakeResponse::error(StatusCode::NOT_FOUND, "Template not found"),
+        },
+        Endpoint::Delete => match state.templates.remove(id) {
+            Some(_) => FakeResponse::json(StatusCode::OK, json!({ "success": true })),
+            None => FakeResponse::error(StatusCode::NOT_FOUND, "Template not found"),
+        },
+        Endpoint::Render => render(id, request, &mut state),
+        Endpoint::Report => match state.reports.remove(id) {
+            // Like the Carbone API, a report can only be downloaded once.
+            Some(report) => FakeResponse {
+                status: StatusCode::OK,
+                headers: vec![
+                    (
+                        "Content-Type",
+                        mime_guess::from_path(id).first_or_octet_stream().to_string(),
+                    ),
+                    ("Content-Disposition", format!("attachment; filename=\"{}\"", id)),
+                ],
+                body: report,
+            },
+            None => FakeResponse::error(StatusCode::NOT_FOUND, "Report not found"),
+        },
+        Endpoint::Status => FakeResponse::json(
+            StatusCode::OK,
+            json!({
+                "success": true,
+                "code": 200,
+                "message": "OK",
+                "version": FAKE_CARBONE_VERSION,
+            }),
+        ),
+        Endpoint::List | Endpoint::Update => {
+            FakeResponse::error(StatusCode::NOT_IMPLEMENTED, "Not supported by FakeCarbone")
+        }
+    }
+}
+
+fn upload(request: &FakeRequest, state: &mut FakeState) -> FakeResponse {
+    let boundary = request
+        .header("content-type")
+        .and_then(|content_type| content_type.split_once("boundary="))
+        .map(|(_, boundary)| boundary.trim_matches('"').to_string());
+    let Some(boundary) = boundary else {
+        return FakeResponse::error(StatusCode::BAD_REQUEST, "Expected a multipart/form-data body");
+    };
+
+    let mut fields = BTreeMap::new();
+    let mut template = None;
+    for part in multipart_parts(&request.body, &boundary) {
+        match (part.name.as_str(), part.file_name) {
+            ("template", Some(file_name)) => template = Some((file_name, part.content)),
+            (name, _) => {
+                fields.insert(name.to_string(), String::from_utf8_lossy(&part.content).into_owned());
+            }
+        }
+    }
+    let Some((file_name, content)) = template else {
+        return FakeResponse::error(StatusCode::BAD_REQUEST, "Missing template file");
+    };
+
+    let template_id = match TemplateId::from_bytes(content.clone(), fields.get("payload").map(String::as_str)) {
+        Ok(template_id) => template_id,
+        Err(e) => return FakeResponse::error(StatusCode::BAD_REQUEST, &e.to_string()),
+    };
+    let template = StoredTemplate::new(&file_name, content, fields);
+
+    let mut data = json!({
+        "id": template_id.as_str(),
+        "templateId": template_id.as_str(),
+        "type": template.extension,
+        "templateFileExtension": template.extension,
+        "size": template.content.len(),
+        "createdAt": template.created_at,
+    });
+    for name in ["name", "comment", "category"] {
+        if let Some(value) = template.fields.get(name) {
+            data[name] = json!(value);
+        }
+    }
+
+    state.templates.insert(template_id.as_str().to_string(), template);
+
+    FakeResponse::json(StatusCode::OK, json!({ "success": true, "data": data }))
+}
+
+fn render(template_id: &str, request: &FakeRequest, state: &mut FakeState) -> FakeResponse {
+    let Some(template) = state.templates.get(template_id) else {
+        return FakeResponse::error(StatusCode::NOT_FOUND, "Template not found");
+    };
+
+    let render_options = match serde_json::from_slice::<Value>(&request.body) {
+        Ok(Value::Object(render_options)) => render_options,
+        _ => return FakeResponse::error(StatusCode::BAD_REQUEST, "Invalid JSON render options"),
+    };
+
+    let extension = match render_options.get("convertTo") {
+        Some(Value::String(format)) => format.to_lowercase(),
+        Some(Value::Object(options)) => match options.get("formatName").and_then(Value::as_str) {
+            Some(format) => format.to_lowercase(),
+            None => template.extension.clone(),
+        },
+        _ => template.extension.clone(),
+    };
+    let data = render_options.get("data").cloned().unwrap_or(Value::Null);
+
+    let report = placeholder_report(template_id, &extension, &data);
+
+    let mut sha256 = Sha256::new();
+    sha256.update(template_id);
+    sha256.update(&report);
+    let render_id = format!("{:x}.{}", sha256.finalize(), extension);
+
+    state.reports.insert(render_id.clone(), report);
+
+    FakeResponse::json(
+        StatusCode::OK,
+        json!({ "success": true, "data": { "renderId": render_id } }),
+    )
+}
+
+/// Deterministic report content: a text listing the template ID, the format and the data,
+/// wrapped between the PDF header and trailer for `pdf`.
+pub(crate) fn placeholder_report(template_id: &str, extension: &str, data: &Value) -> Vec<u8> {
+    let text = format!(
+        "Carbone fake report\ntemplate: {}\nformat: {}\ndata: {}\n",
+        template_id, extension, data
+    );
+
+    match extension {
+        "pdf" => format!("%PDF-1.4\n{}%%EOF\n", text).into_bytes(),
+        _ => text.into_bytes(),
+    }
+}
+
+/// Part of a `multipart/form-data` body.
+struct MultipartPart {
+    name: String,
+    file_name: Option<String>,
+    content: Vec<u8>,
+}
+
+fn multipart_parts(body: &[u8], boundary: &str) -> Vec<MultipartPart> {
+    let delimiter = format!("--{}", boundary).into_bytes();
+
+    let mut parts = Vec::new();
+    for section in split_bytes(body, &delimiter).into_iter().skip(1) {
+        // The last delimiter is followed by `--`.
+        if section.starts_with(b"--") {
+            break;
+        }
+        let section = section.strip_prefix(b"\r\n").unwrap_or(section);
+        let Some(headers_end) = find_bytes(section, b"\r\n\r\n") else {
+            continue;
+        };
+
+        let headers = String::from_utf8_lossy(&section[..headers_end]);
+        let content = &section[headers_end + 4..];
+        let content = content.strip_suffix(b"\r\n").unwrap_or(content);
+
+        let disposition = headers
+            .lines()
+            .find(|line| line.to_lowercase().starts_with("content-disposition"))
+            .unwrap_or_default();
+        let Some(name) = disposition_param(disposition, "name") else {
+            continue;
+        };
+
+        parts.push(MultipartPart {
+            name,
+            file_name: disposition_param(disposition, "filename"),
+            content: content.to_vec(),
+        });
+    }
+
+    parts
+}
+
+fn disposition_param(disposition: &str, param: &str) -> Option<String> {
+    disposition.split(';').find_map(|item| {
+        let (key, value) = item.trim().split_once('=')?;
+        (key == param).then(|| value.trim_matches('"').to_string())
+    })
+}
+
+fn split_bytes<'a>(bytes: &'a [u8], delimiter: &[u8]) -> Vec<&'a [u8]> {
+    let mut sections = Vec::new();
+    let mut rest = bytes;
+    while let Some(position) = find_bytes(rest, delimiter) {
+        sections.push(&rest[..position]);
+        rest = &rest[position + delimiter.len()..];
+    }
+    sections.push(rest);
+    sections
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
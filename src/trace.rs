@@ -0,0 +1,17 @@
+//! Instrumentation of the clients with `tracing`, behind the `tracing` feature.
+//!
+//! API methods open a span named after them, with the template and render IDs and the
+//! sizes they handle, and each HTTP call opens an `http_request` child span with the
+//! endpoint, the HTTP status and the latency. Arguments are never recorded as a whole,
+//! so the API token and the render data stay out of the traces.
+//!
+//! Without the feature, `record` does nothing.
+
+/// Record a field declared by the span of the current method.
+#[cfg(feature = "tracing")]
+pub(crate) fn record<V: tracing::Value>(field: &str, value: V) {
+    tracing::Span::current().record(field, value);
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn record<V>(_field: &str, _value: V) {}
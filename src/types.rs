@@ -4,6 +4,14 @@ use crate::errors::CarboneError;
 
 pub type Result<T> = std::result::Result<T, CarboneError>;
 
+/// Current Unix timestamp in seconds, as expected by the date fields of the API.
+pub(crate) fn unix_timestamp() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
 // pub type Result<(T,U)> = std::result::Result<(T,U), CarboneError>;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -23,6 +31,43 @@ impl ApiJsonToken {
     }
 }
 
+/// Carbone API endpoints reached by the SDK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// `POST /template`
+    Upload,
+    /// `POST /render/{template_id}`
+    Render,
+    /// `GET /render/{render_id}`
+    Report,
+    /// `GET /template/{template_id}`
+    Download,
+    /// `DELETE /template/{template_id}`
+    Delete,
+    /// `GET /status`
+    Status,
+    /// `GET /templates`
+    List,
+    /// `PATCH /template/{template_id}`
+    Update,
+}
+
+impl Endpoint {
+    /// Lowercase name of the endpoint, e.g. `render`, used as a metrics label.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Endpoint::Upload => "upload",
+            Endpoint::Render => "render",
+            Endpoint::Report => "report",
+            Endpoint::Download => "download",
+            Endpoint::Delete => "delete",
+            Endpoint::Status => "status",
+            Endpoint::List => "list",
+            Endpoint::Update => "update",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub struct ApiVersion(String);
 
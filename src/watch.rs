@@ -0,0 +1,528 @@
+//! Watch mode: render a template again each time the template or its data change.
+//!
+//! `watch` runs the loop with any `CarboneApi`, `watch_blocking` with any
+//! `CarboneApiBlocking`, and `PreviewServer` serves the last report on a local page
+//! which reloads after every render.
+//!
+//! Requires the `watch` feature.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use bytes::Bytes;
+use serde_json::Value;
+
+use crate::api::CarboneApi;
+#[cfg(feature = "blocking")]
+use crate::api::CarboneApiBlocking;
+use crate::errors::CarboneError;
+use crate::render::write_report;
+use crate::template::{TemplateFile, TemplateId};
+use crate::types::{JsonData, Result};
+
+/// Interval between two checks of the watched files by default.
+pub const DEFAULT_WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Options of `watch`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchOptions {
+    /// Report file. By default, the report is written next to the template, named
+    /// `<template>.preview.<convertTo>`, e.g. `invoice.preview.pdf` for `invoice.docx`.
+    pub output: Option<PathBuf>,
+    /// Payload used to compute the template ID and sent with the upload.
+    pub payload: Option<String>,
+    pub interval: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            output: None,
+            payload: None,
+            interval: DEFAULT_WATCH_INTERVAL,
+        }
+    }
+}
+
+/// Outcome of a render of the watch mode.
+#[derive(Debug)]
+pub enum WatchEvent {
+    Rendered {
+        /// Template ID of the current content of the template, uploaded if it was unknown.
+        template_id: TemplateId,
+        output: PathBuf,
+        report: Bytes,
+        elapsed: Duration,
+    },
+    /// The render failed, e.g. the data file is not valid JSON. The watch goes on and
+    /// renders again after the next change.
+    Failed(CarboneError),
+}
+
+/// Render a template with the render options of a JSON file, then render again each
+/// time the template or the JSON file change, until `on_event` returns `ControlFlow::Break`.
+///
+/// The template ID is computed from the new content and the template is uploaded when
+/// it changed. The report is written to `options.output` or next to the template.
+/// A failed render is passed to `on_event` and does not stop the watch.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::ops::ControlFlow;
+///
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::carbone::Carbone;
+/// use carbone_sdk_rust::watch::{watch, WatchEvent, WatchOptions};
+/// use carbone_sdk_rust::types::ApiJsonToken;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), CarboneError> {
+///
+///     let config: Config = Default::default();
+///     let api_token = ApiJsonToken::new(std::env::var("CARBONE_TOKEN").unwrap())?;
+///
+///     let carbone = Carbone::new(&config, Some(&api_token))?;
+///
+///     watch(&carbone, "invoice.docx", "invoice.json", &WatchOptions::default(), |event| {
+///         match event {
+///             WatchEvent::Rendered { output, .. } => println!("rendered {}", output.display()),
+///             WatchEvent::Failed(e) => println!("error: {}", e),
+///         }
+///         ControlFlow::Continue(())
+///     })
+///     .await
+/// }
+/// ```
+pub async fn watch<C, P, Q, F>(carbone: &C, template: P, data: Q, options: &WatchOptions, mut on_event: F) -> Result<()>
+where
+    C: CarboneApi + ?Sized,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    F: FnMut(&WatchEvent) -> ControlFlow<()>,
+{
+    let (template, data) = (template.as_ref(), data.as_ref());
+    let mut watcher = FileWatcher::new(&[template, data]);
+
+    loop {
+        let event = match watch_render(carbone, template, data, options).await {
+            Ok(event) => event,
+            Err(e) => WatchEvent::Failed(e),
+        };
+        if on_event(&event).is_break() {
+            return Ok(());
+        }
+
+        while !watcher.poll() {
+            tokio::time::sleep(options.interval).await;
+        }
+    }
+}
+
+async fn watch_render<C: CarboneApi + ?Sized>(
+    carbone: &C,
+    template: &Path,
+    data: &Path,
+    options: &WatchOptions,
+) -> Result<WatchEvent> {
+    let start = Instant::now();
+    let input = WatchInput::read(template, data, options)?;
+
+    let report = carbone
+        .generate_report(
+            input.template_file.file_name().to_string(),
+            input.template_file.content_bytes()?,
+            input.json_data,
+            options.payload.as_deref(),
+        )
+        .await?;
+    write_report(&input.output, &report)?;
+
+    Ok(WatchEvent::Rendered {
+        template_id: input.template_id,
+        output: input.output,
+        report,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Blocking version of [`watch`].
+///
+/// Requires the `blocking` feature.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::ops::ControlFlow;
+///
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::blocking::Carbone;
+/// use carbone_sdk_rust::watch::{watch_blocking, PreviewServer, WatchOptions};
+/// use carbone_sdk_rust::types::ApiJsonToken;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// fn main() -> Result<(), CarboneError> {
+///
+///     let config: Config = Default::default();
+///     let api_token = ApiJsonToken::new(std::env::var("CARBONE_TOKEN").unwrap())?;
+///
+///     let carbone = Carbone::new(&config, &api_token)?;
+///
+///     let preview = PreviewServer::start("127.0.0.1:8080")?;
+///
+///     watch_blocking(&carbone, "invoice.docx", "invoice.json", &WatchOptions::default(), |event| {
+///         preview.update(event);
+///         ControlFlow::Continue(())
+///     })
+/// }
+/// ```
+#[cfg(feature = "blocking")]
+pub fn watch_blocking<C, P, Q, F>(carbone: &C, template: P, data: Q, options: &WatchOptions, mut on_event: F) -> Result<()>
+where
+    C: CarboneApiBlocking + ?Sized,
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    F: FnMut(&WatchEvent) -> ControlFlow<()>,
+{
+    let (template, data) = (template.as_ref(), data.as_ref());
+    let mut watcher = FileWatcher::new(&[template, data]);
+
+    loop {
+        let event = match watch_render_blocking(carbone, template, data, options) {
+            Ok(event) => event,
+            Err(e) => WatchEvent::Failed(e),
+        };
+        if on_event(&event).is_break() {
+            return Ok(());
+        }
+
+        while !watcher.poll() {
+            thread::sleep(options.interval);
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+fn watch_render_blocking<C: CarboneApiBlocking + ?Sized>(
+    carbone: &C,
+    template: &Path,
+    data: &Path,
+    options: &WatchOptions,
+) -> Result<WatchEvent> {
+    let start = Instant::now();
+    let input = WatchInput::read(template, data, options)?;
+
+    let report = carbone.generate_report_with_file(
+        input.template_file.into(),
+        input.json_data,
+        options.payload.as_deref(),
+    )?;
+    write_report(&input.output, &report)?;
+
+    Ok(WatchEvent::Rendered {
+        template_id: input.template_id,
+        output: input.output,
+        report,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Template and render options read when a watched file changes.
+#[derive(Debug)]
+struct WatchInput {
+    pub template_file: TemplateFile,
+    pub template_id: TemplateId,
+    pub json_data: JsonData,
+    pub output: PathBuf,
+}
+
+impl WatchInput {
+    /// Read the template and the render options (`data`, `convertTo`, ...) of the data file.
+    pub fn read(template: &Path, data: &Path, options: &WatchOptions) -> Result<Self> {
+        let content = fs::read(template)
+            .map_err(|_| CarboneError::TemplateFileNotFound(template.to_string_lossy().into_owned()))?;
+        let template_file = TemplateFile::new(template.to_string_lossy().into_owned(), Some(content))?;
+        let template_id = template_file.generate_id(options.payload.as_deref())?;
+
+        let render_options = fs::read_to_string(data)
+            .map_err(|_| CarboneError::FileNotFound(data.to_string_lossy().into_owned()))?;
+        let value: Value = serde_json::from_str(&render_options)
+            .map_err(|e| CarboneError::ParseError(data.to_string_lossy().into_owned(), e.to_string()))?;
+
+        let output = match &options.output {
+            Some(output) => output.clone(),
+            None => {
+                let extension = value
+                    .get("convertTo")
+                    .and_then(|convert_to| convert_to.as_str())
+                    .or_else(|| template_file.extension())
+                    .unwrap_or("out");
+                let stem = template
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                template.with_file_name(format!("{}.preview.{}", stem, extension))
+            }
+        };
+
+        Ok(Self {
+            template_file,
+            template_id,
+            json_data: JsonData::new(render_options)?,
+            output,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+fn stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    })
+}
+
+/// Detect the changes of files by polling their modification date and size.
+///
+/// A change is reported once the files are stable for one poll, so a file still
+/// being saved by an office suite is not read.
+#[derive(Debug)]
+struct FileWatcher {
+    paths: Vec<PathBuf>,
+    stamps: Vec<Option<FileStamp>>,
+    pending: bool,
+}
+
+impl FileWatcher {
+    pub fn new(paths: &[&Path]) -> Self {
+        let paths: Vec<PathBuf> = paths.iter().map(|path| path.to_path_buf()).collect();
+        let stamps = paths.iter().map(|path| stamp(path)).collect();
+        Self {
+            paths,
+            stamps,
+            pending: false,
+        }
+    }
+
+    /// Whether the files changed and are stable since the previous poll.
+    pub fn poll(&mut self) -> bool {
+        let stamps: Vec<Option<FileStamp>> = self.paths.iter().map(|path| stamp(path)).collect();
+
+        if stamps != self.stamps {
+            self.stamps = stamps;
+            self.pending = true;
+            false
+        } else {
+            std::mem::take(&mut self.pending)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Preview {
+    version: u64,
+    file_name: String,
+    report: Option<Bytes>,
+    error: Option<String>,
+}
+
+/// A local HTTP server showing the last report of the watch mode, on a page which
+/// reloads after every render.
+///
+/// Browsers display PDF, HTML, text and image reports; the other formats are downloaded.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::ops::ControlFlow;
+///
+/// use carbone_sdk_rust::config::Config;
+/// use carbone_sdk_rust::carbone::Carbone;
+/// use carbone_sdk_rust::watch::{watch, PreviewServer, WatchOptions};
+/// use carbone_sdk_rust::types::ApiJsonToken;
+/// use carbone_sdk_rust::errors::CarboneError;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), CarboneError> {
+///
+///     let config: Config = Default::default();
+///     let api_token = ApiJsonToken::new(std::env::var("CARBONE_TOKEN").unwrap())?;
+///
+///     let carbone = Carbone::new(&config, Some(&api_token))?;
+///
+///     let preview = PreviewServer::start("127.0.0.1:8080")?;
+///     println!("preview on {}", preview.url());
+///
+///     watch(&carbone, "invoice.docx", "invoice.json", &WatchOptions::default(), |event| {
+///         preview.update(event);
+///         ControlFlow::Continue(())
+///     })
+///     .await
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PreviewServer {
+    addr: SocketAddr,
+    preview: Arc<Mutex<Preview>>,
+}
+
+impl PreviewServer {
+    /// Listen on `addr` in a background thread, e.g. `127.0.0.1:8080`, or `127.0.0.1:0`
+    /// for a free port.
+    pub fn start<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let server = Self {
+            addr: listener.local_addr()?,
+            preview: Arc::new(Mutex::new(Preview::default())),
+        };
+
+        let preview = server.preview.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let preview = preview.clone();
+                thread::spawn(move || {
+                    // A client closing the connection early is not an error of the server.
+                    let _ = handle_connection(stream, &preview);
+                });
+            }
+        });
+
+        Ok(server)
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    /// Show the report of a render, or the error of a failed render above the last report.
+    pub fn update(&self, event: &WatchEvent) {
+        let mut preview = self.preview.lock().unwrap();
+        match event {
+            WatchEvent::Rendered { output, report, .. } => {
+                preview.file_name = output
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                preview.report = Some(report.clone());
+                preview.error = None;
+            }
+            WatchEvent::Failed(e) => preview.error = Some(e.to_string()),
+        }
+        preview.version += 1;
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, preview: &Mutex<Preview>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let path = target.split('?').next().unwrap_or("/");
+
+    let (status, content_type, body) = {
+        let preview = preview.lock().unwrap();
+        match path {
+            "/" => ("200 OK", "text/html; charset=utf-8".to_string(), preview_page(&preview).into_bytes()),
+            "/version" => ("200 OK", "text/plain".to_string(), preview.version.to_string().into_bytes()),
+            "/report" => match &preview.report {
+                Some(report) => (
+                    "200 OK",
+                    mime_guess::from_path(&preview.file_name)
+                        .first_or_octet_stream()
+                        .to_string(),
+                    report.to_vec(),
+                ),
+                None => ("404 Not Found", "text/plain".to_string(), b"no report yet".to_vec()),
+            },
+            _ => ("404 Not Found", "text/plain".to_string(), b"not found".to_vec()),
+        }
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()
+}
+
+fn preview_page(preview: &Preview) -> String {
+    let error = preview
+        .error
+        .as_ref()
+        .map(|error| format!("<pre id=\"error\">{}</pre>", escape_html(error)))
+        .unwrap_or_default();
+
+    let report = if preview.report.is_some() {
+        format!("<iframe src=\"/report?v={}\"></iframe>", preview.version)
+    } else {
+        "<p>Waiting for the first render...</p>".to_string()
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title} - Carbone preview</title>
+<style>
+body {{ margin: 0; font-family: sans-serif; display: flex; flex-direction: column; height: 100vh; }}
+#error {{ margin: 0; padding: 1em; background: #fdd; color: #900; white-space: pre-wrap; }}
+iframe {{ flex: 1; border: 0; }}
+p {{ padding: 1em; }}
+</style>
+</head>
+<body>
+{error}
+{report}
+<script>
+setInterval(function () {{
+  fetch("/version").then(function (response) {{ return response.text(); }}).then(function (version) {{
+    if (version !== "{version}") {{ location.reload(); }}
+  }}).catch(function () {{}});
+}}, 1000);
+</script>
+</body>
+</html>
+"#,
+        title = escape_html(&preview.file_name),
+        error = error,
+        report = report,
+        version = preview.version
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,177 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rust::api::CarboneApi;
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::carbone_response::TemplateList;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::render::RenderId;
+use carbone_sdk_rust::status::Status;
+use carbone_sdk_rust::template::{TemplateId, TemplateListQuery};
+use carbone_sdk_rust::types::{JsonData, Result};
+
+mod helper;
+
+use helper::Helper;
+
+/// Wrapper counting the calls to the API, relying on the default `generate_report`.
+struct CountingCarbone<C> {
+    inner: C,
+    calls: AtomicUsize,
+}
+
+impl<C: CarboneApi> CountingCarbone<C> {
+    fn new(inner: C) -> Self {
+        Self {
+            inner,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    fn count(&self) {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl<C: CarboneApi> CarboneApi for CountingCarbone<C> {
+    async fn upload_template(
+        &self,
+        file_name: &str,
+        file_content: Vec<u8>,
+        payload: Option<&str>,
+    ) -> Result<TemplateId> {
+        self.count();
+        self.inner.upload_template(file_name, file_content, payload).await
+    }
+
+    async fn render_data(&self, template_id: TemplateId, json_data: JsonData) -> Result<RenderId> {
+        self.count();
+        self.inner.render_data(template_id, json_data).await
+    }
+
+    async fn get_report(&self, render_id: &RenderId) -> Result<Bytes> {
+        self.count();
+        self.inner.get_report(render_id).await
+    }
+
+    async fn download_template(&self, template_id: &TemplateId) -> Result<Bytes> {
+        self.count();
+        self.inner.download_template(template_id).await
+    }
+
+    async fn delete_template(&self, template_id: TemplateId) -> Result<bool> {
+        self.count();
+        self.inner.delete_template(template_id).await
+    }
+
+    async fn get_status(&self) -> Result<Status> {
+        self.count();
+        self.inner.get_status().await
+    }
+
+    async fn list_templates(&self, query: &TemplateListQuery) -> Result<TemplateList> {
+        self.count();
+        self.inner.list_templates(query).await
+    }
+}
+
+async fn render_letter<C: CarboneApi + ?Sized>(carbone: &C) -> Result<Bytes> {
+    let json_data = JsonData::new(r#"{ "data": { "name": "John" } }"#.to_string())?;
+    carbone
+        .generate_report("letter.html".to_string(), b"<p>{d.name}</p>".to_vec(), json_data, None)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_generic_client() -> std::result::Result<(), CarboneError> {
+        let template_id = TemplateId::from_bytes(b"<p>{d.name}</p>".to_vec(), None)?;
+
+        let server = MockServer::start();
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST").path(format!("/render/{}", template_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.html" }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.html");
+            then.status(200).body("<p>John</p>");
+        });
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+        assert_eq!(render_letter(&carbone).await?, "<p>John</p>");
+
+        // Composed behind a trait object.
+        let carbone: Box<dyn CarboneApi + '_> = Box::new(CountingCarbone::new(carbone));
+        assert_eq!(render_letter(carbone.as_ref()).await?, "<p>John</p>");
+
+        let carbone = Arc::new(CountingCarbone::new(Carbone::new(&config, Some(&api_token))?));
+        assert_eq!(render_letter(&carbone).await?, "<p>John</p>");
+        assert_eq!(carbone.calls(), 2);
+
+        mock_render.assert_hits(3);
+        mock_report.assert_hits(3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_default_generate_report_uploads_unknown_template() -> std::result::Result<(), CarboneError> {
+        let template_id = TemplateId::from_bytes(b"<p>{d.name}</p>".to_vec(), None)?;
+
+        let server = MockServer::start();
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST").path(format!("/render/{}", template_id.as_str()));
+            then.status(404).json_body(json!({
+                "success": false,
+                "error": "Template not found"
+            }));
+        });
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+
+        let carbone = CountingCarbone::new(Carbone::new(&config, Some(&api_token))?);
+
+        match render_letter(&carbone).await {
+            Err(CarboneError::HttpError { status_code, .. }) => assert_eq!(status_code.as_u16(), 404),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        mock_render.assert_hits(2);
+        mock_upload.assert_hits(1);
+        assert_eq!(carbone.calls(), 3);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+use std::fs;
+use std::path::PathBuf;
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rust::batch::*;
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::template::{TemplateId, TemplateSource};
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn create_output_dir(name: &str) -> Result<PathBuf, CarboneError> {
+        let dir = std::env::temp_dir().join(format!("carbone-batch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        Ok(dir)
+    }
+
+    #[test]
+    fn test_dataset_from_csv() -> Result<(), CarboneError> {
+        let dataset = Dataset::from_csv("id,lastname\n1,Wick\n2,\"Doe, Jane\"\n")?;
+
+        assert_eq!(
+            dataset.rows,
+            vec![
+                json!({ "id": "1", "lastname": "Wick" }),
+                json!({ "id": "2", "lastname": "Doe, Jane" })
+            ]
+        );
+
+        let result = Dataset::from_csv("id,lastname\n1,Wick,extra\n");
+        assert!(matches!(result, Err(CarboneError::ParseError(..))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dataset_from_ndjson_and_json_array() -> Result<(), CarboneError> {
+        let dataset = Dataset::from_ndjson("{\"id\": 1}\n\n{\"id\": 2}\n")?;
+        assert_eq!(dataset.rows, vec![json!({ "id": 1 }), json!({ "id": 2 })]);
+
+        let result = Dataset::from_ndjson("{\"id\": 1}\nnot json\n");
+        match result {
+            Err(CarboneError::ParseError(_, message)) => assert!(message.starts_with("line 2:")),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let dataset = Dataset::from_json_array(r#"[{ "id": 1 }, { "id": 2 }]"#)?;
+        assert_eq!(dataset.len(), 2);
+
+        let result = Dataset::from_json_array(r#"{ "id": 1 }"#);
+        assert!(matches!(result, Err(CarboneError::ParseError(..))));
+
+        assert_eq!(DatasetFormat::from_path("rows.JSONL"), Some(DatasetFormat::Ndjson));
+        assert_eq!(DatasetFormat::from_path("rows.txt"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_name() -> Result<(), CarboneError> {
+        let row = json!({ "id": 42, "lastname": "Wick/Doe", "customer": { "city": "Paris" } });
+
+        let output_name = OutputName::new("{id}-{lastname}-{customer.city}-{#}.pdf")?;
+        assert_eq!(output_name.format(&row, 3)?, "42-Wick_Doe-Paris-3.pdf");
+
+        let output_name = OutputName::new("{firstname}.pdf")?;
+        assert!(output_name.format(&row, 1).is_err());
+
+        assert!(OutputName::new("").is_err());
+        assert!(OutputName::new("letters/{id}.pdf").is_err());
+        assert!(OutputName::new("letters\\{id}.pdf").is_err());
+
+        let output_name = OutputName::new("{name}")?;
+        for name in [".", ".."] {
+            assert!(output_name.format(&json!({ "name": name }), 1).is_err());
+        }
+        assert_eq!(output_name.format(&json!({ "name": "..." }), 1)?, "...");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_batch() -> Result<(), CarboneError> {
+        let content = b"<p>{d.lastname}</p>".to_vec();
+        let template_id = TemplateId::from_bytes(content.clone(), None)?;
+
+        let server = MockServer::start();
+
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+        let mock_render_wick = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()))
+                .json_body(json!({ "convertTo": "pdf", "data": { "id": "1", "lastname": "Wick" } }));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "wick.pdf" }
+            }));
+        });
+        let mock_render_doe = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()))
+                .body_contains("Doe");
+            then.status(400).json_body(json!({
+                "success": false,
+                "error": "Invalid data"
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/wick.pdf");
+            then.status(200).body("wick report");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+
+        let output_dir = create_output_dir("async")?;
+        fs::create_dir_all(&output_dir)?;
+        fs::write(output_dir.join("3-Smith.pdf"), "already rendered")?;
+
+        let dataset = Dataset::from_csv("id,lastname\n1,Wick\n2,Doe\n3,Smith\n4,\n")?;
+        let mut options = BatchOptions::new(&output_dir, OutputName::new("{id}-{lastname}.pdf")?);
+        options.concurrency = 2;
+        options.render_options.insert("convertTo".to_string(), json!("pdf"));
+
+        let template = TemplateSource::from_bytes("letter.html", content);
+        let report = render_batch(&carbone, template, &dataset, &options).await?;
+
+        mock_upload.assert();
+        mock_render_wick.assert();
+        mock_render_doe.assert();
+        mock_report.assert();
+
+        assert_eq!(report.template_id, template_id);
+        assert_eq!(
+            report.rendered,
+            vec![BatchEntry { row: 1, output: output_dir.join("1-Wick.pdf") }]
+        );
+        assert_eq!(
+            report.skipped,
+            vec![BatchEntry { row: 3, output: output_dir.join("3-Smith.pdf") }]
+        );
+        assert_eq!(report.failures.len(), 2);
+        assert_eq!(report.failures[0].row, 2);
+        assert_eq!(report.failures[0].output, Some(output_dir.join("2-Doe.pdf")));
+        assert_eq!(report.failures[1].row, 4);
+        assert_eq!(report.failures[1].output, None);
+        assert!(report.has_failures());
+
+        assert_eq!(fs::read_to_string(output_dir.join("1-Wick.pdf"))?, "wick report");
+        assert_eq!(fs::read_to_string(output_dir.join("3-Smith.pdf"))?, "already rendered");
+        assert!(!output_dir.join("2-Doe.pdf").exists());
+
+        fs::remove_dir_all(&output_dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_batch_duplicate_output_names() -> Result<(), CarboneError> {
+        let content = b"<p>{d.lastname}</p>".to_vec();
+        let template_id = TemplateId::from_bytes(content.clone(), None)?;
+
+        let server = MockServer::start();
+
+        server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+        let mock_render = server.mock(|when, then| {
+            when.method("POST").path(format!("/render/{}", template_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "report.pdf" }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method("GET").path("/render/report.pdf");
+            then.status(200).body("report");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+
+        let output_dir = create_output_dir("duplicates")?;
+        let dataset = Dataset::from_csv("lastname\nWick\nDoe\nWick\nWick\n")?;
+        let options = BatchOptions::new(&output_dir, OutputName::new("{lastname}.pdf")?);
+
+        let template = TemplateSource::from_bytes("letter.html", content);
+        let report = render_batch(&carbone, template, &dataset, &options).await?;
+
+        mock_render.assert_hits(2);
+        assert_eq!(report.rendered.len(), 2);
+        assert_eq!(report.failures.len(), 2);
+        for (failure, row) in report.failures.iter().zip([3, 4]) {
+            assert_eq!(failure.row, row);
+            assert_eq!(failure.output, Some(output_dir.join("Wick.pdf")));
+            assert_eq!(failure.error, "duplicate output name, same as row 1");
+        }
+
+        fs::remove_dir_all(&output_dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_batch_nothing_to_render() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(None)?;
+
+        let carbone = Carbone::new(&config, None)?;
+
+        let output_dir = create_output_dir("empty")?;
+        let options = BatchOptions::new(&output_dir, OutputName::new("{#}.pdf")?);
+
+        let template = TemplateSource::from_bytes("letter.html", b"<p>{d.id}</p>".to_vec());
+        let report = render_batch(&carbone, template, &Dataset::default(), &options).await?;
+
+        assert!(report.rendered.is_empty());
+        assert!(!report.has_failures());
+        assert!(!output_dir.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_batch_invalid_output_names() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(None)?;
+
+        let carbone = Carbone::new(&config, None)?;
+
+        let output_dir = create_output_dir("invalid")?;
+        let dataset = Dataset::from_json_array(r#"[{ "file": "." }, { "file": ".." }]"#)?;
+        let options = BatchOptions::new(&output_dir, OutputName::new("{file}")?);
+
+        let template = TemplateSource::from_bytes("letter.html", b"<p>{d.file}</p>".to_vec());
+        let report = render_batch(&carbone, template, &dataset, &options).await?;
+
+        assert!(report.rendered.is_empty());
+        assert_eq!(report.failures.len(), 2);
+        for (failure, row) in report.failures.iter().zip([1, 2]) {
+            assert_eq!(failure.row, row);
+            assert_eq!(failure.output, None);
+        }
+        assert!(!output_dir.exists());
+
+        Ok(())
+    }
+}
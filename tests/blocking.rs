@@ -1,13 +1,24 @@
 use std::fs;
+use std::ops::ControlFlow;
+use std::sync::Arc;
 
 use httpmock::prelude::*;
+use reqwest::StatusCode;
 use serde_json::json;
 
+use carbone_sdk_rust::api::CarboneApiBlocking;
+#[cfg(feature = "batch")]
+use carbone_sdk_rust::batch::{render_batch_blocking, BatchOptions, Dataset, OutputName};
 use carbone_sdk_rust::blocking::Carbone;
 use carbone_sdk_rust::carbone_response::*;
 use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::interceptor::{InterceptedRequest, InterceptedResponse, Interceptor};
+use carbone_sdk_rust::metrics::PrometheusMetrics;
 use carbone_sdk_rust::render::*;
-use carbone_sdk_rust::types::JsonData;
+use carbone_sdk_rust::store::MemoryTemplateStore;
+use carbone_sdk_rust::types::{Endpoint, JsonData};
+#[cfg(feature = "watch")]
+use carbone_sdk_rust::watch::{watch_blocking, WatchEvent, WatchOptions};
 
 mod helper;
 
@@ -48,12 +59,12 @@ mod tests {
 
         let api_token = &helper.create_api_token()?;
 
-        let carbone = Carbone::new(&config, &api_token)?;
+        let carbone = Carbone::new(&config, api_token)?;
         let is_deleted = carbone.delete_template(template_id)?;
 
         mock_server.assert();
 
-        assert_eq!(is_deleted, true);
+        assert!(is_deleted);
 
         Ok(())
     }
@@ -111,7 +122,10 @@ mod tests {
         let carbone = Carbone::new(&config, &api_token)?;
         let result = carbone.delete_template(template_id);
 
-        let expected_error = CarboneError::Error(error_msg);
+        let expected_error = CarboneError::HttpError {
+            status_code: StatusCode::BAD_REQUEST,
+            error_message: error_msg,
+        };
 
         mock_server.assert();
 
@@ -210,7 +224,10 @@ mod tests {
 
         let result = carbone.download_template(&template_id);
 
-        let expected_error = CarboneError::Error(error_msg);
+        let expected_error = CarboneError::HttpError {
+            status_code: StatusCode::BAD_REQUEST,
+            error_message: error_msg,
+        };
 
         mock_server.assert();
 
@@ -420,7 +437,10 @@ mod tests {
                 .json_body_obj(&body);
         });
 
-        let expected_error = CarboneError::Error(error_msg);
+        let expected_error = CarboneError::HttpError {
+            status_code: StatusCode::BAD_REQUEST,
+            error_message: error_msg,
+        };
         let result = carbone.get_report(render_id);
 
         mock_server.assert();
@@ -469,9 +489,10 @@ mod tests {
         let json_data = JsonData::new(json_data)?;
         let result = carbone.render_data(template_id, json_data);
 
-        let expected_error = CarboneError::Error(
-            "Invalid or undefined TemplateId or RenderId in the URL".to_string(),
-        );
+        let expected_error = CarboneError::HttpError {
+            status_code: StatusCode::BAD_REQUEST,
+            error_message: "Invalid or undefined TemplateId or RenderId in the URL".to_string(),
+        };
 
         mock_server.assert();
         assert!(result.is_err());
@@ -580,7 +601,9 @@ mod tests {
 
         // Create a mock on the server.
         let m = server.mock(|when, then| {
-            when.method("POST").path("/template");
+            when.method("POST")
+                .path("/template")
+                .body_contains("name=\"payload\"\r\n\r\nsalt1234");
             then.status(200)
                 .header("content-type", "application/json")
                 .json_body_obj(&body);
@@ -603,6 +626,252 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_upload_template_from_bytes() -> Result<(), CarboneError> {
+        let content = b"<p>{d.firstname}</p>".to_vec();
+        let template_id = TemplateId::from_bytes(content.clone(), None)?;
+
+        let server = MockServer::start();
+
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("filename=\"letter.html\"\r\nContent-Type: text/html");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?.with_template_id_verification();
+        let result = carbone.upload_template(TemplateSource::from_bytes("letter.html", content), None)?;
+
+        mock_upload.assert();
+        assert_eq!(result, template_id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_report_with_template_name() -> Result<(), CarboneError> {
+        let content = b"<p>{d.firstname}</p>".to_vec();
+        let template_id = TemplateId::from_bytes(content.clone(), None)?;
+
+        let store = MemoryTemplateStore::default().with_template("letters/letter.html", content);
+
+        let server = MockServer::start();
+
+        let mock_download = server.mock(|when, then| {
+            when.method("GET")
+                .path(format!("/template/{}", template_id.as_str()));
+            then.status(404).json_body(json!({
+                "success": false,
+                "error": "Template not found"
+            }));
+        });
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("filename=\"letter.html\"");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.pdf");
+            then.status(200).body("report content");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?.with_template_store(Arc::new(store));
+
+        let json_data = JsonData::new(r#"{ "data": { "firstname": "John" }, "convertTo": "pdf" }"#.to_string())?;
+        let report_content = carbone.generate_report_with_template_name("letters/letter.html", json_data, None)?;
+
+        mock_download.assert();
+        mock_upload.assert();
+        mock_render.assert();
+        mock_report.assert();
+        assert_eq!(report_content, "report content");
+
+        Ok(())
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_render_batch() -> Result<(), CarboneError> {
+        let content = b"<p>{d.lastname}</p>".to_vec();
+        let template_id = TemplateId::from_bytes(content.clone(), None)?;
+
+        let server = MockServer::start();
+
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.pdf");
+            then.status(200).body("report content");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        let output_dir = std::env::temp_dir().join(format!("carbone-batch-blocking-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&output_dir);
+
+        let dataset = Dataset::from_ndjson("{\"id\": 1}\n{\"id\": 2}\n{\"id\": 3}\n")?;
+        let mut options = BatchOptions::new(&output_dir, OutputName::new("letter-{id}.pdf")?);
+        options.concurrency = 2;
+
+        let template = TemplateSource::from_bytes("letter.html", content);
+        let report = render_batch_blocking(&carbone, template, &dataset, &options)?;
+
+        mock_upload.assert();
+        mock_render.assert_hits(3);
+        mock_report.assert_hits(3);
+
+        assert!(!report.has_failures());
+        assert_eq!(
+            report.rendered.iter().map(|entry| entry.row).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(fs::read_to_string(output_dir.join("letter-2.pdf"))?, "report content");
+
+        fs::remove_dir_all(&output_dir)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn test_watch() -> Result<(), CarboneError> {
+        let dir = std::env::temp_dir().join(format!("carbone-watch-blocking-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        let template_path = dir.join("letter.html");
+        let data_path = dir.join("letter.json");
+        let output_path = dir.join("preview.pdf");
+        fs::write(&template_path, "<p>{d.name}</p>")?;
+        fs::write(&data_path, r#"{ "data": { "name": "John" }, "convertTo": "pdf" }"#)?;
+
+        let template_id = TemplateId::from_bytes(b"<p>{d.name}</p>".to_vec(), None)?;
+
+        let server = MockServer::start();
+
+        let mock_download = server.mock(|when, then| {
+            when.method("GET")
+                .path(format!("/template/{}", template_id.as_str()));
+            then.status(200).body("<p>{d.name}</p>");
+        });
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.pdf");
+            then.status(200).body("report content");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        let options = WatchOptions {
+            output: Some(output_path.clone()),
+            ..Default::default()
+        };
+
+        let mut rendered = Vec::new();
+        watch_blocking(&carbone, &template_path, &data_path, &options, |event| {
+            if let WatchEvent::Rendered { template_id, .. } = event {
+                rendered.push(template_id.clone());
+            }
+            ControlFlow::Break(())
+        })?;
+
+        mock_download.assert();
+        mock_render.assert();
+        mock_report.assert();
+        assert_eq!(rendered, vec![template_id]);
+        assert_eq!(fs::read_to_string(&output_path)?, "report content");
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_upload_template_with_template_id_verification() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.odt".to_string(), None)?;
+        let template_id_expected = template_file.generate_id(Some("salt1234"))?;
+
+        let server = MockServer::start();
+
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("name=\"payload\"\r\n\r\nsalt1234");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": "other_template_id" }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+        let template_id = carbone.upload_template(&template_file, Some("salt1234"))?;
+        assert_eq!(template_id.as_str(), "other_template_id");
+
+        let carbone = carbone.with_template_id_verification();
+        let result = carbone.upload_template(&template_file, Some("salt1234"));
+
+        mock_upload.assert_hits(2);
+        assert!(matches!(result, Err(CarboneError::TemplateIdMismatch { expected, .. })
+            if expected == template_id_expected));
+
+        Ok(())
+    }
+
     #[test]
     fn test_upload_template_unsupported_file_format_given() -> Result<(), CarboneError> {
         let error_msg = "Template format not supported, it must be an XML-based document: DOCX, XLSX, PPTX, ODT, ODS, ODP, XHTML, HTML or an XML file";
@@ -632,10 +901,13 @@ mod tests {
 
         let template_file = TemplateFile::new("tests/data/template.test.txt".to_string(), None)?;
 
-        let carbone = Carbone::new(&config, &api_token)?;
+        let carbone = Carbone::new(&config, api_token)?;
         let result = carbone.upload_template(&template_file, None);
 
-        let expected_error = CarboneError::Error(error_msg.to_string());
+        let expected_error = CarboneError::HttpError {
+            status_code: StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            error_message: error_msg.to_string(),
+        };
 
         // Assert
         m.assert();
@@ -644,4 +916,409 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_get_status() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(200).json_body(json!({
+                "success": true,
+                "code": 200,
+                "message": "OK",
+                "version": "4.22.11"
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+        let status = carbone.get_status()?;
+
+        mock_server.assert();
+        assert!(status.success);
+        assert_eq!(status.version, Some("4.22.11".to_string()));
+        assert!(status.is_compatible(&config));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_carbone_api_blocking() -> Result<(), CarboneError> {
+        let template_content = b"<p>{d.name}</p>".to_vec();
+        let template_id = TemplateId::from_bytes(template_content.clone(), None)?;
+
+        let server = MockServer::start();
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST").path(format!("/render/{}", template_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.html" }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.html");
+            then.status(200).body("<p>John</p>");
+        });
+        let mock_download = server.mock(|when, then| {
+            when.method("GET").path(format!("/template/{}", template_id.as_str()));
+            then.status(200).body("<p>{d.name}</p>");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+        let carbone: &dyn CarboneApiBlocking = &carbone;
+
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" } }"#.to_string())?;
+        let report = carbone.generate_report_with_file(
+            TemplateSource::from_bytes("letter.html", template_content),
+            json_data,
+            None,
+        )?;
+
+        assert_eq!(report, "<p>John</p>");
+        mock_download.assert();
+        mock_render.assert();
+        mock_report.assert();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wait_until_ready_starting() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(503).json_body(json!({ "success": false, "message": "starting" }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+
+        match carbone.get_status() {
+            Err(CarboneError::HttpError { status_code, error_message }) => {
+                assert_eq!(status_code, StatusCode::SERVICE_UNAVAILABLE);
+                assert_eq!(error_message, "starting");
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let result = carbone.wait_until_ready(std::time::Duration::from_millis(600));
+
+        assert!(matches!(result, Err(CarboneError::NotReady(_))));
+        assert!(mock_server.hits() >= 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wait_until_ready_timeout() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+
+        let api_version = ApiVersion::new("4".to_string())?;
+        let config = Config::new("http://127.0.0.1:1".to_string(), 1, api_version)?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+        let result = carbone.wait_until_ready(std::time::Duration::from_millis(600));
+
+        assert!(matches!(result, Err(CarboneError::NotReady(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_upload_template_with_options() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("name=\"comment\"\r\n\r\nFirst draft")
+                .body_contains("filename=\"template.test.odt\"");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": {
+                    "templateId": "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114",
+                    "templateFileExtension": "odt",
+                    "comment": "First draft"
+                }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let template_file = TemplateFile::new("tests/data/template.test.odt".to_string(), None)?;
+        let options = UploadOptions {
+            comment: Some("First draft".to_string()),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, &api_token)?;
+        let template_info = carbone.upload_template_with_options(&template_file, &options)?;
+
+        mock_server.assert();
+        assert_eq!(template_info.comment, Some("First draft".to_string()));
+        assert_eq!(template_info.extension, Some("odt".to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_templates() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET")
+                .path("/templates")
+                .query_param("includeVersions", "true");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": [
+                    { "id": "1", "versionId": "b", "name": "B" },
+                    { "id": "1", "versionId": "a", "name": "A" }
+                ]
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let query = TemplateListQuery {
+            include_versions: true,
+            sort_by: Some(TemplateSortBy::Name),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, &api_token)?;
+        let page = carbone.list_templates(&query)?;
+
+        mock_server.assert();
+        assert_eq!(page.templates.len(), 2);
+        assert_eq!(page.templates[0].version_id, Some(VersionId::new("a")?));
+        assert!(!page.has_more);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_upload_and_deploy_template_version() -> Result<(), CarboneError> {
+        let template_id = TemplateId::new("tpl_contract")?;
+
+        let server = MockServer::start();
+
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("name=\"id\"\r\n\r\ntpl_contract");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "id": "tpl_contract", "versionId": "v2" }
+            }));
+        });
+        let mock_deploy = server.mock(|when, then| {
+            when.method("PATCH").path("/template/v2");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let template_file = TemplateFile::new("tests/data/template.test.odt".to_string(), None)?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+        let version =
+            carbone.upload_template_version(&template_id, &template_file, &UploadOptions::default())?;
+        let deployed = carbone.deploy_template_version(version.version_id.as_ref().unwrap())?;
+
+        mock_upload.assert();
+        mock_deploy.assert();
+        assert!(deployed);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sync")]
+    #[test]
+    fn test_sync_templates() -> Result<(), CarboneError> {
+        let dir = std::env::temp_dir().join(format!("carbone-blocking-sync-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("letter.html"), "<p>{d.name}</p>")?;
+
+        let server = MockServer::start();
+
+        let mock_list = server.mock(|when, then| {
+            when.method("GET").path("/templates");
+            then.status(200).json_body(json!({ "success": true, "data": [] }));
+        });
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": "letter_id" }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?;
+        let options = carbone_sdk_rust::sync::SyncOptions::default();
+        let report = carbone_sdk_rust::sync::sync_templates_blocking(&carbone, &dir, &options)?;
+
+        mock_list.assert();
+        mock_upload.assert();
+        assert_eq!(report.uploaded[0].path, "letter.html");
+        assert_eq!(report.manifest.get("letter.html"), Some(&TemplateId::new("letter_id")?));
+
+        fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_data_strict_validation() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.test.odt".to_string(), None)?;
+
+        let mut validator = validate::DataValidator::default();
+        let template_id = validator.add_template(&template_file, None)?;
+
+        let server = MockServer::start();
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?.with_strict_validation(validator);
+
+        let json_data = JsonData::new(r#"{ "data": { "firstname": "John" } }"#.to_string())?;
+        let result = carbone.render_data(template_id.clone(), json_data);
+
+        mock_render.assert_hits(0);
+        assert!(matches!(result, Err(CarboneError::DataValidation(issues))
+            if issues == vec![validate::DataIssue::MissingPath("d.lastname".to_string())]));
+
+        let json_data = JsonData::new(
+            r#"{ "data": { "firstname": "John", "lastname": "Wick" } }"#.to_string(),
+        )?;
+        carbone.render_data(template_id, json_data)?;
+
+        mock_render.assert();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_metrics() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": "template_id" }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method("POST").path("/render/template_id");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.pdf");
+            then.status(200).body("report content");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let metrics = Arc::new(PrometheusMetrics::new());
+        let carbone = Carbone::new(&config, &api_token)?.with_metrics(metrics.clone());
+
+        let template = TemplateSource::from_bytes("letter.html", b"<p>{d.name}</p>".to_vec());
+        let template_id = carbone.upload_template(template, None)?;
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" } }"#.to_string())?;
+        carbone.generate_report_with_template_id(template_id, json_data)?;
+
+        assert_eq!(metrics.requests(Endpoint::Upload, Some(StatusCode::OK)), 1);
+        assert_eq!(metrics.requests(Endpoint::Render, Some(StatusCode::OK)), 1);
+        assert_eq!(metrics.uploaded_bytes(), 15);
+        assert_eq!(metrics.downloaded_bytes(Endpoint::Report), 14);
+        assert_eq!(metrics.renders(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interceptor() -> Result<(), CarboneError> {
+        #[derive(Debug)]
+        struct TenantHeader;
+
+        impl Interceptor for TenantHeader {
+            fn before_send(&self, _endpoint: Endpoint, request: &mut InterceptedRequest) -> Result<(), CarboneError> {
+                request.headers.insert("x-tenant-id", "acme".parse().unwrap());
+                Ok(())
+            }
+
+            fn after_receive(
+                &self,
+                _endpoint: Endpoint,
+                response: Result<InterceptedResponse, CarboneError>,
+            ) -> Result<InterceptedResponse, CarboneError> {
+                let mut response = response?;
+                response.body = response.body.to_ascii_uppercase().into();
+                Ok(response)
+            }
+        }
+
+        let server = MockServer::start();
+
+        let mock_report = server.mock(|when, then| {
+            when.method("GET")
+                .path("/render/render_id.pdf")
+                .header("x-tenant-id", "acme");
+            then.status(200).body("report content");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, &api_token)?.with_interceptor(Arc::new(TenantHeader));
+        let report = carbone.get_report(&RenderId::new("render_id.pdf")?)?;
+
+        mock_report.assert();
+        assert_eq!(report.as_ref(), b"REPORT CONTENT");
+
+        Ok(())
+    }
 }
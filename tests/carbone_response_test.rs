@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
 
-    use carbone_sdk_rust::carbone_response::{APIResponse, APIResponseData};
+    use carbone_sdk_rust::carbone_response::{APIResponse, APIResponseData, TemplateInfo, TemplateInfoResponse};
     use carbone_sdk_rust::errors::CarboneError;
     use carbone_sdk_rust::render::RenderId;
     use carbone_sdk_rust::template::TemplateId;
@@ -199,4 +199,85 @@ mod tests {
 
         assert_eq!(carbone_resp, deserialized);
     }
+
+    #[test]
+    fn test_deserialize_template_info_v4() -> Result<(), CarboneError> {
+        let resp_body = r#"
+        {
+            "success": true,
+            "data": {
+                "id": "8aedb2fb30a3b4b22fa8b8b5e6d1d3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0",
+                "templateId": "8aedb2fb30a3b4b22fa8b8b5e6d1d3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0",
+                "versionId": "5e7e3b9f1c4d2a8b6f0e9d7c5b3a1f2e4d6c8b0a9f7e5d3c1b2a4f6e8d0c9b7a",
+                "type": "docx",
+                "size": 10542,
+                "createdAt": 1720000000,
+                "deployedAt": 1720000100,
+                "name": "Invoice",
+                "comment": "First draft",
+                "category": "accounting",
+                "tags": ["invoice", "2024"]
+            }
+        }
+        "#;
+
+        let deserialized: TemplateInfoResponse = serde_json::from_str(resp_body).unwrap();
+        let template_info = deserialized.data.unwrap();
+
+        assert!(deserialized.success);
+        assert_eq!(
+            template_info.id,
+            TemplateId::new("8aedb2fb30a3b4b22fa8b8b5e6d1d3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0")?
+        );
+        assert_eq!(template_info.extension, Some("docx".to_string()));
+        assert_eq!(template_info.size, Some(10542));
+        assert_eq!(template_info.deployed_at, Some(1720000100));
+        assert_eq!(template_info.category, Some("accounting".to_string()));
+        assert_eq!(template_info.tags, vec!["invoice", "2024"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_template_info_v3() -> Result<(), CarboneError> {
+        let resp_body = r#"
+        {
+            "templateId": "2436447a0d5954de2ad9cd28376f9e743a8fe732b829a1d37b60f51539dad7ad",
+            "templateFileExtension": "odt"
+        }
+        "#;
+
+        let template_info: TemplateInfo = serde_json::from_str(resp_body).unwrap();
+
+        assert_eq!(
+            template_info.id.as_str(),
+            "2436447a0d5954de2ad9cd28376f9e743a8fe732b829a1d37b60f51539dad7ad"
+        );
+        assert_eq!(template_info.extension, Some("odt".to_string()));
+        assert!(template_info.tags.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_template_info_round_trip() -> Result<(), CarboneError> {
+        let template_info: TemplateInfo = serde_json::from_str(
+            r#"{"id": "2436447a0d5954de2ad9cd28376f9e743a8fe732b829a1d37b60f51539dad7ad", "type": "odt", "tags": ["a"]}"#,
+        )
+        .unwrap();
+
+        let serialized = serde_json::to_string(&template_info).unwrap();
+        let deserialized: TemplateInfo = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(template_info, deserialized);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_template_info_without_id() {
+        let result = serde_json::from_str::<TemplateInfo>(r#"{"type": "odt"}"#);
+
+        assert!(result.is_err());
+    }
 }
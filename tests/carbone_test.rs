@@ -50,12 +50,12 @@ mod tests {
 
         let api_token = &helper.create_api_token()?;
 
-        let carbone = Carbone::new(&config, Some(&api_token))?;
+        let carbone = Carbone::new(&config, Some(api_token))?;
         let is_deleted = carbone.delete_template(template_id).await.unwrap();
 
         mock_server.assert();
 
-        assert_eq!(is_deleted, true);
+        assert!(is_deleted);
 
         Ok(())
     }
@@ -113,7 +113,10 @@ mod tests {
         let carbone = Carbone::new(&config, Some(&api_token))?;
         let result = carbone.delete_template(template_id).await;
 
-        let expected_error = CarboneError::Error(error_msg);
+        let expected_error = CarboneError::HttpError {
+            status_code: StatusCode::BAD_REQUEST,
+            error_message: error_msg,
+        };
 
         mock_server.assert();
 
@@ -212,7 +215,10 @@ mod tests {
 
         let result = carbone.download_template(&template_id).await;
 
-        let expected_error = CarboneError::Error(error_msg);
+        let expected_error = CarboneError::HttpError {
+            status_code: StatusCode::BAD_REQUEST,
+            error_message: error_msg,
+        };
 
         mock_server.assert();
 
@@ -295,7 +301,7 @@ mod tests {
 
         let template_name = "template.odt".to_string();
         let template_path = format!("tests/data/{}", template_name);
-        let template_data = fs::read(template_path.to_owned())?;
+        let template_data = fs::read(&template_path)?;
 
         let template_file = TemplateFile::new(template_path, Some(template_data.to_owned()))?;
         let template_id = template_file.generate_id(None)?;
@@ -328,7 +334,7 @@ mod tests {
         });
 
         let result = carbone
-            .generate_report(template_name, template_data, json_data, None, None)
+            .generate_report(template_name, template_data, json_data, None)
             .await
             .unwrap();
 
@@ -357,7 +363,7 @@ mod tests {
 
         let template_name = "template2.odt".to_string();
         let template_path = format!("tests/data/{}", template_name);
-        let template_data = fs::read(template_path.to_owned())?;
+        let template_data = fs::read(&template_path)?;
 
         let template_file = TemplateFile::new(template_path, Some(template_data.to_owned()))?;
         let template_id = template_file.generate_id(None)?;
@@ -388,7 +394,7 @@ mod tests {
 
         let expected_content = fs::read(file_path)?;
 
-        let mock_render_response_false = server.mock(|when, then| {
+        let _mock_render_response_false = server.mock(|when, then| {
             when.method("POST")
                 .path(format!("/render/{}", template_id.as_str()));
             then.status(404)
@@ -398,9 +404,9 @@ mod tests {
             }));
         });
 
-        let mock_upload_template = server.mock(|when, then| {
+        let _mock_upload_template = server.mock(|when, then| {
             when.method("POST")
-                .path(format!("/template"));
+                .path("/template");
             then.status(200)
                 .header("content-type", "application/json")
                 .json_body_obj(&body);
@@ -425,7 +431,7 @@ mod tests {
         });
 
         let result = carbone
-            .generate_report(template_name, template_data, json_data, None, None)
+            .generate_report(template_name, template_data, json_data, None)
             .await
             .unwrap();
 
@@ -522,7 +528,10 @@ mod tests {
                 .json_body_obj(&body);
         });
 
-        let expected_error = CarboneError::Error(error_msg);
+        let expected_error = CarboneError::HttpError {
+            status_code: StatusCode::BAD_REQUEST,
+            error_message: error_msg,
+        };
         let result = carbone.get_report(render_id).await;
 
         mock_server.assert();
@@ -665,6 +674,101 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_upload_template_with_options() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("name=\"name\"\r\n\r\nInvoice")
+                .body_contains("name=\"tags\"\r\n\r\n[\"invoice\",\"2024\"]")
+                .body_contains("name=\"category\"\r\n\r\naccounting")
+                .body_contains("name=\"expireAt\"\r\n\r\n1900000000");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": {
+                    "id": "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114",
+                    "versionId": "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114",
+                    "type": "odt",
+                    "size": 9876,
+                    "createdAt": 1720000000,
+                    "name": "Invoice",
+                    "category": "accounting",
+                    "tags": ["invoice", "2024"],
+                    "expireAt": 1900000000
+                }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let file_content = fs::read("tests/data/template.odt")?;
+
+        let options = UploadOptions {
+            name: Some("Invoice".to_string()),
+            category: Some("accounting".to_string()),
+            tags: vec!["invoice".to_string(), "2024".to_string()],
+            expire_at: Some(1900000000),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+        let template_info = carbone
+            .upload_template_with_options(TemplateSource::from_bytes("template.odt", file_content), &options)
+            .await?;
+
+        mock_server.assert();
+        assert_eq!(
+            template_info.id.as_str(),
+            "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114"
+        );
+        assert_eq!(template_info.name, Some("Invoice".to_string()));
+        assert_eq!(template_info.extension, Some("odt".to_string()));
+        assert_eq!(template_info.size, Some(9876));
+        assert_eq!(template_info.expire_at, Some(1900000000));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_template_with_options_failed() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let error_msg = "Template format not supported";
+
+        let mock_server = server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(400).json_body(json!({
+                "success": false,
+                "error": error_msg,
+                "code": "w118"
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+        let result = carbone
+            .upload_template_with_options(TemplateSource::from_bytes("template.txt", b"hello".to_vec()), &UploadOptions::default())
+            .await;
+
+        mock_server.assert();
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            CarboneError::HttpError {
+                status_code: StatusCode::BAD_REQUEST,
+                error_message: error_msg.to_string(),
+            }.to_string()
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_upload_template_with_payload() -> Result<(), CarboneError> {
         let template_id_expected = TemplateId::new(
@@ -689,7 +793,9 @@ mod tests {
 
         // Create a mock on the server.
         let m = server.mock(|when, then| {
-            when.method("POST").path("/template");
+            when.method("POST")
+                .path("/template")
+                .body_contains("name=\"payload\"\r\n\r\nsalt1234");
             then.status(200)
                 .header("content-type", "application/json")
                 .json_body_obj(&body);
@@ -717,6 +823,176 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_upload_template_with_template_id_verification() -> Result<(), CarboneError> {
+        let file_content = fs::read("tests/data/template.odt")?;
+        let template_id_expected = TemplateId::from_bytes(file_content.clone(), Some("salt1234"))?;
+
+        let server = MockServer::start();
+
+        let mut mock_upload = server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id_expected.as_str() }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_template_id_verification();
+
+        let template_id = carbone
+            .upload_template("template.odt", file_content.clone(), Some("salt1234"))
+            .await?;
+
+        mock_upload.assert();
+        assert_eq!(template_id, template_id_expected);
+
+        mock_upload.delete();
+        server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": "other_template_id" }
+            }));
+        });
+
+        let result = carbone
+            .upload_template("template.odt", file_content, Some("salt1234"))
+            .await;
+
+        match result {
+            Err(CarboneError::TemplateIdMismatch { expected, received }) => {
+                assert_eq!(expected, template_id_expected);
+                assert_eq!(received.as_str(), "other_template_id");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_template_with_template_id_verification_api_v4() -> Result<(), CarboneError> {
+        let file_content = fs::read("tests/data/template.test.odt")?;
+        let template_id_expected = TemplateId::new(
+            "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114".to_string(),
+        )?;
+
+        let server = MockServer::start();
+
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST").path("/template").header("carbone-version", "4");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": {
+                    "id": "1",
+                    "versionId": "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114",
+                    "templateId": "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114"
+                }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_template_id_verification();
+
+        let template_id = carbone
+            .upload_template("template.test.odt", file_content.clone(), None)
+            .await?;
+
+        mock_upload.assert();
+        assert_eq!(template_id, template_id_expected);
+
+        let config = Config::new(config.api_url.clone(), config.api_timeout, ApiVersion::new("5".to_string())?)?;
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_template_id_verification();
+
+        let result = carbone.upload_template("template.test.odt", file_content, None).await;
+
+        assert!(matches!(result, Err(CarboneError::Error(_))));
+        mock_upload.assert_hits(1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_report_with_payload() -> Result<(), CarboneError> {
+        let template_data = fs::read("tests/data/template.odt")?;
+        let template_id = TemplateId::from_bytes(template_data.clone(), Some("salt1234"))?;
+
+        let server = MockServer::start();
+
+        let mock_render_missing = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()))
+                .body_contains("firstname");
+            then.status(404).json_body(json!({ "success": false, "error": "not found" }));
+        });
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("name=\"payload\"\r\n\r\nsalt1234");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?.with_template_id_verification();
+
+        let json_data = JsonData::new(r#"{ "data": { "firstname": "John" } }"#.to_string())?;
+        let result = carbone
+            .generate_report("template.odt".to_string(), template_data, json_data, Some("salt1234"))
+            .await;
+
+        // the render after the upload targets the same template ID
+        mock_render_missing.assert_hits(2);
+        mock_upload.assert();
+        assert!(matches!(result, Err(CarboneError::HttpError { .. })));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_template_file_from_reader() -> Result<(), CarboneError> {
+        let content = b"<p>{d.firstname}</p>".to_vec();
+        let template_id = TemplateId::from_bytes(content.clone(), None)?;
+
+        let server = MockServer::start();
+
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("filename=\"letter.html\"\r\nContent-Type: text/html")
+                .body_contains("<p>{d.firstname}</p>");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?.with_template_id_verification();
+
+        let source = TemplateSource::from_reader("templates/letter.html", std::io::Cursor::new(content));
+        let result = carbone.upload_template_file(source, None).await?;
+
+        mock_upload.assert();
+        assert_eq!(result, template_id);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_upload_template_unsupported_file_format_given() -> Result<(), CarboneError> {
         let error_msg = "Template format not supported, it must be an XML-based document: DOCX, XLSX, PPTX, ODT, ODS, ODP, XHTML, HTML or an XML file";
@@ -748,12 +1024,15 @@ mod tests {
         let file_path = format!("tests/data/{}", file_name);
         let filte_content = fs::read(file_path)?;
 
-        let carbone = Carbone::new(&config, Some(&api_token))?;
+        let carbone = Carbone::new(&config, Some(api_token))?;
         let result = carbone
             .upload_template(file_name, filte_content, None)
             .await;
 
-        let expected_error = CarboneError::Error(error_msg.to_string());
+        let expected_error = CarboneError::HttpError {
+            status_code: StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            error_message: error_msg.to_string(),
+        };
 
         // Assert
         m.assert();
@@ -763,6 +1042,397 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_list_templates() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET")
+                .path("/templates")
+                .query_param("category", "accounting")
+                .query_param("search", "invoice")
+                .query_param("limit", "10")
+                .query_param("cursor", "abc");
+            then.status(200).json_body(json!({
+                "success": true,
+                "hasMore": true,
+                "nextCursor": "def",
+                "data": [
+                    { "id": "1", "name": "Invoice A", "size": 300, "createdAt": 3, "type": "docx", "tags": ["2024"] },
+                    { "id": "2", "name": "Invoice B", "size": 100, "createdAt": 1, "type": "odt", "tags": ["2023"] }
+                ]
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let query = TemplateListQuery {
+            category: Some("accounting".to_string()),
+            search: Some("invoice".to_string()),
+            limit: Some(10),
+            cursor: Some("abc".to_string()),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+        let page = carbone.list_templates(&query).await?;
+
+        mock_server.assert();
+
+        let ids: Vec<&str> = page.templates.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        assert_eq!(page.templates[1].extension, Some("odt".to_string()));
+        assert!(page.has_more);
+        assert_eq!(page.next_cursor, Some("def".to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_templates_with_tag_and_sort() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let first_page = server.mock(|when, then| {
+            when.method("GET")
+                .path("/templates")
+                .query_param("category", "accounting")
+                .matches(|req| {
+                    !req.query_params
+                        .iter()
+                        .flatten()
+                        .any(|(name, _)| name == "cursor" || name == "limit")
+                });
+            then.status(200).json_body(json!({
+                "success": true,
+                "hasMore": true,
+                "nextCursor": "page2",
+                "data": [
+                    { "id": "1", "createdAt": 3, "tags": ["2024"] },
+                    { "id": "2", "createdAt": 5, "tags": ["2023"] },
+                    { "id": "3", "createdAt": 2, "tags": ["2024", "draft"] }
+                ]
+            }));
+        });
+        let second_page = server.mock(|when, then| {
+            when.method("GET")
+                .path("/templates")
+                .query_param("category", "accounting")
+                .query_param("cursor", "page2");
+            then.status(200).json_body(json!({
+                "success": true,
+                "hasMore": false,
+                "data": [
+                    { "id": "4", "createdAt": 4, "tags": ["2024"] },
+                    { "id": "5", "createdAt": 1, "tags": ["2024"] }
+                ]
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let mut query = TemplateListQuery {
+            category: Some("accounting".to_string()),
+            tag: Some("2024".to_string()),
+            sort_by: Some(TemplateSortBy::CreatedAt),
+            descending: true,
+            limit: Some(3),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+        let page = carbone.list_templates(&query).await?;
+
+        first_page.assert();
+        second_page.assert();
+
+        let ids: Vec<&str> = page.templates.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["4", "1", "3"]);
+        assert!(!page.has_more);
+        assert_eq!(page.next_cursor, None);
+
+        query.cursor = Some("page2".to_string());
+        let result = carbone.list_templates(&query).await;
+
+        assert!(matches!(result, Err(CarboneError::Error(_))));
+        second_page.assert_hits(1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_templates_failed() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET").path("/templates");
+            then.status(401).json_body(json!({
+                "success": false,
+                "error": "Unauthorized, please provide a valid API key"
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let result = carbone.list_templates(&TemplateListQuery::default()).await;
+
+        mock_server.assert();
+        assert!(matches!(
+            result,
+            Err(CarboneError::HttpError { status_code: StatusCode::UNAUTHORIZED, .. })
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_template_info() -> Result<(), CarboneError> {
+        let template_id = TemplateId::new(
+            "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114".to_string(),
+        )?;
+
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET")
+                .path("/templates")
+                .query_param("id", template_id.as_str())
+                .query_param("limit", "1");
+            then.status(200).json_body(json!({
+                "success": true,
+                "hasMore": false,
+                "data": [
+                    { "id": template_id.as_str(), "type": "odt", "size": 1234, "deployedAt": 1720000000 }
+                ]
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let template_info = carbone.get_template_info(&template_id).await?;
+
+        mock_server.assert();
+        assert_eq!(template_info.id, template_id);
+        assert_eq!(template_info.size, Some(1234));
+        assert_eq!(template_info.deployed_at, Some(1720000000));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_template_info_not_found() -> Result<(), CarboneError> {
+        let template_id = TemplateId::new("unknown_template_id".to_string())?;
+
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET").path("/templates");
+            then.status(200).json_body(json!({ "success": true, "data": [] }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let result = carbone.get_template_info(&template_id).await;
+
+        mock_server.assert();
+        assert!(matches!(result, Err(CarboneError::TemplateIdNotFound(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_template_version() -> Result<(), CarboneError> {
+        let template_id = TemplateId::new("tpl_contract")?;
+
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("name=\"versioning\"\r\n\r\ntrue")
+                .body_contains("name=\"id\"\r\n\r\ntpl_contract")
+                .body_contains("name=\"comment\"\r\n\r\nNew clause");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "id": "tpl_contract", "versionId": "v2", "type": "docx" }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let options = UploadOptions {
+            comment: Some("New clause".to_string()),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, None)?;
+        let version = carbone
+            .upload_template_version(&template_id, TemplateSource::from_bytes("contract.docx", b"content".to_vec()), &options)
+            .await?;
+
+        mock_server.assert();
+        assert_eq!(version.id, template_id);
+        assert_eq!(version.version_id, Some(VersionId::new("v2")?));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_template_versions() -> Result<(), CarboneError> {
+        let template_id = TemplateId::new("tpl_contract")?;
+
+        let server = MockServer::start();
+
+        let first_page = server.mock(|when, then| {
+            when.method("GET")
+                .path("/templates")
+                .query_param("id", "tpl_contract")
+                .query_param("includeVersions", "true")
+                .matches(|req| {
+                    !req.query_params
+                        .iter()
+                        .flatten()
+                        .any(|(name, _)| name == "cursor")
+                });
+            then.status(200).json_body(json!({
+                "success": true,
+                "hasMore": true,
+                "nextCursor": "page2",
+                "data": [ { "id": "tpl_contract", "versionId": "v3" } ]
+            }));
+        });
+        let second_page = server.mock(|when, then| {
+            when.method("GET")
+                .path("/templates")
+                .query_param("cursor", "page2");
+            then.status(200).json_body(json!({
+                "success": true,
+                "hasMore": false,
+                "data": [
+                    { "id": "tpl_contract", "versionId": "v2" },
+                    { "id": "tpl_contract", "versionId": "v1" }
+                ]
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let versions = carbone.list_template_versions(&template_id).await?;
+
+        first_page.assert();
+        second_page.assert();
+
+        let version_ids: Vec<&str> = versions
+            .iter()
+            .map(|v| v.version_id.as_ref().unwrap().as_str())
+            .collect();
+        assert_eq!(version_ids, vec!["v3", "v2", "v1"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deploy_template_version() -> Result<(), CarboneError> {
+        let version_id = VersionId::new("v1")?;
+
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("PATCH")
+                .path("/template/v1")
+                .body_contains("deployedAt");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let deployed = carbone.deploy_template_version(&version_id).await?;
+
+        mock_server.assert();
+        assert!(deployed);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deploy_template_version_not_found() -> Result<(), CarboneError> {
+        let version_id = VersionId::new("unknown")?;
+
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("PATCH").path("/template/unknown");
+            then.status(404).json_body(json!({
+                "success": false,
+                "error": "Template not found"
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let result = carbone.deploy_template_version(&version_id).await;
+
+        mock_server.assert();
+        assert!(matches!(
+            result,
+            Err(CarboneError::HttpError { status_code: StatusCode::NOT_FOUND, .. })
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_report_with_version_id() -> Result<(), CarboneError> {
+        let version_id = VersionId::new("v1")?;
+        let render_id = RenderId::new("MTAuMjAuMjEuMTAgICAg01E98H4R7PMC2H6XSE5Z6J8XYQ.pdf")?;
+
+        let server = MockServer::start();
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST").path("/render/v1");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": render_id.as_str() }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET")
+                .path(format!("/render/{}", render_id.as_str()));
+            then.status(200).body("old contract");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let json_data = JsonData::new(json!({ "data": {} }).to_string())?;
+        let report = carbone
+            .generate_report_with_version_id(&version_id, json_data)
+            .await?;
+
+        mock_render.assert();
+        mock_report.assert();
+        assert_eq!(report.as_ref(), b"old contract");
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_status() -> Result<(), CarboneError> {
         let body : String = "{\"success\":true,\"code\":200,\"message\":\"OK\",\"version\":\"4.22.11\"}".to_string();
@@ -771,7 +1441,7 @@ mod tests {
         // Create a mock on the server.
         let mock_server = server.mock(|when, then| {
             when.method("GET")
-                .path(format!("/status"));
+                .path("/status");
             then.status(200).body(body.clone());
         });
 
@@ -786,7 +1456,102 @@ mod tests {
 
         mock_server.assert();
 
-        assert_eq!(body, response);
+        assert!(response.success);
+        assert_eq!(response.code, Some(200));
+        assert_eq!(response.message, Some("OK".to_string()));
+        assert_eq!(response.version, Some("4.22.11".to_string()));
+        assert!(response.is_compatible(&config));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_status_failed() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+
+        let api_version = ApiVersion::new("4".to_string())?;
+        let config = Config::new("http://bad_url".to_string(), 1, api_version)?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+        let result = carbone.get_status().await;
+
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(200).json_body(json!({
+                "success": true,
+                "code": 200,
+                "message": "OK",
+                "version": "4.22.11"
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let status = carbone
+            .wait_until_ready(std::time::Duration::from_secs(2))
+            .await?;
+
+        mock_server.assert();
+        assert_eq!(status.version, Some("4.22.11".to_string()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_starting() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(503).json_body(json!({ "success": false, "message": "starting" }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?;
+
+        match carbone.get_status().await {
+            Err(CarboneError::HttpError { status_code, error_message }) => {
+                assert_eq!(status_code, StatusCode::SERVICE_UNAVAILABLE);
+                assert_eq!(error_message, "starting");
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let result = carbone
+            .wait_until_ready(std::time::Duration::from_millis(600))
+            .await;
+
+        assert!(matches!(result, Err(CarboneError::NotReady(_))));
+        assert!(mock_server.hits() >= 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_ready_timeout() -> Result<(), CarboneError> {
+        let api_version = ApiVersion::new("4".to_string())?;
+        let config = Config::new("http://127.0.0.1:1".to_string(), 1, api_version)?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let result = carbone
+            .wait_until_ready(std::time::Duration::from_millis(600))
+            .await;
+
+        assert!(matches!(result, Err(CarboneError::NotReady(_))));
+
         Ok(())
     }
 }
@@ -0,0 +1,304 @@
+use std::fs;
+use std::process::{Command, Output};
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::template::TemplateFile;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn carbone(server: Option<&MockServer>, args: &[&str]) -> Result<Output, CarboneError> {
+        run(env!("CARGO_BIN_EXE_carbone"), server, args)
+    }
+
+    #[cfg(feature = "batch")]
+    fn carbone_batch(server: Option<&MockServer>, args: &[&str]) -> Result<Output, CarboneError> {
+        run(env!("CARGO_BIN_EXE_carbone-batch"), server, args)
+    }
+
+    fn run(program: &str, server: Option<&MockServer>, args: &[&str]) -> Result<Output, CarboneError> {
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(server)?;
+        let api_token = helper.create_api_token()?;
+
+        let output = Command::new(program)
+            .args(args)
+            .env_remove("CARBONE_CONFIG")
+            .env("CARBONE_API_URL", &config.api_url)
+            .env("CARBONE_TOKEN", api_token.as_str())
+            .output()?;
+
+        Ok(output)
+    }
+
+    fn stdout(output: &Output) -> String {
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn test_status_json() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_status = server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(200).json_body(json!({
+                "success": true,
+                "code": 200,
+                "message": "OK",
+                "version": "4.22.0"
+            }));
+        });
+
+        let output = carbone(Some(&server), &["status", "--json"])?;
+
+        mock_status.assert();
+        assert!(output.status.success());
+        let status: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(status["version"], "4.22.0");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_upload() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.test.html".to_string(), None)?;
+        let template_id = template_file.generate_id(Some("salt1234"))?;
+
+        let server = MockServer::start();
+
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("name=\"payload\"\r\n\r\nsalt1234");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+
+        let output = carbone(
+            Some(&server),
+            &["upload", "tests/data/template.test.html", "--payload", "salt1234"],
+        )?;
+
+        mock_upload.assert();
+        assert!(output.status.success());
+        assert_eq!(stdout(&output), template_id.as_str());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_template_id() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path("/render/template_id")
+                .json_body(json!({ "data": { "id": 42 }, "convertTo": "pdf" }));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.pdf");
+            then.status(200).body("report content");
+        });
+
+        let dir = std::env::temp_dir().join(format!("carbone-cli-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        let data_path = dir.join("data.json");
+        let output_path = dir.join("report.pdf");
+        fs::write(&data_path, r#"{ "data": { "id": 42 }, "convertTo": "pdf" }"#)?;
+
+        let output = carbone(
+            Some(&server),
+            &[
+                "render",
+                "--id",
+                "template_id",
+                &data_path.to_string_lossy(),
+                "--output",
+                &output_path.to_string_lossy(),
+            ],
+        )?;
+
+        mock_render.assert();
+        mock_report.assert();
+        assert!(output.status.success());
+        assert_eq!(fs::read_to_string(&output_path)?, "report content");
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_json() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_list = server.mock(|when, then| {
+            when.method("GET")
+                .path("/templates")
+                .query_param("category", "invoices");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": [ { "id": "template_id", "name": "Invoice", "type": "docx" } ]
+            }));
+        });
+
+        let output = carbone(Some(&server), &["list", "--category", "invoices", "--json"])?;
+
+        mock_list.assert();
+        assert!(output.status.success());
+        let templates: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(templates[0]["id"], "template_id");
+        assert_eq!(templates[0]["name"], "Invoice");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_exit_codes() -> Result<(), CarboneError> {
+        // Nothing listens on the port 1.
+        let output = carbone(None, &["status", "--api-url", "http://127.0.0.1:1"])?;
+        assert_eq!(output.status.code(), Some(6));
+
+        let output = carbone(None, &["render", "tests/data/template.test.html", "unknown.json", "-o", "report.pdf"])?;
+        assert_eq!(output.status.code(), Some(3));
+
+        let output = Command::new(env!("CARGO_BIN_EXE_carbone"))
+            .args(["status", "--json"])
+            .env_remove("CARBONE_TOKEN")
+            .output()?;
+        assert_eq!(output.status.code(), Some(4));
+        let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+        assert_eq!(error["success"], false);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_download_exit_codes() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let unauthorized_id = "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114";
+        let mock_unauthorized = server.mock(|when, then| {
+            when.method("GET").path(format!("/template/{}", unauthorized_id));
+            then.status(401).json_body(json!({
+                "success": false,
+                "error": "Unauthorized, please provide a valid API key"
+            }));
+        });
+
+        let output = carbone(Some(&server), &["download", unauthorized_id])?;
+        mock_unauthorized.assert();
+        assert_eq!(output.status.code(), Some(4));
+
+        let missing_id = "1545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114";
+        let mock_missing = server.mock(|when, then| {
+            when.method("GET").path(format!("/template/{}", missing_id));
+            then.status(404).json_body(json!({
+                "success": false,
+                "error": "Template not found"
+            }));
+        });
+
+        let output = carbone(Some(&server), &["download", missing_id, "--json"])?;
+        mock_missing.assert();
+        assert_eq!(output.status.code(), Some(3));
+        let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+        assert_eq!(error["success"], false);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn test_batch() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.test.html".to_string(), None)?;
+        let template_id = template_file.generate_id(None)?;
+
+        let server = MockServer::start();
+
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+        let mock_render_failure = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()))
+                .body_contains("Doe");
+            then.status(400).json_body(json!({
+                "success": false,
+                "error": "Invalid data"
+            }));
+        });
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()))
+                .json_body(json!({ "convertTo": "pdf", "lang": "fr-fr", "data": { "id": 1, "lastname": "Wick" } }));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.pdf");
+            then.status(200).body("report content");
+        });
+
+        let dir = std::env::temp_dir().join(format!("carbone-cli-batch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        let dataset_path = dir.join("rows.jsonl");
+        let report_path = dir.join("summary.json");
+        fs::write(&dataset_path, "{\"id\": 1, \"lastname\": \"Wick\"}\n{\"id\": 2, \"lastname\": \"Doe\"}\n")?;
+
+        let output = carbone_batch(
+            Some(&server),
+            &[
+                "tests/data/template.test.html",
+                &dataset_path.to_string_lossy(),
+                "--output-dir",
+                &dir.join("reports").to_string_lossy(),
+                "--name",
+                "{id}-{lastname}.pdf",
+                "--convert-to",
+                "pdf",
+                "--render-options",
+                r#"{"lang": "fr-fr"}"#,
+                "--report",
+                &report_path.to_string_lossy(),
+            ],
+        )?;
+
+        mock_upload.assert();
+        mock_render.assert();
+        mock_render_failure.assert();
+        mock_report.assert();
+        assert_eq!(output.status.code(), Some(8));
+        assert!(stdout(&output).starts_with("1 rendered, 0 skipped, 1 failed"));
+        assert_eq!(fs::read_to_string(dir.join("reports/1-Wick.pdf"))?, "report content");
+
+        let summary: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report_path)?).unwrap();
+        assert_eq!(summary["failures"][0]["row"], 2);
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,409 @@
+use std::fs;
+use std::time::Duration;
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rust::carbone_response::{APIResponse, APIResponseData};
+use carbone_sdk_rust::cluster::{CarboneCluster, SelectionStrategy};
+use carbone_sdk_rust::config::Config;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::render::RenderId;
+use carbone_sdk_rust::template::TemplateId;
+use carbone_sdk_rust::types::{ApiVersion, JsonData};
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const TEMPLATE_ID: &str = "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114";
+    const RENDER_ID: &str = "MTAuMjAuMjEuMTAgICAg01E98H4R7PMC2H6XSE5Z6J8XYQ.pdf";
+
+    fn unreachable_config() -> Result<Config, CarboneError> {
+        let api_version = ApiVersion::new("4".to_string())?;
+        Ok(Config::new("http://127.0.0.1:1".to_string(), 1, api_version)?)
+    }
+
+    fn render_response() -> APIResponse {
+        APIResponse {
+            success: true,
+            data: Some(APIResponseData {
+                template_id: None,
+                render_id: Some(RenderId::new(RENDER_ID).unwrap()),
+                template_file_extension: None,
+            }),
+            error: None,
+            code: None,
+        }
+    }
+
+    fn mock_render<'a>(server: &'a MockServer, template_id: &str) -> httpmock::Mock<'a> {
+        server.mock(|when, then| {
+            when.method("POST").path(format!("/render/{}", template_id));
+            then.status(200).json_body_obj(&render_response());
+        })
+    }
+
+    fn mock_report<'a>(server: &'a MockServer) -> httpmock::Mock<'a> {
+        server.mock(|when, then| {
+            when.method("GET").path(format!("/render/{}", RENDER_ID));
+            then.status(200).body("report");
+        })
+    }
+
+    fn json_data() -> Result<JsonData, CarboneError> {
+        JsonData::new(json!({"data": {}, "convertTo": "pdf"}).to_string())
+    }
+
+    #[test]
+    fn test_cluster_without_configs() {
+        let configs: Vec<Config> = vec![];
+
+        let result = CarboneCluster::new(&configs, None);
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cluster_round_robin() -> Result<(), CarboneError> {
+        let server_1 = MockServer::start();
+        let server_2 = MockServer::start();
+
+        let render_1 = mock_render(&server_1, TEMPLATE_ID);
+        let report_1 = mock_report(&server_1);
+        let render_2 = mock_render(&server_2, TEMPLATE_ID);
+        let report_2 = mock_report(&server_2);
+
+        let helper = Helper::new();
+        let configs = vec![
+            helper.create_config_for_mock_server(Some(&server_1))?,
+            helper.create_config_for_mock_server(Some(&server_2))?,
+        ];
+        let api_token = helper.create_api_token()?;
+
+        let cluster = CarboneCluster::new(&configs, Some(&api_token))?;
+
+        for _ in 0..4 {
+            let template_id = TemplateId::new(TEMPLATE_ID)?;
+            let report = cluster
+                .generate_report_with_template_id(template_id, json_data()?)
+                .await?;
+            assert_eq!(report.as_ref(), b"report");
+        }
+
+        render_1.assert_hits(2);
+        report_1.assert_hits(2);
+        render_2.assert_hits(2);
+        report_2.assert_hits(2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cluster_failover_and_ejection() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let render = mock_render(&server, TEMPLATE_ID);
+        let report = mock_report(&server);
+
+        let helper = Helper::new();
+        let configs = vec![
+            unreachable_config()?,
+            helper.create_config_for_mock_server(Some(&server))?,
+        ];
+
+        let cluster = CarboneCluster::new(&configs, None)?
+            .with_strategy(SelectionStrategy::LeastInFlight)
+            .with_failure_threshold(1)
+            .with_cooldown(Duration::from_secs(60));
+
+        for _ in 0..3 {
+            let template_id = TemplateId::new(TEMPLATE_ID)?;
+            cluster
+                .generate_report_with_template_id(template_id, json_data()?)
+                .await?;
+        }
+
+        render.assert_hits(3);
+        report.assert_hits(3);
+
+        let states = cluster.node_states();
+        assert!(!states[0].healthy);
+        assert_eq!(states[0].consecutive_failures, 1);
+        assert!(states[1].healthy);
+        assert_eq!(states[1].in_flight, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cluster_all_nodes_failing() -> Result<(), CarboneError> {
+        let configs = vec![unreachable_config()?, unreachable_config()?];
+
+        let cluster = CarboneCluster::new(&configs, None)?;
+
+        let template_id = TemplateId::new(TEMPLATE_ID)?;
+        let result = cluster
+            .generate_report_with_template_id(template_id, json_data()?)
+            .await;
+
+        assert!(matches!(result, Err(CarboneError::RequestError(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cluster_retries_server_errors_only() -> Result<(), CarboneError> {
+        let server_1 = MockServer::start();
+        let server_2 = MockServer::start();
+
+        let render_1 = mock_render(&server_1, TEMPLATE_ID);
+        let report_unavailable = server_1.mock(|when, then| {
+            when.method("GET").path(format!("/render/{}", RENDER_ID));
+            then.status(503).json_body(json!({ "success": false, "error": "unavailable" }));
+        });
+        let render_2 = mock_render(&server_2, TEMPLATE_ID);
+        let report_2 = mock_report(&server_2);
+
+        let helper = Helper::new();
+        let configs = vec![
+            helper.create_config_for_mock_server(Some(&server_1))?,
+            helper.create_config_for_mock_server(Some(&server_2))?,
+        ];
+
+        let cluster = CarboneCluster::new(&configs, None)?;
+        let report = cluster
+            .generate_report_with_template_id(TemplateId::new(TEMPLATE_ID)?, json_data()?)
+            .await?;
+
+        assert_eq!(report.as_ref(), b"report");
+        render_1.assert();
+        report_unavailable.assert();
+        render_2.assert();
+        report_2.assert();
+
+        let unknown_id = "1545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114";
+        let render_missing = server_1.mock(|when, then| {
+            when.method("POST").path(format!("/render/{}", unknown_id));
+            then.status(404).json_body(json!({ "success": false, "error": "Template not found" }));
+        });
+        let render_other = mock_render(&server_2, unknown_id);
+
+        let cluster = CarboneCluster::new(&configs, None)?;
+        let result = cluster
+            .generate_report_with_template_id(TemplateId::new(unknown_id)?, json_data()?)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(CarboneError::HttpError { status_code, .. }) if status_code == 404
+        ));
+        render_missing.assert();
+        render_other.assert_hits(0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cluster_generate_report_uploads_to_rendering_node() -> Result<(), CarboneError> {
+        let server_1 = MockServer::start();
+        let server_2 = MockServer::start();
+
+        let template_data = fs::read("tests/data/template.odt")?;
+        let template_id = TemplateId::from_bytes(template_data.clone(), None)?;
+
+        let render_missing = server_1.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()));
+            then.status(404).json_body(json!({
+                "success": false,
+                "error": "Template not found"
+            }));
+        });
+        let upload = server_1.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": TEMPLATE_ID }
+            }));
+        });
+        let render_uploaded = mock_render(&server_1, TEMPLATE_ID);
+        let report = mock_report(&server_1);
+        let upload_other = server_2.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(500);
+        });
+
+        let helper = Helper::new();
+        let configs = vec![
+            helper.create_config_for_mock_server(Some(&server_1))?,
+            helper.create_config_for_mock_server(Some(&server_2))?,
+        ];
+
+        let cluster = CarboneCluster::new(&configs, None)?;
+
+        let content = cluster
+            .generate_report(
+                "template.odt".to_string(),
+                template_data,
+                json_data()?,
+                None,
+            )
+            .await?;
+
+        assert_eq!(content.as_ref(), b"report");
+        render_missing.assert();
+        upload.assert();
+        render_uploaded.assert();
+        report.assert();
+        upload_other.assert_hits(0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cluster_check_health() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let status = server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(200).body("{\"success\":true}");
+        });
+
+        let helper = Helper::new();
+        let configs = vec![
+            helper.create_config_for_mock_server(Some(&server))?,
+            unreachable_config()?,
+        ];
+
+        let cluster = CarboneCluster::new(&configs, None)?;
+
+        let healthy = cluster.check_health().await;
+
+        status.assert();
+        assert_eq!(healthy, vec![true, false]);
+
+        let states = cluster.node_states();
+        assert!(states[0].healthy);
+        assert!(!states[1].healthy);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cluster_all_nodes_ejected() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mut unavailable = server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(503).json_body(json!({ "success": false, "message": "starting" }));
+        });
+        let render = mock_render(&server, TEMPLATE_ID);
+
+        let helper = Helper::new();
+        let configs = vec![helper.create_config_for_mock_server(Some(&server))?];
+
+        let cluster = CarboneCluster::new(&configs, None)?.with_cooldown(Duration::from_secs(60));
+
+        assert_eq!(cluster.check_health().await, vec![false]);
+
+        let result = cluster
+            .generate_report_with_template_id(TemplateId::new(TEMPLATE_ID)?, json_data()?)
+            .await;
+
+        assert!(matches!(result, Err(CarboneError::ServerError)));
+        render.assert_hits(0);
+
+        unavailable.delete();
+        let ready = server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(200).body("{\"success\":true}");
+        });
+
+        let checks = cluster.run_health_checks(Duration::from_millis(100));
+        let result = tokio::time::timeout(Duration::from_millis(250), checks).await;
+
+        assert!(result.is_err());
+        assert!(ready.hits() >= 2);
+        assert!(cluster.node_states()[0].healthy);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cluster_upload_template_skips_failing_node() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let upload = server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": TEMPLATE_ID }
+            }));
+        });
+
+        let helper = Helper::new();
+        let configs = vec![
+            unreachable_config()?,
+            helper.create_config_for_mock_server(Some(&server))?,
+        ];
+
+        let cluster = CarboneCluster::new(&configs, None)?;
+
+        let template_data = fs::read("tests/data/template.odt")?;
+        let template_id = cluster
+            .upload_template("template.odt", template_data, None)
+            .await?;
+
+        upload.assert();
+        assert_eq!(template_id.as_str(), TEMPLATE_ID);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cluster_delete_template_on_every_node() -> Result<(), CarboneError> {
+        let servers = [MockServer::start(), MockServer::start(), MockServer::start()];
+
+        let missing = servers[0].mock(|when, then| {
+            when.method("DELETE").path(format!("/template/{}", TEMPLATE_ID));
+            then.status(404).json_body(json!({
+                "success": false,
+                "error": "Template not found"
+            }));
+        });
+        let deleted: Vec<_> = servers[1..]
+            .iter()
+            .map(|server| {
+                server.mock(|when, then| {
+                    when.method("DELETE").path(format!("/template/{}", TEMPLATE_ID));
+                    then.status(200).json_body(json!({ "success": true }));
+                })
+            })
+            .collect();
+
+        let helper = Helper::new();
+        let configs = servers
+            .iter()
+            .map(|server| helper.create_config_for_mock_server(Some(server)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let cluster = CarboneCluster::new(&configs, None)?;
+
+        let is_deleted = cluster.delete_template(TemplateId::new(TEMPLATE_ID)?).await?;
+
+        assert!(is_deleted);
+        missing.assert();
+        for mock in deleted {
+            mock.assert();
+        }
+        assert!(cluster.node_states().iter().all(|state| state.healthy));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use std::io::{Cursor, Write};
+
+use zip::write::FileOptions;
+
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::template::inspect::*;
+use carbone_sdk_rust::template::TemplateFile;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn create_zip(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_inspect_odt() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.odt".to_string(), None)?;
+
+        let markers = inspect(&template_file)?;
+        let paths: Vec<&str> = markers.iter().map(|m| m.path()).collect();
+
+        assert!(paths.contains(&"d.id"));
+        assert!(paths.contains(&"d.company.name"));
+        assert!(paths.contains(&"d.products[i].name"));
+        assert!(paths.contains(&"d.products[i+1].name"));
+
+        let date = markers.iter().find(|m| m.path() == "d.date").unwrap();
+        assert_eq!(date.kind, MarkerKind::Data);
+        assert_eq!(date.formatters(), vec!["convDate(X,L)"]);
+        assert_eq!(date.location.file, "template.odt");
+        assert_eq!(date.location.part.as_deref(), Some("content.xml"));
+        assert!(date.location.paragraph.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_docx_split_runs() -> Result<(), CarboneError> {
+        let document = r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>
+<w:p><w:r><w:t>Hello {d.first</w:t></w:r><w:r><w:t>name}</w:t></w:r></w:p>
+<w:p><w:r><w:t>{c.now:formatD(&apos;YYYY&apos;)} {t(Total)} {o.lang} &amp; {#total = d.total} {$total}</w:t></w:r></w:p>
+<w:p><w:r><w:t>{not a marker}</w:t></w:r></w:p>
+</w:body></w:document>"#;
+        let content = create_zip(&[
+            ("[Content_Types].xml", "<Types>{d.ignored}</Types>"),
+            ("word/document.xml", document),
+        ]);
+
+        let markers = inspect_bytes("templates/letter.docx", &content)?;
+
+        let summary: Vec<(&str, MarkerKind, Option<usize>)> = markers
+            .iter()
+            .map(|m| (m.text.as_str(), m.kind, m.location.paragraph))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("{d.firstname}", MarkerKind::Data, Some(1)),
+                ("{c.now:formatD('YYYY')}", MarkerKind::Complement, Some(2)),
+                ("{t(Total)}", MarkerKind::Translation, Some(2)),
+                ("{o.lang}", MarkerKind::Option, Some(2)),
+                ("{#total = d.total}", MarkerKind::AliasDefinition, Some(2)),
+                ("{$total}", MarkerKind::Alias, Some(2)),
+            ]
+        );
+        assert_eq!(markers[0].location.file, "letter.docx");
+        assert_eq!(markers[0].location.part.as_deref(), Some("word/document.xml"));
+        assert!(markers[0].split_across_runs);
+        assert!(!markers[1].split_across_runs);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_xlsx_shared_strings() -> Result<(), CarboneError> {
+        let content = create_zip(&[
+            (
+                "xl/workbook.xml",
+                r#"<workbook><sheets><sheet name="Invoice" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            ),
+            (
+                "xl/sharedStrings.xml",
+                r#"<sst><si><t>Total</t></si><si><t>{d.total}</t></si></sst>"#,
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet><sheetData><row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row><row r="2"><c r="A2" t="inlineStr"><is><t>{d.name}</t></is></c></row></sheetData></worksheet>"#,
+            ),
+        ]);
+
+        let markers = inspect_bytes("invoice.xlsx", &content)?;
+        let cells: Vec<(&str, Option<&str>, Option<&str>)> = markers
+            .iter()
+            .map(|m| {
+                (
+                    m.path(),
+                    m.location.sheet.as_deref(),
+                    m.location.cell.as_deref(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            cells,
+            vec![
+                ("d.name", Some("Invoice"), Some("A2")),
+                ("d.total", Some("Invoice"), Some("B1")),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_ods_cells() -> Result<(), CarboneError> {
+        let content = create_zip(&[(
+            "content.xml",
+            r#"<office:document-content><office:body><office:spreadsheet><table:table table:name="Data"><table:table-row><table:table-cell table:number-columns-repeated="2"/><table:table-cell><text:p>{d.value}</text:p></table:table-cell></table:table-row></table:table></office:spreadsheet></office:body></office:document-content>"#,
+        )]);
+
+        let markers = inspect_bytes("data.ods", &content)?;
+
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].location.sheet.as_deref(), Some("Data"));
+        assert_eq!(markers[0].location.cell.as_deref(), Some("C1"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_text_template() -> Result<(), CarboneError> {
+        let content = "<style>p { color: red; }</style>\n<p>{d.name:upperCase:substr(0, 3)}</p>\n";
+
+        let markers = inspect_bytes("letter.html", content.as_bytes())?;
+
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].location.line, Some(2));
+        assert_eq!(markers[0].path(), "d.name");
+        assert_eq!(markers[0].formatters(), vec!["upperCase", "substr(0, 3)"]);
+        assert_eq!(markers[0].location.to_string(), "letter.html:2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inspect_invalid_archive() {
+        let result = inspect_bytes("broken.docx", b"PK\x03\x04broken");
+
+        assert!(matches!(result, Err(CarboneError::ParseError(_, _))));
+    }
+}
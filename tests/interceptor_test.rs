@@ -0,0 +1,203 @@
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use httpmock::prelude::*;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use serde_json::json;
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::config::Config;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::interceptor::{InterceptedRequest, InterceptedResponse, Interceptor};
+use carbone_sdk_rust::template::TemplateId;
+use carbone_sdk_rust::types::{ApiVersion, Endpoint, JsonData};
+
+mod helper;
+
+use helper::Helper;
+
+/// Interceptor adding a header and logging the calls it sees.
+#[derive(Debug)]
+struct Recorder {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Interceptor for Recorder {
+    fn before_send(&self, endpoint: Endpoint, request: &mut InterceptedRequest) -> Result<(), CarboneError> {
+        let body = request
+            .body
+            .as_ref()
+            .map(|body| String::from_utf8_lossy(body).into_owned())
+            .unwrap_or_default();
+        self.log.lock().unwrap().push(format!(
+            "{} before {} {} {}",
+            self.name,
+            endpoint.as_str(),
+            request.headers.contains_key("authorization"),
+            body
+        ));
+
+        request
+            .headers
+            .insert("x-correlation-id", HeaderValue::from_static("correlation-42"));
+        Ok(())
+    }
+
+    fn after_receive(
+        &self,
+        endpoint: Endpoint,
+        response: Result<InterceptedResponse, CarboneError>,
+    ) -> Result<InterceptedResponse, CarboneError> {
+        let status = match &response {
+            Ok(response) => response.status.as_u16().to_string(),
+            Err(_) => "error".to_string(),
+        };
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("{} after {} {}", self.name, endpoint.as_str(), status));
+        response
+    }
+}
+
+/// Interceptor answering for an unavailable status endpoint.
+#[derive(Debug)]
+struct StatusFallback;
+
+impl Interceptor for StatusFallback {
+    fn after_receive(
+        &self,
+        endpoint: Endpoint,
+        response: Result<InterceptedResponse, CarboneError>,
+    ) -> Result<InterceptedResponse, CarboneError> {
+        match response {
+            Ok(response) if endpoint == Endpoint::Status && response.status.is_server_error() => {
+                Ok(InterceptedResponse {
+                    status: StatusCode::OK,
+                    headers: HeaderMap::new(),
+                    body: Bytes::from(r#"{ "success": false, "code": 503, "message": "maintenance" }"#),
+                })
+            }
+            Err(_) => Err(CarboneError::Error("Carbone is unreachable".to_string())),
+            response => response,
+        }
+    }
+}
+
+/// Interceptor rejecting the deletions.
+#[derive(Debug)]
+struct ReadOnly;
+
+impl Interceptor for ReadOnly {
+    fn before_send(&self, endpoint: Endpoint, _request: &mut InterceptedRequest) -> Result<(), CarboneError> {
+        match endpoint {
+            Endpoint::Delete => Err(CarboneError::Error("read-only client".to_string())),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const TEMPLATE_ID: &str = "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114";
+
+    #[tokio::test]
+    async fn test_interceptors_order_and_headers() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", TEMPLATE_ID))
+                .header("x-correlation-id", "correlation-42")
+                .header_exists("authorization");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let carbone = Carbone::new(&config, Some(&api_token))?
+            .with_interceptor(Arc::new(Recorder { name: "first", log: log.clone() }))
+            .with_interceptor(Arc::new(Recorder { name: "second", log: log.clone() }));
+
+        let json_data = JsonData::new(r#"{"data":{"id":42}}"#.to_string())?;
+        let render_id = carbone.render_data(TemplateId::new(TEMPLATE_ID)?, json_data).await?;
+
+        mock_render.assert();
+        assert_eq!(render_id.as_str(), "render_id.pdf");
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                r#"first before render false {"data":{"id":42}}"#,
+                r#"second before render false {"data":{"id":42}}"#,
+                "second after render 200",
+                "first after render 200",
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_replaces_responses_and_errors() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(503).body("Service Unavailable");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_interceptor(Arc::new(StatusFallback));
+
+        let status = carbone.get_status().await?;
+        assert!(!status.success);
+        assert_eq!(status.code, Some(503));
+        assert_eq!(status.message.as_deref(), Some("maintenance"));
+
+        let api_version = ApiVersion::new("4".to_string())?;
+        let unreachable = Config::new("http://127.0.0.1:1".to_string(), 1, api_version)?;
+        let carbone = Carbone::new(&unreachable, Some(&api_token))?.with_interceptor(Arc::new(StatusFallback));
+
+        let result = carbone.get_status().await;
+        assert!(matches!(result, Err(CarboneError::Error(message)) if message == "Carbone is unreachable"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_cancels_request() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_delete = server.mock(|when, then| {
+            when.method("DELETE").path(format!("/template/{}", TEMPLATE_ID));
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_interceptor(Arc::new(ReadOnly));
+
+        let result = carbone.delete_template(TemplateId::new(TEMPLATE_ID)?).await;
+
+        mock_delete.assert_hits(0);
+        assert!(matches!(result, Err(CarboneError::Error(message)) if message == "read-only client"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use std::io::{Cursor, Write};
+
+use zip::write::FileOptions;
+
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::template::lint::*;
+use carbone_sdk_rust::template::TemplateFile;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn create_docx(paragraphs: &[&str]) -> Vec<u8> {
+        let body: String = paragraphs
+            .iter()
+            .map(|paragraph| format!("<w:p>{}</w:p>", paragraph))
+            .collect();
+        let document = format!(
+            r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}</w:body></w:document>"#,
+            body
+        );
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file("word/document.xml", FileOptions::default())
+            .unwrap();
+        writer.write_all(document.as_bytes()).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn rules(report: &LintReport) -> Vec<(Severity, LintRule, &str)> {
+        report
+            .issues
+            .iter()
+            .map(|issue| (issue.severity, issue.rule, issue.marker.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_valid_template() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.odt".to_string(), None)?;
+
+        let report = lint(&template_file, &LintOptions::default())?;
+
+        assert!(report.is_empty(), "{:?}", report.issues);
+        assert!(!report.has_errors());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_unbalanced_loops() -> Result<(), CarboneError> {
+        let content = "{d.products[i].name}\n{d.products[i].items[j].qty}\n{d.products[i+1].name}\n{d.rows[i+1].id}\n{d.products[0].name}\n";
+
+        let report = lint_bytes("report.html", content.as_bytes(), &LintOptions::default())?;
+
+        assert_eq!(
+            rules(&report),
+            vec![
+                (
+                    Severity::Error,
+                    LintRule::UnbalancedLoop,
+                    "{d.products[i].items[j].qty}"
+                ),
+                (Severity::Error, LintRule::UnbalancedLoop, "{d.rows[i+1].id}"),
+            ]
+        );
+        assert_eq!(report.issues[0].location.line, Some(2));
+        assert!(report.issues[0].message.contains("d.products[i].items[j]"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_formatters() -> Result<(), CarboneError> {
+        let content = "{d.total:formatn(2)}\n{d.total:formatN(two)}\n{d.total:formatN(2, 3)}\n{d.total:formatN(.precision)}\n{d.name:myFormatter}\n{d.name:substr(0,}\n{d.name:upperCase:ellipsis(10)}\n";
+
+        let report = lint_bytes("report.html", content.as_bytes(), &LintOptions::default())?;
+
+        assert_eq!(
+            rules(&report),
+            vec![
+                (
+                    Severity::Error,
+                    LintRule::UnknownFormatter,
+                    "{d.total:formatn(2)}"
+                ),
+                (
+                    Severity::Error,
+                    LintRule::InvalidFormatterArguments,
+                    "{d.total:formatN(two)}"
+                ),
+                (
+                    Severity::Error,
+                    LintRule::InvalidFormatterArguments,
+                    "{d.total:formatN(2, 3)}"
+                ),
+                (
+                    Severity::Warning,
+                    LintRule::UnknownFormatter,
+                    "{d.name:myFormatter}"
+                ),
+                (
+                    Severity::Error,
+                    LintRule::InvalidFormatterArguments,
+                    "{d.name:substr(0,}"
+                ),
+            ]
+        );
+        assert!(report.issues[0].message.contains("did you mean formatN?"));
+
+        let options = LintOptions {
+            custom_formatters: vec!["myFormatter".to_string()],
+            ..Default::default()
+        };
+        let report = lint_bytes("report.html", content.as_bytes(), &options)?;
+
+        assert_eq!(report.warnings().count(), 0);
+        assert_eq!(report.errors().count(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_unbalanced_conditions() -> Result<(), CarboneError> {
+        let content = "{d.vip:ifEQ(true):showBegin}\n{d.name}\n{d.vip:showEnd}\n{d.hidden:hideEnd}\n{d.open:ifEM:hideBegin}\n";
+
+        let report = lint_bytes("report.html", content.as_bytes(), &LintOptions::default())?;
+
+        assert_eq!(
+            rules(&report),
+            vec![
+                (
+                    Severity::Error,
+                    LintRule::UnbalancedCondition,
+                    "{d.hidden:hideEnd}"
+                ),
+                (
+                    Severity::Error,
+                    LintRule::UnbalancedCondition,
+                    "{d.open:ifEM:hideBegin}"
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lint_split_marker() -> Result<(), CarboneError> {
+        let content = create_docx(&[
+            r#"<w:r><w:t>{d.first</w:t></w:r><w:proofErr w:type="spellStart"/><w:r><w:rPr><w:b/></w:rPr><w:t>name}</w:t></w:r>"#,
+            r#"<w:r><w:t>Total</w:t></w:r><w:r><w:t>{d.total}</w:t></w:r>"#,
+        ]);
+
+        let report = lint_bytes("letter.docx", &content, &LintOptions::default())?;
+
+        assert_eq!(
+            rules(&report),
+            vec![(Severity::Warning, LintRule::SplitMarker, "{d.firstname}")]
+        );
+        assert_eq!(report.issues[0].location.paragraph, Some(1));
+        assert_eq!(
+            report.issues[0].to_string(),
+            "warning[split-marker] letter.docx:word/document.xml paragraph 1: {d.firstname} is split across several runs, retype it in one go or clear its formatting"
+        );
+
+        let options = LintOptions {
+            allow_split_markers: true,
+            ..Default::default()
+        };
+        assert!(lint_bytes("letter.docx", &content, &options)?.is_empty());
+
+        Ok(())
+    }
+}
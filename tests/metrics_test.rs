@@ -0,0 +1,195 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use httpmock::prelude::*;
+use reqwest::StatusCode;
+use serde_json::json;
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::carbone_response::{APIResponse, APIResponseData};
+#[cfg(feature = "cluster")]
+use carbone_sdk_rust::cluster::CarboneCluster;
+use carbone_sdk_rust::config::Config;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::metrics::{MetricsRecorder, PrometheusMetrics};
+use carbone_sdk_rust::render::RenderId;
+use carbone_sdk_rust::template::TemplateId;
+use carbone_sdk_rust::types::{ApiVersion, Endpoint, JsonData};
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const TEMPLATE_ID: &str = "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114";
+    const RENDER_ID: &str = "MTAuMjAuMjEuMTAgICAg01E98H4R7PMC2H6XSE5Z6J8XYQ.pdf";
+
+    fn response_data(template_id: Option<TemplateId>, render_id: Option<RenderId>) -> APIResponse {
+        APIResponse {
+            success: true,
+            data: Some(APIResponseData {
+                template_id,
+                render_id,
+                template_file_extension: None,
+            }),
+            error: None,
+            code: None,
+        }
+    }
+
+    fn mock_render(server: &MockServer) {
+        server.mock(|when, then| {
+            when.method("POST").path(format!("/render/{}", TEMPLATE_ID));
+            then.status(200)
+                .json_body_obj(&response_data(None, Some(RenderId::new(RENDER_ID).unwrap())));
+        });
+        server.mock(|when, then| {
+            when.method("GET").path(format!("/render/{}", RENDER_ID));
+            then.status(200).body("report");
+        });
+    }
+
+    fn json_data() -> Result<JsonData, CarboneError> {
+        JsonData::new(json!({"data": {}, "convertTo": "pdf"}).to_string())
+    }
+
+    #[tokio::test]
+    async fn test_metrics_of_generate_report() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+        mock_render(&server);
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let metrics = Arc::new(PrometheusMetrics::new());
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_metrics(metrics.clone());
+
+        carbone
+            .generate_report_with_template_id(TemplateId::new(TEMPLATE_ID)?, json_data()?)
+            .await?;
+
+        assert_eq!(metrics.requests(Endpoint::Render, Some(StatusCode::OK)), 1);
+        assert_eq!(metrics.requests(Endpoint::Report, Some(StatusCode::OK)), 1);
+        assert_eq!(metrics.downloaded_bytes(Endpoint::Report), 6);
+        assert_eq!(metrics.renders(), 1);
+
+        let text = metrics.render();
+        assert!(text.contains("carbone_requests_total{endpoint=\"render\",status=\"200\"} 1"), "{}", text);
+        assert!(text.contains("carbone_request_duration_seconds_count{endpoint=\"report\"} 1"), "{}", text);
+        assert!(text.contains("carbone_request_duration_seconds_bucket{endpoint=\"render\",le=\"+Inf\"} 1"), "{}", text);
+        assert!(text.contains("carbone_download_bytes_sum{endpoint=\"report\"} 6"), "{}", text);
+        assert!(text.contains("carbone_render_duration_seconds_count 1"), "{}", text);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metrics_of_upload_and_errors() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method("POST").path("/template").body_contains("rejected.txt");
+            then.status(413).json_body(json!({ "success": false, "error": "too large" }));
+        });
+        server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200)
+                .json_body_obj(&response_data(Some(TemplateId::new(TEMPLATE_ID).unwrap()), None));
+        });
+        server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(500).json_body(json!({ "success": false, "error": "unavailable" }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let metrics = Arc::new(PrometheusMetrics::new());
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_metrics(metrics.clone());
+
+        carbone
+            .upload_template("template.txt", b"hello".to_vec(), None)
+            .await?;
+        assert_eq!(metrics.requests(Endpoint::Upload, Some(StatusCode::OK)), 1);
+        assert_eq!(metrics.uploaded_bytes(), 5);
+
+        let result = carbone
+            .upload_template("rejected.txt", b"hello".to_vec(), None)
+            .await;
+        assert!(matches!(result, Err(CarboneError::HttpError { .. })));
+        assert_eq!(metrics.requests(Endpoint::Upload, Some(StatusCode::PAYLOAD_TOO_LARGE)), 1);
+        assert_eq!(metrics.uploaded_bytes(), 5);
+
+        assert!(carbone.get_status().await.is_err());
+        assert_eq!(metrics.requests(Endpoint::Status, Some(StatusCode::INTERNAL_SERVER_ERROR)), 1);
+
+        let api_version = ApiVersion::new("4".to_string())?;
+        let unreachable = Config::new("http://127.0.0.1:1".to_string(), 1, api_version)?;
+        let carbone = Carbone::new(&unreachable, Some(&api_token))?.with_metrics(metrics.clone());
+
+        assert!(carbone.get_status().await.is_err());
+        assert_eq!(metrics.requests(Endpoint::Status, None), 1);
+        assert!(metrics
+            .render()
+            .contains("carbone_requests_total{endpoint=\"status\",status=\"error\"} 1"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "cluster")]
+    #[tokio::test]
+    async fn test_metrics_of_cluster_retries() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+        mock_render(&server);
+
+        let helper = Helper::new();
+        let api_version = ApiVersion::new("4".to_string())?;
+        let configs = vec![
+            Config::new("http://127.0.0.1:1".to_string(), 1, api_version)?,
+            helper.create_config_for_mock_server(Some(&server))?,
+        ];
+        let api_token = helper.create_api_token()?;
+
+        let metrics = Arc::new(PrometheusMetrics::new());
+        let cluster = CarboneCluster::new(&configs, Some(&api_token))?.with_metrics(metrics.clone());
+
+        cluster
+            .generate_report_with_template_id(TemplateId::new(TEMPLATE_ID)?, json_data()?)
+            .await?;
+
+        assert_eq!(metrics.retries(), 1);
+        assert_eq!(metrics.requests(Endpoint::Render, None), 1);
+        assert_eq!(metrics.requests(Endpoint::Render, Some(StatusCode::OK)), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prometheus_metrics_histogram() {
+        let metrics = PrometheusMetrics::new();
+
+        metrics.record_render(Duration::from_millis(40));
+        metrics.record_render(Duration::from_millis(700));
+        metrics.record_render(Duration::from_secs(300));
+        metrics.record_cache(true);
+        metrics.record_cache(false);
+        metrics.record_cache(false);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE carbone_render_duration_seconds histogram"), "{}", text);
+        assert!(text.contains("carbone_render_duration_seconds_bucket{le=\"0.01\"} 0"), "{}", text);
+        assert!(text.contains("carbone_render_duration_seconds_bucket{le=\"0.05\"} 1"), "{}", text);
+        assert!(text.contains("carbone_render_duration_seconds_bucket{le=\"1\"} 2"), "{}", text);
+        assert!(text.contains("carbone_render_duration_seconds_bucket{le=\"120\"} 2"), "{}", text);
+        assert!(text.contains("carbone_render_duration_seconds_bucket{le=\"+Inf\"} 3"), "{}", text);
+        assert!(text.contains("carbone_render_duration_seconds_count 3"), "{}", text);
+        assert!(text.contains("carbone_cache_hits_total 1"), "{}", text);
+        assert!(text.contains("carbone_cache_misses_total 2"), "{}", text);
+        assert!(text.contains("carbone_retries_total 0"), "{}", text);
+    }
+}
@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use carbone_sdk_rust::api::CarboneApi;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::template::{TemplateId, TemplateListQuery, TemplateSource};
+use carbone_sdk_rust::testing::mock::*;
+use carbone_sdk_rust::types::JsonData;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_render_text_template() -> Result<(), CarboneError> {
+        let carbone = MockCarbone::new();
+
+        let template = r#"<h1>{t(Invoice)} {d.id}</h1>
+<p style="color: {c.color}">{d.customer.name:upperCase}, {d.products[1].name}</p>
+<p>{d.products[i].name}{d.missing}{#total = d.total}</p>"#;
+        let json_data = JsonData::new(
+            r#"{
+                "data": {
+                    "id": 42,
+                    "customer": { "name": "Tom & Jerry" },
+                    "products": [{ "name": "Apple" }, { "name": "<Pear>" }]
+                },
+                "complement": { "color": "red" }
+            }"#
+            .to_string(),
+        )?;
+
+        let report = carbone
+            .generate_report("invoice.html".to_string(), template.as_bytes().to_vec(), json_data, None)
+            .await?;
+
+        assert_eq!(
+            report,
+            "<h1>Invoice 42</h1>\n<p style=\"color: red\">Tom &amp; Jerry, &lt;Pear&gt;</p>\n<p></p>"
+        );
+
+        let json_data = JsonData::new(r#"{ "data": { "name": "a < b" } }"#.to_string())?;
+        let report = carbone
+            .generate_report_with_file(TemplateSource::from_bytes("names.csv", b"name\n{d.name}\n".to_vec()), json_data, None)
+            .await?;
+        assert_eq!(report, "name\na < b\n");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fixtures() -> Result<(), CarboneError> {
+        let carbone = MockCarbone::new().with_fixture("PDF", b"%PDF-1.4 fixture".to_vec());
+
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" }, "convertTo": "pdf" }"#.to_string())?;
+        let report = carbone
+            .generate_report("letter.html".to_string(), b"<p>{d.name}</p>".to_vec(), json_data, None)
+            .await?;
+        assert_eq!(report, "%PDF-1.4 fixture");
+
+        // Binary templates without fixture give a placeholder report.
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" } }"#.to_string())?;
+        let report = carbone
+            .generate_report("letter.docx".to_string(), b"PK\x03\x04".to_vec(), json_data, None)
+            .await?;
+        assert!(String::from_utf8_lossy(&report).contains(r#"data: {"name":"John"}"#));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_templates() -> Result<(), CarboneError> {
+        let carbone = MockCarbone::new();
+        let content = b"<p>{d.name}</p>".to_vec();
+
+        let template_id = carbone
+            .upload_template("letter.html", content.clone(), Some("salt"))
+            .await?;
+        assert_eq!(template_id, TemplateId::from_bytes(content.clone(), Some("salt"))?);
+        assert_eq!(carbone.template_ids(), vec![template_id.clone()]);
+        assert_eq!(carbone.download_template(&template_id).await?, content);
+
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" } }"#.to_string())?;
+        let render_id = carbone.render_data(template_id.clone(), json_data.clone()).await?;
+        assert!(render_id.as_str().ends_with(".html"));
+        assert_eq!(carbone.get_report(&render_id).await?, "<p>John</p>");
+        assert!(carbone.get_report(&render_id).await.is_err());
+
+        assert!(carbone.delete_template(template_id.clone()).await?);
+        assert!(!carbone.has_template(&template_id));
+        match carbone.render_data(template_id, json_data).await {
+            Err(CarboneError::HttpError { status_code, .. }) => assert_eq!(status_code.as_u16(), 404),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let template_id = carbone
+            .upload_template("letter.html", content, None)
+            .await?;
+        let json_data = JsonData::new("not json".to_string())?;
+        assert!(matches!(
+            carbone.render_data(template_id, json_data).await,
+            Err(CarboneError::RequestBodyNotWellFormedJsonError)
+        ));
+
+        assert!(carbone.get_status().await?.success);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_templates() -> Result<(), CarboneError> {
+        let carbone = MockCarbone::new();
+        let letter_id = carbone
+            .upload_template("letter.html", b"<p>{d.name}</p>".to_vec(), None)
+            .await?;
+        let invoice_id = carbone
+            .upload_template("invoice.html", b"<p>{d.total}</p>".to_vec(), None)
+            .await?;
+
+        let list = carbone.list_templates(&TemplateListQuery::default()).await?;
+        assert_eq!(list.templates.len(), 2);
+        assert!(!list.has_more);
+
+        let query = TemplateListQuery {
+            id: Some(letter_id.clone()),
+            ..Default::default()
+        };
+        let list = CarboneApi::list_templates(&carbone, &query).await?;
+        assert_eq!(list.templates.len(), 1);
+        assert_eq!(list.templates[0].id, letter_id);
+        assert_eq!(list.templates[0].extension.as_deref(), Some("html"));
+        assert_eq!(list.templates[0].size, Some(15));
+
+        let query = TemplateListQuery {
+            category: Some("invoices".to_string()),
+            ..Default::default()
+        };
+        assert!(carbone.list_templates(&query).await?.templates.is_empty());
+
+        assert!(carbone.has_template(&invoice_id));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_missing_template_not_found() -> Result<(), CarboneError> {
+        let carbone = MockCarbone::new();
+        let template_id = TemplateId::from_bytes(b"<p>{d.name}</p>".to_vec(), None)?;
+
+        match carbone.delete_template(template_id.clone()).await {
+            Err(CarboneError::HttpError { status_code, .. }) => assert_eq!(status_code.as_u16(), 404),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match carbone.download_template(&template_id).await {
+            Err(CarboneError::HttpError { status_code, .. }) => assert_eq!(status_code.as_u16(), 404),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_carbone_api() -> Result<(), CarboneError> {
+        let mock = Arc::new(MockCarbone::new());
+        let carbone: Arc<dyn CarboneApi> = mock.clone();
+
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" } }"#.to_string())?;
+        let report = carbone
+            .generate_report("letter.txt".to_string(), b"Hello {d.name}".to_vec(), json_data, None)
+            .await?;
+
+        assert_eq!(report, "Hello John");
+        assert_eq!(mock.template_ids().len(), 1);
+
+        Ok(())
+    }
+}
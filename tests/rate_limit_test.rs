@@ -0,0 +1,112 @@
+use std::time::{Duration, Instant};
+
+use httpmock::prelude::*;
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::rate_limit::RateLimiter;
+use carbone_sdk_rust::types::Endpoint;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_invalid_values_given() {
+        assert!(RateLimiter::new(0, 1, Duration::from_secs(1)).is_err());
+        assert!(RateLimiter::new(1, 0, Duration::from_secs(1)).is_err());
+        assert!(RateLimiter::new(1, 1, Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_rate_limiter_cost_exceeds_capacity() -> Result<(), CarboneError> {
+        let result = RateLimiter::per_minute(2)?.with_cost(Endpoint::Render, 3);
+
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_limiter_try_acquire() -> Result<(), CarboneError> {
+        let rate_limiter = RateLimiter::per_minute(3)?.with_cost(Endpoint::Render, 2)?;
+
+        assert_eq!(rate_limiter.cost(Endpoint::Upload), 1);
+        assert_eq!(rate_limiter.cost(Endpoint::Render), 2);
+
+        assert!(rate_limiter.try_acquire(Endpoint::Render).is_ok());
+        assert!(rate_limiter.try_acquire(Endpoint::Upload).is_ok());
+
+        let wait = rate_limiter.try_acquire(Endpoint::Upload).unwrap_err();
+        assert!(wait > Duration::ZERO);
+        assert!(wait <= Duration::from_secs(20));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_limiter_free_endpoint() -> Result<(), CarboneError> {
+        let rate_limiter = RateLimiter::per_minute(1)?.with_cost(Endpoint::Status, 0)?;
+
+        assert!(rate_limiter.try_acquire(Endpoint::Upload).is_ok());
+        assert!(rate_limiter.try_acquire(Endpoint::Status).is_ok());
+        assert!(rate_limiter.try_acquire(Endpoint::Status).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_limiter_shared_between_clones() -> Result<(), CarboneError> {
+        let rate_limiter = RateLimiter::per_minute(1)?;
+        let shared = rate_limiter.clone();
+
+        assert!(rate_limiter.try_acquire(Endpoint::Render).is_ok());
+        assert!(shared.try_acquire(Endpoint::Render).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_acquire_waits() -> Result<(), CarboneError> {
+        let rate_limiter = RateLimiter::new(1, 1, Duration::from_millis(200))?;
+
+        let start = Instant::now();
+        rate_limiter.acquire(Endpoint::Render).await;
+        rate_limiter.acquire(Endpoint::Render).await;
+
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_carbone_with_rate_limiter() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+
+        let mock_server = server.mock(|when, then| {
+            when.method("GET").path("/status");
+            then.status(200).body("{\"success\":true}");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let rate_limiter = RateLimiter::new(1, 1, Duration::from_millis(200))?;
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_rate_limiter(rate_limiter);
+
+        let start = Instant::now();
+        carbone.get_status().await?;
+        carbone.get_status().await?;
+
+        mock_server.assert_hits(2);
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        Ok(())
+    }
+}
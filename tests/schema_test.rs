@@ -0,0 +1,108 @@
+use serde_json::json;
+
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::template::schema::*;
+use carbone_sdk_rust::template::TemplateFile;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_schema_from_odt() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new("tests/data/template.odt".to_string(), None)?;
+
+        let schema = TemplateSchema::from_template_file(&template_file)?;
+        let sample = schema.sample_data();
+
+        assert!(sample["id"].is_string());
+        assert!(sample["company"]["name"].is_string());
+        assert!(sample["products"].is_array());
+        assert!(sample["products"][0]["name"].is_string());
+        assert_eq!(schema.complement, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_shapes_and_types() -> Result<(), CarboneError> {
+        let content = r#"
+            {d.customer.name} {d.customer.address.city}
+            {d.rows[i].label} {d.rows[i].price:formatC(2)} {d.rows[i+1].label}
+            {d.rows[i].tags[j].name}
+            {d.date:formatD(YYYY)} {d.total:formatN(2)} {d.codes:arrayJoin}
+            {d.rows[i].product..currency}
+            {c.now} {t(Invoice)} {$alias}
+        "#;
+
+        let schema = TemplateSchema::from_bytes("invoice.html", content.as_bytes())?;
+
+        assert_eq!(
+            schema.sample_data(),
+            json!({
+                "codes": ["codes"],
+                "customer": { "address": { "city": "city" }, "name": "name" },
+                "date": "2024-01-01T00:00:00.000Z",
+                "rows": [{
+                    "currency": "currency",
+                    "label": "label",
+                    "price": 0,
+                    "tags": [{ "name": "name" }]
+                }],
+                "total": 0
+            })
+        );
+        assert_eq!(schema.sample_complement(), Some(json!({ "now": "now" })));
+        assert!(schema.unresolved_aliases);
+
+        assert_eq!(
+            schema.data_schema()["properties"]["rows"],
+            json!({
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "currency": {},
+                        "label": {},
+                        "price": { "type": "number" },
+                        "tags": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": { "name": {} },
+                                "required": ["name"]
+                            }
+                        }
+                    },
+                    "required": ["currency", "label", "price", "tags"]
+                }
+            })
+        );
+
+        let json_schema = schema.json_schema();
+        assert_eq!(json_schema["required"], json!(["data"]));
+        assert_eq!(
+            json_schema["properties"]["complement"]["required"],
+            json!(["now"])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_root_array() -> Result<(), CarboneError> {
+        let schema = TemplateSchema::from_bytes("list.csv", b"{d[i].name};{d[i+1].name}")?;
+
+        assert_eq!(
+            schema.data,
+            DataShape::Array(Box::new(DataShape::Object(
+                [("name".to_string(), DataShape::Value(ValueType::Any))]
+                    .into_iter()
+                    .collect()
+            )))
+        );
+
+        Ok(())
+    }
+}
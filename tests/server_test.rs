@@ -0,0 +1,288 @@
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::time::Duration;
+
+use httpmock::prelude::*;
+use hyper::{Body, Request, Response, StatusCode};
+use serde_json::json;
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::config::Config;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::metrics::PrometheusMetrics;
+use carbone_sdk_rust::server::cache::*;
+use carbone_sdk_rust::server::*;
+use carbone_sdk_rust::store::MemoryTemplateStore;
+use carbone_sdk_rust::template::TemplateId;
+use carbone_sdk_rust::types::ApiJsonToken;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const TEMPLATE: &str = "<p>{d.name}</p>";
+
+    fn create_gateway(server: Option<&MockServer>, options: GatewayOptions) -> Result<Gateway, CarboneError> {
+        let helper = Helper::new();
+        let config: &'static Config = Box::leak(Box::new(helper.create_config_for_mock_server(server)?));
+        let api_token: &'static ApiJsonToken = Box::leak(Box::new(helper.create_api_token()?));
+
+        let carbone = Carbone::new(config, Some(api_token))?;
+        let store = MemoryTemplateStore::default()
+            .with_template("letters/letter.html", TEMPLATE.as_bytes().to_vec());
+
+        Ok(Gateway::new(carbone, Arc::new(store), options))
+    }
+
+    fn render_request(path: &str, key: Option<&str>, body: &str) -> Request<Body> {
+        let mut request = Request::post(path);
+        if let Some(key) = key {
+            request = request.header("Authorization", format!("Bearer {}", key));
+        }
+        request.body(Body::from(body.to_string())).unwrap()
+    }
+
+    async fn body_text(response: Response<Body>) -> String {
+        let bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    #[tokio::test]
+    async fn test_render_with_cache() -> Result<(), CarboneError> {
+        let template_id = TemplateId::from_bytes(TEMPLATE.as_bytes().to_vec(), None)?;
+
+        let server = MockServer::start();
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()))
+                .json_body(json!({ "data": { "name": "John" }, "convertTo": "pdf" }));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.pdf");
+            then.status(200).body("report content");
+        });
+
+        let options = GatewayOptions {
+            api_keys: vec!["secret".to_string()],
+            ..Default::default()
+        };
+        let gateway = create_gateway(Some(&server), options)?;
+
+        let body = r#"{ "data": { "name": "John" }, "convertTo": "pdf" }"#;
+
+        let response = gateway
+            .handle(render_request("/render/letters/letter.html", None, body))
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = gateway
+            .handle(render_request("/render/letters/letter.html", Some("wrong"), body))
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = gateway
+            .handle(render_request("/render/letters/letter.html", Some("secret"), body))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/pdf");
+        assert_eq!(response.headers()["content-disposition"], "inline; filename=\"letter.pdf\"");
+        assert_eq!(response.headers()["x-carbone-template-id"], template_id.as_str());
+        assert_eq!(response.headers()["x-carbone-cache"], "miss");
+        assert_eq!(body_text(response).await, "report content");
+
+        // Same render options, formatted differently.
+        let body = r#"{"convertTo":"pdf","data":{"name":"John"}}"#;
+        let response = gateway
+            .handle(render_request("/render/letters%2Fletter.html", Some("secret"), body))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-carbone-cache"], "hit");
+        assert_eq!(body_text(response).await, "report content");
+
+        mock_render.assert_hits(1);
+        mock_report.assert_hits(1);
+        assert_eq!(gateway.metrics().cache_hits(), 1);
+        assert_eq!(gateway.metrics().cache_misses(), 1);
+
+        let response = gateway
+            .handle(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await;
+        let metrics = body_text(response).await;
+        assert!(metrics.contains("carbone_gateway_responses_total{status=\"200\"} 2"), "{}", metrics);
+        assert!(metrics.contains("carbone_gateway_responses_total{status=\"401\"} 2"), "{}", metrics);
+        assert!(metrics.contains("carbone_gateway_cache_entries 1"), "{}", metrics);
+        assert!(metrics.contains("carbone_gateway_render_duration_seconds_count 1"), "{}", metrics);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_metrics_of_client() -> Result<(), CarboneError> {
+        let template_id = TemplateId::from_bytes(TEMPLATE.as_bytes().to_vec(), None)?;
+
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method("POST").path(format!("/render/{}", template_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+        server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.pdf");
+            then.status(200).body("report content");
+        });
+
+        let helper = Helper::new();
+        let config: &'static Config = Box::leak(Box::new(helper.create_config_for_mock_server(Some(&server))?));
+        let api_token: &'static ApiJsonToken = Box::leak(Box::new(helper.create_api_token()?));
+
+        let metrics = Arc::new(PrometheusMetrics::new());
+        let carbone = Carbone::new(config, Some(api_token))?.with_metrics(metrics.clone());
+        let store = MemoryTemplateStore::default()
+            .with_template("letters/letter.html", TEMPLATE.as_bytes().to_vec());
+        let gateway = Gateway::new(carbone, Arc::new(store), GatewayOptions::default());
+
+        let body = r#"{ "data": { "name": "John" }, "convertTo": "pdf" }"#;
+        for _ in 0..3 {
+            let response = gateway
+                .handle(render_request("/render/letters/letter.html", None, body))
+                .await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        assert_eq!(metrics.cache_misses(), 1);
+        assert_eq!(metrics.cache_hits(), 2);
+        assert_eq!(metrics.renders(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_errors() -> Result<(), CarboneError> {
+        let gateway = create_gateway(None, GatewayOptions::default())?;
+
+        let cases = [
+            ("POST", "/render/unknown.html", "{}", StatusCode::NOT_FOUND),
+            ("POST", "/render/letters/letter.html", "not json", StatusCode::BAD_REQUEST),
+            ("POST", "/render/letters/letter.html", "[1, 2]", StatusCode::BAD_REQUEST),
+            ("POST", "/render/..%2Fsecret.html", "{}", StatusCode::BAD_REQUEST),
+            ("POST", "/render/", "{}", StatusCode::BAD_REQUEST),
+            ("GET", "/render/letters/letter.html", "", StatusCode::METHOD_NOT_ALLOWED),
+            ("GET", "/unknown", "", StatusCode::NOT_FOUND),
+            ("GET", "/health", "", StatusCode::OK),
+        ];
+
+        for (method, path, body, status) in cases {
+            let request = Request::builder()
+                .method(method)
+                .uri(path)
+                .body(Body::from(body))
+                .unwrap();
+            let response = gateway.handle(request).await;
+            assert_eq!(response.status(), status, "{} {}", method, path);
+        }
+
+        let options = GatewayOptions {
+            max_body_bytes: 8,
+            ..Default::default()
+        };
+        let gateway = create_gateway(None, options)?;
+        let response = gateway
+            .handle(render_request("/render/letters/letter.html", None, r#"{ "data": {} }"#))
+            .await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_serve() -> Result<(), CarboneError> {
+        let gateway = create_gateway(None, GatewayOptions::default())?;
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+
+        let (shutdown, shutdown_signal) = tokio::sync::oneshot::channel::<()>();
+        let serve = tokio::spawn(gateway.serve(listener, async {
+            let _ = shutdown_signal.await;
+        }));
+
+        let response = reqwest::get(format!("http://{}/health", addr)).await?;
+        assert_eq!(response.status(), 200);
+
+        shutdown.send(()).unwrap();
+        serve.await.unwrap()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_cache_eviction() {
+        let report = |content: &str| CachedReport {
+            content: content.to_string().into(),
+            content_type: "text/plain".to_string(),
+            file_name: "report.txt".to_string(),
+        };
+
+        let mut cache = RenderCache::new(CacheOptions {
+            max_entries: 2,
+            max_bytes: 10,
+            ttl: None,
+        });
+
+        cache.insert("a".to_string(), report("aaa"));
+        cache.insert("b".to_string(), report("bbb"));
+        assert!(cache.get("a").is_some());
+
+        // "b" is the least recently used.
+        cache.insert("c".to_string(), report("ccc"));
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.bytes(), 6);
+
+        // Over the size limit: "a" and "c" are evicted.
+        cache.insert("d".to_string(), report("dddddddd"));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("d"), Some(report("dddddddd")));
+
+        // Larger than the cache.
+        cache.insert("e".to_string(), report("eeeeeeeeeee"));
+        assert!(cache.get("e").is_none());
+
+        let mut cache = RenderCache::new(CacheOptions {
+            ttl: Some(Duration::ZERO),
+            ..Default::default()
+        });
+        cache.insert("a".to_string(), report("aaa"));
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(cache.get("a").is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_render_cache_key() -> Result<(), CarboneError> {
+        let template_id = TemplateId::new("template_id")?;
+
+        assert_eq!(
+            RenderCache::key(&template_id, &json!({ "a": 1, "b": 2 })),
+            RenderCache::key(&template_id, &serde_json::from_str(r#"{"b":2,"a":1}"#).unwrap())
+        );
+        assert_ne!(
+            RenderCache::key(&template_id, &json!({ "a": 1 })),
+            RenderCache::key(&TemplateId::new("other_id")?, &json!({ "a": 1 }))
+        );
+
+        Ok(())
+    }
+}
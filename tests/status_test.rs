@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+
+    use carbone_sdk_rust::config::Config;
+    use carbone_sdk_rust::errors::CarboneError;
+    use carbone_sdk_rust::status::Status;
+    use carbone_sdk_rust::types::ApiVersion;
+
+    #[test]
+    fn test_deserialize_status() -> Result<(), CarboneError> {
+        let body = r#"{"success":true,"code":200,"message":"OK","version":"4.22.11"}"#;
+
+        let status: Status = serde_json::from_str(body).unwrap();
+
+        let expected = Status {
+            success: true,
+            code: Some(200),
+            message: Some("OK".to_string()),
+            version: Some("4.22.11".to_string()),
+        };
+
+        assert_eq!(status, expected);
+        assert_eq!(status.major_version(), Some("4"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_status_without_version() -> Result<(), CarboneError> {
+        let status: Status = serde_json::from_str(r#"{"success":false}"#).unwrap();
+
+        assert!(!status.success);
+        assert_eq!(status.major_version(), None);
+        assert!(!status.is_compatible(&Config::default()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_status_is_compatible() -> Result<(), CarboneError> {
+        let status: Status = serde_json::from_str(r#"{"success":true,"version":"3.5.2"}"#).unwrap();
+
+        let config_v3 = Config::new(
+            "http://127.0.0.1".to_string(),
+            4,
+            ApiVersion::new("3".to_string())?,
+        )?;
+
+        assert!(status.is_compatible(&config_v3));
+        assert!(!status.is_compatible(&Config::default()));
+
+        Ok(())
+    }
+}
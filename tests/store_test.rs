@@ -0,0 +1,266 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::store::*;
+#[cfg(feature = "sync")]
+use carbone_sdk_rust::sync::{sync_store, SyncOptions};
+use carbone_sdk_rust::template::TemplateId;
+use carbone_sdk_rust::types::JsonData;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn create_store_dir(name: &str) -> Result<PathBuf, CarboneError> {
+        let dir = std::env::temp_dir().join(format!("carbone-store-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    #[test]
+    fn test_memory_store() -> Result<(), CarboneError> {
+        let store = MemoryTemplateStore::default()
+            .with_template("letter.html", b"<p>{d.name}</p>".to_vec());
+
+        store.put("invoices/invoice.html", b"<p>{d.total}</p>")?;
+
+        assert_eq!(store.names()?, vec!["invoices/invoice.html", "letter.html"]);
+        assert_eq!(store.get("letter.html")?, Some(b"<p>{d.name}</p>".to_vec()));
+        assert_eq!(store.get("missing.html")?, None);
+
+        let template_file = store.template_file("invoices/invoice.html")?;
+        assert_eq!(template_file.file_name(), "invoice.html");
+
+        assert!(store.remove("letter.html")?);
+        assert!(!store.remove("letter.html")?);
+        assert!(matches!(
+            store.template_file("letter.html"),
+            Err(CarboneError::TemplateFileNotFound(name)) if name == "letter.html"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fs_store() -> Result<(), CarboneError> {
+        let dir = create_store_dir("fs")?;
+        let store = FsTemplateStore::new(&dir);
+
+        store.put("invoices/invoice.html", b"<p>{d.total}</p>")?;
+        store.put("letter.html", b"<p>{d.name}</p>")?;
+        fs::write(dir.join(".hidden.html"), "hidden")?;
+        fs::write(dir.join("invoices/~$invoice.html"), "lock file")?;
+
+        assert_eq!(store.names()?, vec!["invoices/invoice.html", "letter.html"]);
+        assert_eq!(fs::read(dir.join("invoices/invoice.html"))?, b"<p>{d.total}</p>");
+        assert_eq!(store.get("invoices/invoice.html")?, Some(b"<p>{d.total}</p>".to_vec()));
+        assert_eq!(store.get("invoices")?, None);
+
+        assert!(store.remove("letter.html")?);
+        assert_eq!(store.names()?, vec!["invoices/invoice.html"]);
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fs_store_invalid_names() {
+        let store = FsTemplateStore::new("tests/data");
+
+        for name in ["", "../Cargo.toml", "/etc/passwd", "invoices/../../Cargo.toml"] {
+            assert!(
+                matches!(store.get(name), Err(CarboneError::Error(_))),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_fs_store_unknown_dir() {
+        let store = FsTemplateStore::new("tests/unknown_dir");
+
+        assert!(matches!(store.names(), Err(CarboneError::FileNotFound(_))));
+    }
+
+    #[cfg(feature = "kv-store")]
+    #[test]
+    fn test_kv_store() -> Result<(), CarboneError> {
+        let dir = create_store_dir("kv")?;
+        let path = dir.join("templates.redb");
+
+        {
+            let store = KvTemplateStore::open(&path)?;
+            store.put("letter.html", b"<p>{d.name}</p>")?;
+            store.put("invoices/invoice.html", b"<p>{d.total}</p>")?;
+        }
+
+        let store = KvTemplateStore::open(&path)?;
+        assert_eq!(store.names()?, vec!["invoices/invoice.html", "letter.html"]);
+        assert_eq!(store.get("letter.html")?, Some(b"<p>{d.name}</p>".to_vec()));
+
+        assert!(store.remove("letter.html")?);
+        assert!(!store.remove("letter.html")?);
+        assert_eq!(store.get("letter.html")?, None);
+
+        drop(store);
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_report_with_template_name() -> Result<(), CarboneError> {
+        let content = b"<p>{d.name}</p>".to_vec();
+        let template_id = TemplateId::from_bytes(content.clone(), Some("salt1234"))?;
+
+        let store = MemoryTemplateStore::default().with_template("letters/letter.html", content);
+
+        let server = MockServer::start();
+
+        let mut mock_render_not_found = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()));
+            then.status(404).json_body(json!({
+                "success": false,
+                "error": "Template not found"
+            }));
+        });
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("filename=\"letter.html\"")
+                .body_contains("name=\"payload\"\r\n\r\nsalt1234");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": template_id.as_str() }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.pdf");
+            then.status(200).body("report content");
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_template_store(Arc::new(store));
+
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" }, "convertTo": "pdf" }"#.to_string())?;
+
+        // The renders are refused before and after the upload of the template from the store.
+        let result = carbone
+            .generate_report_with_template_name("letters/letter.html", json_data, Some("salt1234"))
+            .await;
+
+        mock_render_not_found.assert_hits(2);
+        mock_upload.assert();
+        assert!(result.is_err());
+        mock_render_not_found.delete();
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" }, "convertTo": "pdf" }"#.to_string())?;
+        let report_content = carbone
+            .generate_report_with_template_name("letters/letter.html", json_data, Some("salt1234"))
+            .await?;
+
+        mock_render.assert();
+        mock_report.assert();
+        mock_upload.assert_hits(1);
+        assert_eq!(report_content, "report content");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_report_with_template_name_errors() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(None)?;
+
+        let json_data = JsonData::new(r#"{ "data": {} }"#.to_string())?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let result = carbone
+            .generate_report_with_template_name("letter.html", json_data.clone(), None)
+            .await;
+        assert!(matches!(result, Err(CarboneError::Error(_))));
+
+        let carbone = carbone.with_template_store(Arc::new(MemoryTemplateStore::default()));
+        let result = carbone
+            .generate_report_with_template_name("letter.html", json_data, None)
+            .await;
+        assert!(matches!(result, Err(CarboneError::TemplateFileNotFound(_))));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sync")]
+    #[tokio::test]
+    async fn test_sync_store() -> Result<(), CarboneError> {
+        let letter_id = TemplateId::from_bytes(b"<p>{d.name}</p>".to_vec(), None)?;
+        let invoice_id = TemplateId::from_bytes(b"<p>{d.total}</p>".to_vec(), None)?;
+
+        let store = MemoryTemplateStore::default()
+            .with_template("letter.html", b"<p>{d.name}</p>".to_vec())
+            .with_template("invoices/invoice.html", b"<p>{d.total}</p>".to_vec());
+
+        let server = MockServer::start();
+
+        let mock_list = server.mock(|when, then| {
+            when.method("GET").path("/templates");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": [ { "id": letter_id.as_str() } ]
+            }));
+        });
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("filename=\"invoice.html\"");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": invoice_id.as_str() }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+        let report = sync_store(&carbone, &store, &SyncOptions::default()).await?;
+
+        mock_list.assert();
+        mock_upload.assert();
+
+        assert_eq!(report.uploaded.len(), 1);
+        assert_eq!(report.uploaded[0].path, "invoices/invoice.html");
+        assert_eq!(report.unchanged.len(), 1);
+        assert_eq!(report.manifest.get("letter.html"), Some(&letter_id));
+        assert_eq!(report.manifest.get("invoices/invoice.html"), Some(&invoice_id));
+
+        Ok(())
+    }
+}
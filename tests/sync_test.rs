@@ -0,0 +1,379 @@
+use std::fs;
+use std::path::PathBuf;
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::config::Config;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::sync::{sync_templates, Manifest, SyncOptions};
+use carbone_sdk_rust::template::TemplateId;
+use carbone_sdk_rust::types::ApiVersion;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn create_templates_dir(name: &str) -> Result<PathBuf, CarboneError> {
+        let dir = std::env::temp_dir().join(format!("carbone-sync-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        fs::create_dir_all(dir.join("invoices"))?;
+        fs::write(dir.join("letter.html"), "<p>{d.name}</p>")?;
+        fs::write(dir.join("invoices/invoice.html"), "<p>{d.total}</p>")?;
+        fs::write(dir.join(".hidden.html"), "hidden")?;
+        fs::write(dir.join("invoices/~$invoice.html"), "lock file")?;
+
+        Ok(dir)
+    }
+
+    fn template_id_of(content: &str) -> Result<TemplateId, CarboneError> {
+        TemplateId::from_bytes(content.as_bytes().to_vec(), None)
+    }
+
+    #[tokio::test]
+    async fn test_sync_templates_with_remote() -> Result<(), CarboneError> {
+        let dir = create_templates_dir("remote")?;
+        let letter_id = template_id_of("<p>{d.name}</p>")?;
+        let invoice_id = template_id_of("<p>{d.total}</p>")?;
+
+        let server = MockServer::start();
+
+        let mock_list = server.mock(|when, then| {
+            when.method("GET").path("/templates");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": [ { "id": "other", "versionId": letter_id.as_str() } ]
+            }));
+        });
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("filename=\"invoice.html\"");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": invoice_id.as_str() }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let manifest_path = dir.join("manifest.json");
+        let options = SyncOptions {
+            manifest_path: Some(manifest_path.clone()),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+        let report = sync_templates(&carbone, &dir, &options).await?;
+
+        mock_list.assert();
+        mock_upload.assert();
+
+        assert_eq!(report.uploaded.len(), 1);
+        assert_eq!(report.uploaded[0].path, "invoices/invoice.html");
+        assert_eq!(report.unchanged.len(), 1);
+        assert_eq!(report.unchanged[0].path, "letter.html");
+        assert!(report.deleted.is_empty());
+
+        let manifest = Manifest::load(&manifest_path)?;
+        assert_eq!(manifest.len(), 2);
+        assert_eq!(manifest.get("letter.html"), Some(&letter_id));
+        assert_eq!(manifest.get("invoices/invoice.html"), Some(&invoice_id));
+
+        fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_sync_templates_with_mock_client() -> Result<(), CarboneError> {
+        use carbone_sdk_rust::testing::mock::MockCarbone;
+
+        let dir = create_templates_dir("mock")?;
+        let letter_id = template_id_of("<p>{d.name}</p>")?;
+        let invoice_id = template_id_of("<p>{d.total}</p>")?;
+
+        let carbone = MockCarbone::new();
+        carbone
+            .upload_template("letter.html", b"<p>{d.name}</p>".to_vec(), None)
+            .await?;
+
+        let report = sync_templates(&carbone, &dir, &SyncOptions::default()).await?;
+
+        assert_eq!(report.uploaded.len(), 1);
+        assert_eq!(report.uploaded[0].template_id, invoice_id);
+        assert_eq!(report.unchanged.len(), 1);
+        assert_eq!(report.unchanged[0].template_id, letter_id);
+        assert_eq!(carbone.template_ids().len(), 2);
+
+        fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_templates_with_manifest_and_orphans() -> Result<(), CarboneError> {
+        let dir = create_templates_dir("orphans")?;
+        let letter_id = template_id_of("<p>{d.name}</p>")?;
+        let invoice_id = template_id_of("<p>{d.total}</p>")?;
+        let old_id = TemplateId::new("old_template_id")?;
+
+        let manifest_path = dir.join("manifest.json");
+        let mut previous = Manifest::default();
+        previous.insert("letter.html".to_string(), letter_id.clone());
+        previous.insert("invoices/invoice.html".to_string(), old_id.clone());
+        previous.save(&manifest_path)?;
+
+        let server = MockServer::start();
+
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST").path("/template");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": invoice_id.as_str() }
+            }));
+        });
+        let mock_delete = server.mock(|when, then| {
+            when.method("DELETE").path("/template/old_template_id");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let options = SyncOptions {
+            delete_orphans: true,
+            manifest_path: Some(manifest_path.clone()),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, None)?;
+        let report = sync_templates(&carbone, &dir, &options).await?;
+
+        mock_upload.assert();
+        mock_delete.assert();
+
+        assert_eq!(report.uploaded.len(), 1);
+        assert_eq!(report.unchanged.len(), 1);
+        assert_eq!(report.deleted.len(), 1);
+        assert_eq!(report.deleted[0].template_id, old_id);
+
+        let manifest = Manifest::load(&manifest_path)?;
+        assert_eq!(manifest.get("invoices/invoice.html"), Some(&invoice_id));
+
+        fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_templates_orphan_already_deleted() -> Result<(), CarboneError> {
+        let dir = create_templates_dir("deleted-orphans")?;
+        let letter_id = template_id_of("<p>{d.name}</p>")?;
+        let invoice_id = template_id_of("<p>{d.total}</p>")?;
+
+        let manifest_path = dir.join("manifest.json");
+        let mut previous = Manifest::default();
+        previous.insert("letter.html".to_string(), letter_id.clone());
+        previous.insert("invoices/invoice.html".to_string(), invoice_id.clone());
+        previous.insert("old.html".to_string(), TemplateId::new("old_template_id")?);
+        previous.insert("gone.html".to_string(), TemplateId::new("gone_template_id")?);
+        previous.save(&manifest_path)?;
+
+        let server = MockServer::start();
+
+        let mock_delete_old = server.mock(|when, then| {
+            when.method("DELETE").path("/template/old_template_id");
+            then.status(200).json_body(json!({ "success": true }));
+        });
+        let mock_delete_gone = server.mock(|when, then| {
+            when.method("DELETE").path("/template/gone_template_id");
+            then.status(404).json_body(json!({ "success": false, "error": "Template not found" }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let options = SyncOptions {
+            delete_orphans: true,
+            manifest_path: Some(manifest_path.clone()),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, None)?;
+        let report = sync_templates(&carbone, &dir, &options).await?;
+
+        mock_delete_old.assert();
+        mock_delete_gone.assert();
+        assert_eq!(report.deleted.len(), 2);
+
+        let manifest = Manifest::load(&manifest_path)?;
+        assert_eq!(manifest.get("old.html"), None);
+        assert_eq!(manifest.get("gone.html"), None);
+        assert_eq!(manifest.get("letter.html"), Some(&letter_id));
+
+        fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_templates_dry_run() -> Result<(), CarboneError> {
+        let dir = create_templates_dir("dry-run")?;
+        let invoice_id = template_id_of("<p>{d.total}</p>")?;
+
+        let manifest_path = dir.join("manifest.json");
+        let mut previous = Manifest::default();
+        previous.insert("removed.html".to_string(), TemplateId::new("removed_id")?);
+        previous.save(&manifest_path)?;
+
+        let server = MockServer::start();
+
+        let mock_any = server.mock(|when, then| {
+            when.any_request();
+            then.status(500);
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let options = SyncOptions {
+            dry_run: true,
+            delete_orphans: true,
+            manifest_path: Some(manifest_path.clone()),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, None)?;
+        let report = sync_templates(&carbone, &dir, &options).await?;
+
+        mock_any.assert_hits(0);
+
+        assert!(report.dry_run);
+        assert_eq!(report.uploaded.len(), 2);
+        assert!(report
+            .uploaded
+            .iter()
+            .any(|entry| entry.template_id == invoice_id));
+        assert_eq!(report.deleted.len(), 1);
+        assert_eq!(report.manifest.len(), 2);
+
+        let manifest = Manifest::load(&manifest_path)?;
+        assert_eq!(manifest, previous);
+
+        fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_templates_delete_orphans_without_manifest() -> Result<(), CarboneError> {
+        let dir = create_templates_dir("orphans-without-manifest")?;
+
+        let server = MockServer::start();
+
+        let mock_any = server.mock(|when, then| {
+            when.any_request();
+            then.status(500);
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let options = SyncOptions {
+            delete_orphans: true,
+            manifest_path: Some(dir.join("manifest.json")),
+            ..Default::default()
+        };
+
+        let carbone = Carbone::new(&config, None)?;
+        let result = sync_templates(&carbone, &dir, &options).await;
+
+        mock_any.assert_hits(0);
+        assert!(matches!(result, Err(CarboneError::Error(_))));
+        assert!(!dir.join("manifest.json").exists());
+
+        fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_templates_with_remote_before_api_v4() -> Result<(), CarboneError> {
+        let dir = create_templates_dir("remote-v3")?;
+
+        let server = MockServer::start();
+
+        let mock_list = server.mock(|when, then| {
+            when.method("GET").path("/templates");
+            then.status(404).json_body(json!({ "success": false, "error": "Not found" }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let result = sync_templates(&carbone, &dir, &SyncOptions::default()).await;
+
+        mock_list.assert();
+        match result {
+            Err(CarboneError::Error(message)) => assert!(message.contains("API v4"), "{}", message),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let config = Config {
+            api_version: ApiVersion::new("3".to_string())?,
+            ..config
+        };
+
+        let carbone = Carbone::new(&config, None)?;
+        let result = sync_templates(&carbone, &dir, &SyncOptions::default()).await;
+
+        mock_list.assert_hits(1);
+        assert!(matches!(result, Err(CarboneError::Error(_))));
+
+        fs::remove_dir_all(dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_templates_unknown_dir() -> Result<(), CarboneError> {
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(None)?;
+
+        let carbone = Carbone::new(&config, None)?;
+        let result = sync_templates(&carbone, "tests/data/unknown", &SyncOptions::default())
+            .await;
+
+        assert!(matches!(result, Err(CarboneError::FileNotFound(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_manifest_load_missing_file() -> Result<(), CarboneError> {
+        let manifest = Manifest::load("tests/data/unknown-manifest.json")?;
+
+        assert!(manifest.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_manifest_load_invalid_file() {
+        let result = Manifest::load("tests/data/template.test.html");
+
+        assert!(matches!(result, Err(CarboneError::ParseError(_, _))));
+    }
+}
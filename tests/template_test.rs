@@ -2,6 +2,7 @@ use std::fs;
 
 use carbone_sdk_rust::errors::CarboneError;
 use carbone_sdk_rust::template::*;
+use carbone_sdk_rust::types::ApiVersion;
 
 #[cfg(test)]
 mod tests {
@@ -23,7 +24,7 @@ mod tests {
     #[test]
     fn test_template_file_file_content_given() -> Result<(), CarboneError> {
         let template_file_path = "tests/data/template.test.odt";
-        let file_content = fs::read(template_file_path.to_owned())?;
+        let file_content = fs::read(template_file_path)?;
         let template_file = TemplateFile::new(template_file_path.to_string(), Some(file_content))?;
 
         assert_eq!(template_file.path_as_str(), template_file_path);
@@ -57,6 +58,69 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_template_file_content_given_without_file() -> Result<(), CarboneError> {
+        let template_file = TemplateFile::new(
+            "templates/unknown/letter.html".to_string(),
+            Some(b"<p>{d.name}</p>".to_vec()),
+        )?;
+
+        assert_eq!(template_file.file_name(), "letter.html");
+        assert_eq!(template_file.extension(), Some("html"));
+        assert!(template_file.metadata.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_file_from_bytes() -> Result<(), CarboneError> {
+        let content = fs::read("tests/data/template.test.odt")?;
+        let template_file = TemplateFile::from_bytes("template.test.odt", content)?;
+
+        let template_id = template_file.generate_id(None)?;
+        let template_id_from_disk =
+            TemplateFile::new("tests/data/template.test.odt".to_string(), None)?.generate_id(None)?;
+
+        assert_eq!(template_id, template_id_from_disk);
+        assert!(TemplateFile::from_bytes("", Vec::new()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_source() -> Result<(), CarboneError> {
+        let content = fs::read("tests/data/template.test.html")?;
+        let expected = TemplateId::from_bytes(content.clone(), Some("payload"))?;
+
+        let sources = vec![
+            TemplateSource::from_path("tests/data/template.test.html"),
+            TemplateSource::from_bytes("template.test.html", content.clone()),
+            TemplateSource::from_reader("template.test.html", std::io::Cursor::new(content)),
+            TemplateSource::from(&TemplateFile::new("tests/data/template.test.html".to_string(), None)?),
+        ];
+
+        for source in sources {
+            assert_eq!(TemplateId::from_source(source, Some("payload"))?, expected);
+        }
+
+        let result = TemplateSource::from_path("tests/data/unknown.docx").into_template_file();
+        assert!(matches!(result, Err(CarboneError::TemplateFileNotFound(_))));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_template_source_non_utf8_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = std::path::Path::new(OsStr::from_bytes(b"tests/data/template\xff.odt"));
+
+        let result = TemplateSource::from_path(path).into_template_file();
+        assert!(matches!(result, Err(CarboneError::Error(_))));
+    }
+
     #[test]
     fn test_generate_template_id_odt_1() -> Result<(), CarboneError> {
         let template_file_path = "tests/data/template.test.odt".to_string();
@@ -131,6 +195,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_template_id_from_bytes_for_api() -> Result<(), CarboneError> {
+        let data = fs::read("tests/data/template.test.odt")?;
+
+        let expected_template_id = TemplateId::new(
+            "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114".to_string(),
+        )?;
+
+        for version in ["3", "4", "4.1"] {
+            let api_version = ApiVersion::new(version.to_string())?;
+            let template_id = TemplateId::from_bytes_for_api(&api_version, data.clone(), None)?;
+            assert_eq!(template_id, expected_template_id);
+        }
+
+        let api_version = ApiVersion::new("4".to_string())?;
+        let template_id = TemplateId::from_bytes_for_api(&api_version, data.clone(), Some("ThisIsAPayload"))?;
+        assert_eq!(template_id, TemplateId::from_bytes(data.clone(), Some("ThisIsAPayload"))?);
+
+        let api_version = ApiVersion::new("5".to_string())?;
+        let result = TemplateId::from_bytes_for_api(&api_version, data, None);
+        assert!(matches!(result, Err(CarboneError::Error(_))));
+
+        Ok(())
+    }
+
     #[test]
     fn test_template_id_as_ref() -> Result<(), CarboneError> {
         let id_value = "1";
@@ -140,4 +229,25 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_version_id() -> Result<(), CarboneError> {
+        let version_id = VersionId::new("0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114")?;
+
+        assert_eq!(
+            version_id.as_str(),
+            "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_id_empty_value_given() {
+        let result = VersionId::new("");
+
+        let expected_error = CarboneError::EmptyString("version_id".to_string());
+
+        assert_eq!(result.unwrap_err().to_string(), expected_error.to_string());
+    }
 }
@@ -0,0 +1,222 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::template::TemplateId;
+use carbone_sdk_rust::testing::cassette::*;
+use carbone_sdk_rust::testing::*;
+use carbone_sdk_rust::types::{ApiJsonToken, Endpoint, JsonData};
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const TEMPLATE: &str = "<p>{d.name}</p>";
+
+    fn create_api_token() -> Result<ApiJsonToken, CarboneError> {
+        // The fake server accepts any token of a valid length.
+        ApiJsonToken::new("test_".repeat(60))
+    }
+
+    #[tokio::test]
+    async fn test_upload_render_and_get_report() -> Result<(), CarboneError> {
+        let fake = FakeCarbone::start()?;
+        let config = fake.config()?;
+        let api_token = create_api_token()?;
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+
+        let template_id = carbone
+            .upload_template("letter.html", TEMPLATE.as_bytes().to_vec(), Some("salt"))
+            .await?;
+
+        assert_eq!(template_id, TemplateId::from_bytes(TEMPLATE.as_bytes().to_vec(), Some("salt"))?);
+        assert!(fake.has_template(&template_id));
+        assert_eq!(fake.template_ids(), vec![template_id.clone()]);
+
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" }, "convertTo": "pdf" }"#.to_string())?;
+        let render_id = carbone.render_data(template_id.clone(), json_data.clone()).await?;
+        assert!(render_id.as_str().ends_with(".pdf"));
+
+        let report = carbone.get_report(&render_id).await?;
+        assert!(report.starts_with(b"%PDF-"));
+        assert!(String::from_utf8_lossy(&report).contains(r#"data: {"name":"John"}"#));
+
+        // A report is downloaded once, and the same render gives the same report.
+        assert!(carbone.get_report(&render_id).await.is_err());
+        assert_eq!(carbone.generate_report_with_template_id(template_id, json_data).await?, report);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_report_uploads_unknown_template() -> Result<(), CarboneError> {
+        let fake = FakeCarbone::start()?;
+        let config = fake.config()?;
+        let api_token = create_api_token()?;
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" } }"#.to_string())?;
+        let report = carbone
+            .generate_report("letter.html".to_string(), TEMPLATE.as_bytes().to_vec(), json_data.clone(), None)
+            .await?;
+        assert!(String::from_utf8_lossy(&report).starts_with("Carbone fake report\n"));
+
+        assert_eq!(fake.hits(Endpoint::Render), 2);
+        assert_eq!(fake.hits(Endpoint::Upload), 1);
+        assert_eq!(fake.hits(Endpoint::Report), 1);
+
+        carbone
+            .generate_report("letter.html".to_string(), TEMPLATE.as_bytes().to_vec(), json_data, None)
+            .await?;
+        assert_eq!(fake.hits(Endpoint::Render), 3);
+        assert_eq!(fake.hits(Endpoint::Upload), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_delete_and_status() -> Result<(), CarboneError> {
+        let fake = FakeCarbone::start()?;
+        let config = fake.config()?;
+        let api_token = create_api_token()?;
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+
+        let template_id = fake.add_template("letter.html", TEMPLATE.as_bytes().to_vec(), None)?;
+
+        assert_eq!(carbone.download_template(&template_id).await?, TEMPLATE.as_bytes());
+        assert!(carbone.delete_template(template_id.clone()).await?);
+        assert!(!fake.has_template(&template_id));
+        assert!(carbone.download_template(&template_id).await.is_err());
+
+        let status = carbone.get_status().await?;
+        assert!(status.success);
+        assert_eq!(status.version.as_deref(), Some(FAKE_CARBONE_VERSION));
+        assert!(status.is_compatible(&config));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_faults() -> Result<(), CarboneError> {
+        let fake = FakeCarbone::start()?;
+        let config = fake.config()?;
+        let api_token = create_api_token()?;
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+
+        let template_id = fake.add_template("letter.html", TEMPLATE.as_bytes().to_vec(), None)?;
+        let json_data = JsonData::new(r#"{ "data": {} }"#.to_string())?;
+
+        fake.fail_next(Endpoint::Render, Fault::Status(503));
+        fake.fail_next(Endpoint::Render, Fault::TooManyRequests { retry_after: 1 });
+
+        match carbone.render_data(template_id.clone(), json_data.clone()).await {
+            Err(CarboneError::HttpError { status_code, .. }) => assert_eq!(status_code.as_u16(), 503),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        match carbone.render_data(template_id.clone(), json_data.clone()).await {
+            Err(CarboneError::HttpError { status_code, .. }) => assert_eq!(status_code.as_u16(), 429),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        carbone.render_data(template_id.clone(), json_data.clone()).await?;
+
+        fake.fail_next(Endpoint::Status, Fault::MalformedJson);
+        assert!(matches!(carbone.get_status().await, Err(CarboneError::RequestError(_))));
+
+        fake.fail_next(Endpoint::Status, Fault::Latency(Duration::from_millis(200)));
+        let start = Instant::now();
+        carbone.get_status().await?;
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        fake.set_latency(Duration::from_millis(100));
+        let start = Instant::now();
+        carbone.get_status().await?;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        assert_eq!(fake.hits(Endpoint::Render), 3);
+        assert_eq!(fake.hits(Endpoint::Status), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay_cassette() -> Result<(), CarboneError> {
+        let path = std::env::temp_dir()
+            .join(format!("carbone-cassette-{}", std::process::id()))
+            .join("generate_report.json");
+
+        let json_data = JsonData::new(r#"{ "data": { "name": "John" }, "convertTo": "pdf" }"#.to_string())?;
+        let api_token = create_api_token()?;
+
+        let report = {
+            let fake = FakeCarbone::start()?;
+            let config = fake.config()?;
+            let cassette = Arc::new(Cassette::open(&path, CassetteMode::Record)?);
+            let carbone = Carbone::new(&config, Some(&api_token))?.with_cassette(cassette.clone());
+
+            let report = carbone
+                .generate_report("letter.html".to_string(), TEMPLATE.as_bytes().to_vec(), json_data.clone(), None)
+                .await?;
+
+            let methods: Vec<String> = cassette
+                .interactions()
+                .iter()
+                .map(|interaction| format!("{} {}", interaction.request.method, interaction.response.status))
+                .collect();
+            assert_eq!(methods, vec!["POST 404", "POST 200", "POST 200", "GET 200"]);
+
+            report
+        };
+
+        let content = std::fs::read_to_string(&path)?;
+        assert!(!content.contains(api_token.as_str()));
+
+        // A new fake server without templates: the responses come from the cassette.
+        let fake = FakeCarbone::start()?;
+        let cassette = Arc::new(Cassette::open(&path, CassetteMode::Replay)?);
+        let config = fake.config()?;
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_cassette(cassette.clone());
+
+        let replayed = carbone
+            .generate_report("letter.html".to_string(), TEMPLATE.as_bytes().to_vec(), json_data, None)
+            .await?;
+        assert_eq!(replayed, report);
+        assert_eq!(cassette.remaining(), 0);
+        assert_eq!(fake.hits(Endpoint::Render), 0);
+
+        match carbone.get_status().await {
+            Err(CarboneError::Error(message)) => assert!(message.starts_with("no recorded interaction for GET /status")),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        std::fs::remove_dir_all(path.parent().unwrap())?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_replay_cassette_blocking() -> Result<(), CarboneError> {
+        let path = std::env::temp_dir()
+            .join(format!("carbone-cassette-blocking-{}", std::process::id()))
+            .join("status.json");
+
+        let fake = FakeCarbone::start()?;
+        let config = fake.config()?;
+        let api_token = create_api_token()?;
+
+        let cassette = Arc::new(Cassette::open(&path, CassetteMode::Record)?);
+        let carbone = carbone_sdk_rust::blocking::Carbone::new(&config, &api_token)?.with_cassette(cassette);
+        let status = carbone.get_status()?;
+
+        let cassette = Arc::new(Cassette::open(&path, CassetteMode::Replay)?);
+        let carbone = carbone_sdk_rust::blocking::Carbone::new(&config, &api_token)?.with_cassette(cassette);
+        assert_eq!(carbone.get_status()?, status);
+        assert_eq!(fake.hits(Endpoint::Status), 1);
+
+        std::fs::remove_dir_all(path.parent().unwrap())?;
+
+        Ok(())
+    }
+}
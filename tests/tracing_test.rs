@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use httpmock::prelude::*;
+use serde_json::json;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::Registry;
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::carbone_response::{APIResponse, APIResponseData};
+#[cfg(feature = "cluster")]
+use carbone_sdk_rust::cluster::CarboneCluster;
+#[cfg(feature = "cluster")]
+use carbone_sdk_rust::config::Config;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::render::RenderId;
+use carbone_sdk_rust::template::TemplateId;
+#[cfg(feature = "cluster")]
+use carbone_sdk_rust::types::ApiVersion;
+use carbone_sdk_rust::types::JsonData;
+
+mod helper;
+
+use helper::Helper;
+
+/// Span recorded by `SpanRecorder`, with its fields formatted.
+#[derive(Debug, Clone)]
+struct RecordedSpan {
+    name: &'static str,
+    fields: BTreeMap<String, String>,
+}
+
+impl Visit for RecordedSpan {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields.insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+/// Layer keeping the spans in memory, by ID.
+#[derive(Clone, Default)]
+struct SpanRecorder {
+    spans: Arc<Mutex<Vec<(Id, RecordedSpan)>>>,
+}
+
+impl SpanRecorder {
+    fn spans(&self, name: &str) -> Vec<RecordedSpan> {
+        self.spans
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, span)| span.name == name)
+            .map(|(_, span)| span.clone())
+            .collect()
+    }
+
+    fn values(&self) -> Vec<String> {
+        self.spans
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|(_, span)| span.fields.values().cloned().collect::<Vec<_>>())
+            .collect()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for SpanRecorder {
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+        let mut span = RecordedSpan {
+            name: attributes.metadata().name(),
+            fields: BTreeMap::new(),
+        };
+        attributes.record(&mut span);
+        self.spans.lock().unwrap().push((id.clone(), span));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        let mut spans = self.spans.lock().unwrap();
+        // IDs are reused once spans close, so the latest span with the ID is the open one.
+        if let Some((_, span)) = spans.iter_mut().rev().find(|(span_id, _)| span_id == id) {
+            values.record(span);
+        }
+    }
+}
+
+fn subscriber(recorder: &SpanRecorder) -> impl Subscriber {
+    Registry::default().with(recorder.clone())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const TEMPLATE_ID: &str = "0545253258577a632a99065f0572720225f5165cc43db9515e9cef0e17b40114";
+    const RENDER_ID: &str = "MTAuMjAuMjEuMTAgICAg01E98H4R7PMC2H6XSE5Z6J8XYQ.pdf";
+
+    fn render_response() -> APIResponse {
+        APIResponse {
+            success: true,
+            data: Some(APIResponseData {
+                template_id: None,
+                render_id: Some(RenderId::new(RENDER_ID).unwrap()),
+                template_file_extension: None,
+            }),
+            error: None,
+            code: None,
+        }
+    }
+
+    fn mock_render(server: &MockServer) {
+        server.mock(|when, then| {
+            when.method("POST").path(format!("/render/{}", TEMPLATE_ID));
+            then.status(200).json_body_obj(&render_response());
+        });
+        server.mock(|when, then| {
+            when.method("GET").path(format!("/render/{}", RENDER_ID));
+            then.status(200).body("report");
+        });
+    }
+
+    fn json_data() -> Result<JsonData, CarboneError> {
+        JsonData::new(json!({"data": {"secret": "do-not-trace"}, "convertTo": "pdf"}).to_string())
+    }
+
+    #[tokio::test]
+    async fn test_spans_of_generate_report_with_template_id() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+        mock_render(&server);
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let recorder = SpanRecorder::default();
+        let _guard = tracing::subscriber::set_default(subscriber(&recorder));
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+        let report = carbone
+            .generate_report_with_template_id(TemplateId::new(TEMPLATE_ID)?, json_data()?)
+            .await?;
+        assert_eq!(report.as_ref(), b"report");
+
+        let generate = recorder.spans("generate_report_with_template_id");
+        assert_eq!(generate.len(), 1);
+        assert_eq!(generate[0].fields["template_id"], TEMPLATE_ID);
+
+        let render = recorder.spans("render_data");
+        assert_eq!(render[0].fields["render_id"], RENDER_ID);
+
+        let get_report = recorder.spans("get_report");
+        assert_eq!(get_report[0].fields["render_id"], RENDER_ID);
+        assert_eq!(get_report[0].fields["bytes"], "6");
+
+        let requests = recorder.spans("http_request");
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].fields["endpoint"], "Render");
+        assert_eq!(requests[1].fields["endpoint"], "Report");
+        for request in &requests {
+            assert_eq!(request.fields["http.status_code"], "200");
+            assert!(request.fields.contains_key("elapsed_ms"));
+        }
+
+        for value in recorder.values() {
+            assert!(!value.contains(api_token.as_str()));
+            assert!(!value.contains("do-not-trace"));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "cluster")]
+    #[tokio::test]
+    async fn test_spans_of_cluster_retries() -> Result<(), CarboneError> {
+        let server = MockServer::start();
+        mock_render(&server);
+
+        let helper = Helper::new();
+        let api_version = ApiVersion::new("4".to_string())?;
+        let configs = vec![
+            Config::new("http://127.0.0.1:1".to_string(), 1, api_version)?,
+            helper.create_config_for_mock_server(Some(&server))?,
+        ];
+        let api_token = helper.create_api_token()?;
+
+        let recorder = SpanRecorder::default();
+        let _guard = tracing::subscriber::set_default(subscriber(&recorder));
+
+        let cluster = CarboneCluster::new(&configs, Some(&api_token))?;
+        cluster
+            .generate_report_with_template_id(TemplateId::new(TEMPLATE_ID)?, json_data()?)
+            .await?;
+
+        let spans = recorder.spans("cluster.generate_report_with_template_id");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].fields["node"], "1");
+        assert_eq!(spans[0].fields["retries"], "1");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::template::schema::TemplateSchema;
+use carbone_sdk_rust::template::validate::*;
+use carbone_sdk_rust::template::TemplateFile;
+use carbone_sdk_rust::types::JsonData;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn invoice_template() -> Result<TemplateFile, CarboneError> {
+        TemplateFile::new("tests/data/template.odt".to_string(), None)
+    }
+
+    fn valid_render_options() -> serde_json::Value {
+        json!({
+            "data": {
+                "id": 42,
+                "date": 1717171717,
+                "total": 120.5,
+                "company": { "name": "Carbone", "address": "1 rue", "city": "Paris", "postalCode": "75000" },
+                "customer": { "name": "John", "address": "2 rue", "city": "Lyon", "postalCode": "69000" },
+                "products": [
+                    { "name": "Pen", "priceUnit": 1, "priceTotal": 2, "quantity": 2 }
+                ]
+            },
+            "convertTo": "pdf"
+        })
+    }
+
+    #[test]
+    fn test_validate_render_data_valid() -> Result<(), CarboneError> {
+        let json_data = JsonData::new(valid_render_options().to_string())?;
+
+        let issues = validate_render_data(&invoice_template()?, &json_data)?;
+
+        assert!(issues.is_empty(), "{:?}", issues);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_aliases() -> Result<(), CarboneError> {
+        let content = r#"
+            {#customer = d.customer} {#total = d.total:formatN(2)}
+            {$customer.name} {$customer.address.city} {$total}
+        "#;
+        let schema = TemplateSchema::from_bytes("letter.html", content.as_bytes())?;
+        assert!(!schema.unresolved_aliases);
+
+        let render_options = json!({
+            "data": { "customer": { "name": "John", "address": { "city": "Lyon" } }, "total": 10 }
+        });
+        assert_eq!(schema.validate_value(&render_options), vec![]);
+
+        let render_options = json!({
+            "data": { "customer": { "name": "John", "age": 42 }, "total": "ten" }
+        });
+        assert_eq!(
+            schema.validate_value(&render_options),
+            vec![
+                DataIssue::MissingPath("d.customer.address".to_string()),
+                DataIssue::UnusedKey("d.customer.age".to_string()),
+                DataIssue::TypeMismatch {
+                    path: "d.total".to_string(),
+                    expected: "a number",
+                    found: "string",
+                },
+            ]
+        );
+
+        let content = "{#price($i) = d.rows[$i].price} {$price(0)} {d.name}";
+        let schema = TemplateSchema::from_bytes("letter.html", content.as_bytes())?;
+        assert!(schema.unresolved_aliases);
+
+        let render_options = json!({ "data": { "name": "John", "rows": [{ "price": 1 }] } });
+        assert_eq!(schema.validate_value(&render_options), vec![]);
+
+        let render_options = json!({ "data": { "rows": [] } });
+        assert_eq!(
+            schema.validate_value(&render_options),
+            vec![DataIssue::MissingPath("d.name".to_string())]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_render_data_issues() -> Result<(), CarboneError> {
+        let mut render_options = valid_render_options();
+        let data = &mut render_options["data"];
+        data["customer"].as_object_mut().unwrap().remove("city");
+        data["products"] = json!({ "name": "Pen" });
+        data["date"] = json!({ "day": 1 });
+        data["discount"] = json!(10);
+
+        let json_data = JsonData::new(render_options.to_string())?;
+
+        let issues = validate_render_data(&invoice_template()?, &json_data)?;
+
+        assert_eq!(
+            issues,
+            vec![
+                DataIssue::MissingPath("d.customer.city".to_string()),
+                DataIssue::TypeMismatch {
+                    path: "d.date".to_string(),
+                    expected: "a date",
+                    found: "object"
+                },
+                DataIssue::TypeMismatch {
+                    path: "d.products".to_string(),
+                    expected: "an array",
+                    found: "object"
+                },
+                DataIssue::UnusedKey("d.discount".to_string()),
+            ]
+        );
+        assert_eq!(
+            issues[2].to_string(),
+            "d.products is an object, expected an array"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_render_data_array_items() -> Result<(), CarboneError> {
+        let mut render_options = valid_render_options();
+        render_options["data"]["products"] = json!([
+            { "name": "Pen", "priceUnit": 1, "priceTotal": 2, "quantity": 2 },
+            { "name": "Book", "priceUnit": 10, "priceTotal": 10 }
+        ]);
+
+        let json_data = JsonData::new(render_options.to_string())?;
+
+        let issues = validate_render_data(&invoice_template()?, &json_data)?;
+
+        assert_eq!(
+            issues,
+            vec![DataIssue::MissingPath("d.products[1].quantity".to_string())]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_render_data_not_json() -> Result<(), CarboneError> {
+        let json_data = JsonData::new("not json".to_string())?;
+
+        let result = validate_render_data(&invoice_template()?, &json_data);
+
+        assert!(matches!(
+            result,
+            Err(CarboneError::RequestBodyNotWellFormedJsonError)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_data_strict_validation() -> Result<(), CarboneError> {
+        let mut validator = DataValidator::default();
+        let template_id = validator.add_template(&invoice_template()?, None)?;
+
+        let server = MockServer::start();
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", template_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?.with_strict_validation(validator);
+
+        let mut render_options = valid_render_options();
+        render_options["data"].as_object_mut().unwrap().remove("total");
+        render_options["data"]["unused"] = json!(true);
+        let json_data = JsonData::new(render_options.to_string())?;
+
+        let result = carbone.render_data(template_id.clone(), json_data).await;
+
+        mock_render.assert_hits(0);
+        match result {
+            Err(CarboneError::DataValidation(issues)) => assert_eq!(
+                issues,
+                vec![DataIssue::MissingPath("d.total".to_string())]
+            ),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let json_data = JsonData::new(valid_render_options().to_string())?;
+        let render_id = carbone.render_data(template_id, json_data).await?;
+
+        mock_render.assert();
+        assert_eq!(render_id.as_str(), "render_id.pdf");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_data_strict_validation_unknown_template() -> Result<(), CarboneError> {
+        let validator = DataValidator::default().with_unused_keys_denied();
+
+        let server = MockServer::start();
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST").path("/render/other_template_id");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+
+        let carbone = Carbone::new(&config, None)?.with_strict_validation(validator);
+
+        let template_id = carbone_sdk_rust::template::TemplateId::new("other_template_id")?;
+        let json_data = JsonData::new(r#"{ "data": { "anything": 1 } }"#.to_string())?;
+        carbone.render_data(template_id, json_data).await?;
+
+        mock_render.assert();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use httpmock::prelude::*;
+use serde_json::json;
+
+use carbone_sdk_rust::carbone::Carbone;
+use carbone_sdk_rust::errors::CarboneError;
+use carbone_sdk_rust::template::TemplateId;
+use carbone_sdk_rust::watch::*;
+
+mod helper;
+
+use helper::Helper;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn create_watch_dir(name: &str) -> Result<PathBuf, CarboneError> {
+        let dir = std::env::temp_dir().join(format!("carbone-watch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    #[tokio::test]
+    async fn test_watch() -> Result<(), CarboneError> {
+        let dir = create_watch_dir("async")?;
+        let template_path = dir.join("letter.html");
+        let data_path = dir.join("letter.json");
+        fs::write(&template_path, "<p>{d.name}</p>")?;
+        fs::write(&data_path, r#"{ "data": { "name": "John" }, "convertTo": "pdf" }"#)?;
+
+        let first_id = TemplateId::from_bytes(b"<p>{d.name}</p>".to_vec(), None)?;
+        let second_id = TemplateId::from_bytes(b"<h1>{d.name}</h1>".to_vec(), None)?;
+
+        let server = MockServer::start();
+
+        let mock_render = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", first_id.as_str()));
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "renderId": "render_id.pdf" }
+            }));
+        });
+        let mock_report = server.mock(|when, then| {
+            when.method("GET").path("/render/render_id.pdf");
+            then.status(200).body("first report");
+        });
+        let mock_render_changed = server.mock(|when, then| {
+            when.method("POST")
+                .path(format!("/render/{}", second_id.as_str()));
+            then.status(404).json_body(json!({
+                "success": false,
+                "error": "Template not found"
+            }));
+        });
+        let mock_upload = server.mock(|when, then| {
+            when.method("POST")
+                .path("/template")
+                .body_contains("<h1>{d.name}</h1>");
+            then.status(200).json_body(json!({
+                "success": true,
+                "data": { "templateId": second_id.as_str() }
+            }));
+        });
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(Some(&server))?;
+        let api_token = helper.create_api_token()?;
+
+        let carbone = Carbone::new(&config, Some(&api_token))?;
+
+        let options = WatchOptions {
+            interval: Duration::from_millis(10),
+            ..Default::default()
+        };
+
+        let mut events = Vec::new();
+        watch(&carbone, &template_path, &data_path, &options, |event| {
+            events.push(match event {
+                WatchEvent::Rendered { template_id, output, report, .. } => {
+                    format!("rendered {} {} {:?}", template_id.as_str(), output.display(), report)
+                }
+                WatchEvent::Failed(e) => format!("failed {}", e),
+            });
+
+            if events.len() == 1 {
+                fs::write(&template_path, "<h1>{d.name}</h1>").unwrap();
+                ControlFlow::Continue(())
+            } else {
+                ControlFlow::Break(())
+            }
+        })
+        .await?;
+
+        mock_render.assert();
+        mock_report.assert();
+        mock_upload.assert();
+        mock_render_changed.assert_hits(2);
+
+        let output = dir.join("letter.preview.pdf");
+        assert_eq!(
+            events[0],
+            format!("rendered {} {} b\"first report\"", first_id.as_str(), output.display())
+        );
+        assert!(events[1].starts_with("failed"), "{}", events[1]);
+        assert_eq!(fs::read_to_string(&output)?, "first report");
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_watch_invalid_data() -> Result<(), CarboneError> {
+        let dir = create_watch_dir("invalid")?;
+        let template_path = dir.join("letter.html");
+        let data_path = dir.join("letter.json");
+        fs::write(&template_path, "<p>{d.name}</p>")?;
+        fs::write(&data_path, "{ not json")?;
+
+        let helper = Helper::new();
+        let config = helper.create_config_for_mock_server(None)?;
+
+        let carbone = Carbone::new(&config, None)?;
+
+        let mut error = None;
+        watch(&carbone, &template_path, &data_path, &WatchOptions::default(), |event| {
+            if let WatchEvent::Failed(e) = event {
+                error = Some(e.to_string());
+            }
+            ControlFlow::Break(())
+        })
+        .await?;
+
+        assert!(error.unwrap().contains("letter.json"));
+
+        fs::remove_dir_all(&dir)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_preview_server() -> Result<(), CarboneError> {
+        let preview = PreviewServer::start("127.0.0.1:0")?;
+        let url = preview.url();
+
+        let page = reqwest::blocking::get(&url)?.text()?;
+        assert!(page.contains("Waiting for the first render"));
+        assert_eq!(reqwest::blocking::get(format!("{}report", url))?.status(), 404);
+
+        preview.update(&WatchEvent::Rendered {
+            template_id: TemplateId::new("template_id")?,
+            output: PathBuf::from("letter.preview.pdf"),
+            report: "report content".into(),
+            elapsed: Duration::from_millis(10),
+        });
+
+        assert_eq!(reqwest::blocking::get(format!("{}version", url))?.text()?, "1");
+        let response = reqwest::blocking::get(format!("{}report?v=1", url))?;
+        assert_eq!(response.headers()["content-type"], "application/pdf");
+        assert_eq!(response.text()?, "report content");
+
+        preview.update(&WatchEvent::Failed(CarboneError::Error("<bad> template".to_string())));
+
+        let page = reqwest::blocking::get(&url)?.text()?;
+        assert!(page.contains("&lt;bad&gt; template"));
+        assert!(page.contains("/report?v=2"));
+
+        Ok(())
+    }
+}